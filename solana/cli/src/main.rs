@@ -0,0 +1,695 @@
+use std::path::PathBuf;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use connected::{
+    address, ChainConfig, Config, FinalDestination, NFTInfo, OwnerIndex, PendingTransfer, SupplyIndex,
+    UniversalNFTState, UpdateConfigParams, OWNER_INDEX_PAGE_SIZE, SUPPLY_INDEX_PAGE_SIZE,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::instruction::derive_lookup_table_address;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address;
+
+// Outbound transfers burn, record and CPI into the gateway in one instruction
+const TRANSFER_COMPUTE_UNITS: u32 = 400_000;
+
+/// Operate the Universal NFT program against any RPC endpoint
+#[derive(Parser)]
+#[command(name = "universal-nft-cli", version)]
+struct Cli {
+    /// RPC URL or moniker: localnet, devnet, testnet, mainnet-beta
+    #[arg(short, long, global = true, default_value = "localnet")]
+    url: String,
+
+    /// Keypair paying for and signing transactions [default: ~/.config/solana/id.json]
+    #[arg(short, long, global = true)]
+    keypair: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the program state, config, treasury and outbound queue, with the keypair as authority
+    Initialize,
+    /// Mint a new NFT (authority only)
+    Mint {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long)]
+        uri: String,
+        /// Recipient wallet [default: the keypair]
+        #[arg(long)]
+        to: Option<Pubkey>,
+        /// Token id [default: the next free id]
+        #[arg(long)]
+        token_id: Option<u64>,
+        #[arg(long)]
+        soulbound: bool,
+    },
+    /// Burn an NFT held by the keypair and send it to another chain with SOL gas
+    Burn {
+        token_id: u64,
+        /// Destination chain id, as registered with `set_chain_config`
+        #[arg(long)]
+        chain: u64,
+        /// Receiver on the destination chain: 0x-prefixed hex or a base58 address
+        #[arg(long)]
+        recipient: String,
+        /// Lamports deposited for destination gas [default: the chain's configured deposit]
+        #[arg(long)]
+        gas: Option<u64>,
+    },
+    /// Bridge an NFT held by the keypair through the gateway
+    Transfer {
+        token_id: u64,
+        /// Destination chain id, as registered with `set_chain_config`
+        #[arg(long)]
+        chain: u64,
+        /// Receiver on the destination chain: 0x-prefixed hex or a base58 address
+        #[arg(long)]
+        recipient: String,
+        /// Lamports deposited for destination gas [default: the chain's configured deposit]
+        #[arg(long)]
+        gas: Option<u64>,
+        /// Opaque context carried to the destination, 0x-prefixed hex
+        #[arg(long)]
+        extra_data: Option<String>,
+        /// Chain the universal contract forwards the NFT on to from the destination chain
+        #[arg(long, requires = "final_recipient")]
+        final_chain: Option<u64>,
+        /// Receiver on the final chain: 0x-prefixed hex or a base58 address
+        #[arg(long, requires = "final_chain")]
+        final_recipient: Option<String>,
+        /// Units to send of a semi-fungible token [default: 1]
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Print an NFT's record and any in-flight transfer
+    Inspect { token_id: u64 },
+    /// Print the program config, updating any field given (authority only)
+    Config(ConfigArgs),
+    /// Create or extend the address lookup table of static program accounts (authority only)
+    LookupTable,
+}
+
+#[derive(Args)]
+struct ConfigArgs {
+    #[arg(long)]
+    gateway_program: Option<Pubkey>,
+    #[arg(long)]
+    bridge_fee_lamports: Option<u64>,
+    #[arg(long)]
+    mint_fee_lamports: Option<u64>,
+    #[arg(long)]
+    paused: Option<bool>,
+    #[arg(long)]
+    self_chain_id: Option<u64>,
+    /// Universal contract on ZetaChain, 0x-prefixed hex
+    #[arg(long)]
+    universal_contract: Option<String>,
+    #[arg(long)]
+    max_transfers_per_epoch: Option<u32>,
+    /// Log verbose diagnostics from outbound transfers
+    #[arg(long)]
+    debug_logs: Option<bool>,
+    /// Forward deposits arriving with inbound calls to the treasury instead of the receiver
+    #[arg(long)]
+    forward_deposits_to_treasury: Option<bool>,
+    /// Programs allowed to transfer through CPI, comma-separated; replaces the current list
+    #[arg(long, value_delimiter = ',')]
+    allowed_callers: Option<Vec<Pubkey>>,
+}
+
+impl ConfigArgs {
+    fn into_params(self) -> Result<Option<UpdateConfigParams>> {
+        let universal_contract = self
+            .universal_contract
+            .map(|contract| parse_evm_address(&contract))
+            .transpose()?;
+        let params = UpdateConfigParams {
+            gateway_program: self.gateway_program,
+            bridge_fee_lamports: self.bridge_fee_lamports,
+            mint_fee_lamports: self.mint_fee_lamports,
+            paused: self.paused,
+            self_chain_id: self.self_chain_id,
+            universal_contract,
+            max_transfers_per_epoch: self.max_transfers_per_epoch,
+            debug_logs: self.debug_logs,
+            forward_deposits_to_treasury: self.forward_deposits_to_treasury,
+            allowed_callers: self.allowed_callers,
+            ..Default::default()
+        };
+        let any_set = params.gateway_program.is_some()
+            || params.bridge_fee_lamports.is_some()
+            || params.mint_fee_lamports.is_some()
+            || params.paused.is_some()
+            || params.self_chain_id.is_some()
+            || params.universal_contract.is_some()
+            || params.max_transfers_per_epoch.is_some()
+            || params.debug_logs.is_some()
+            || params.forward_deposits_to_treasury.is_some()
+            || params.allowed_callers.is_some();
+        Ok(any_set.then_some(params))
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new_with_commitment(rpc_url(&cli.url), CommitmentConfig::confirmed());
+    let keypair_path = match cli.keypair {
+        Some(path) => path,
+        None => default_keypair_path()?,
+    };
+    let load_keypair = || {
+        read_keypair_file(&keypair_path)
+            .map_err(|err| anyhow!("failed to read keypair {}: {}", keypair_path.display(), err))
+    };
+
+    match cli.command {
+        Command::Initialize => initialize(&rpc, &load_keypair()?),
+        Command::Mint { name, symbol, uri, to, token_id, soulbound } => {
+            mint(&rpc, &load_keypair()?, name, symbol, uri, to, token_id, soulbound)
+        }
+        Command::Burn { token_id, chain, recipient, gas } => {
+            burn(&rpc, &load_keypair()?, token_id, chain, &recipient, gas)
+        }
+        Command::Transfer {
+            token_id,
+            chain,
+            recipient,
+            gas,
+            extra_data,
+            final_chain,
+            final_recipient,
+            amount,
+        } => {
+            let extra_data = extra_data
+                .map(|data| hex::decode(data.trim_start_matches("0x")))
+                .transpose()
+                .context("extra data must be hex")?;
+            let final_destination = match (final_chain, final_recipient) {
+                (Some(chain_id), Some(receiver)) => {
+                    Some(FinalDestination { chain_id, receiver: parse_recipient(&receiver)? })
+                }
+                _ => None,
+            };
+            transfer(
+                &rpc,
+                &load_keypair()?,
+                token_id,
+                chain,
+                &recipient,
+                gas,
+                extra_data,
+                final_destination,
+                amount,
+            )
+        }
+        Command::Inspect { token_id } => inspect(&rpc, token_id),
+        Command::Config(args) => match args.into_params()? {
+            Some(params) => update_config(&rpc, &load_keypair()?, params),
+            None => show_config(&rpc),
+        },
+        Command::LookupTable => sync_lookup_table(&rpc, &load_keypair()?),
+    }
+}
+
+fn initialize(rpc: &RpcClient, payer: &Keypair) -> Result<()> {
+    let ix = connected_ix(
+        connected::accounts::Initialize {
+            payer: payer.pubkey(),
+            authority: payer.pubkey(),
+            universal_nft_state: address::universal_nft_state(),
+            pda: address::program_authority(),
+            config: address::config(),
+            treasury: address::treasury(),
+            outbound_queue: Pubkey::find_program_address(&[b"outbound_queue"], &connected::ID).0,
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::Initialize {},
+    );
+    let signature = send(rpc, payer, vec![ix])?;
+    println!("Initialized with authority {}: {}", payer.pubkey(), signature);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn mint(
+    rpc: &RpcClient,
+    payer: &Keypair,
+    name: String,
+    symbol: String,
+    uri: String,
+    to: Option<Pubkey>,
+    token_id: Option<u64>,
+    soulbound: bool,
+) -> Result<()> {
+    let to = to.unwrap_or_else(|| payer.pubkey());
+    let explicit_token_id = token_id;
+    let token_id = match token_id {
+        Some(token_id) => token_id,
+        None => fetch::<UniversalNFTState>(rpc, &address::universal_nft_state())?.next_token_id,
+    };
+    let mint = address::nft_mint(token_id);
+    let mut instructions = Vec::new();
+
+    // Mints land on the first index pages with room, created on demand
+    let owner_page = free_owner_page(rpc, &to)?;
+    if !owner_page.exists {
+        instructions.push(connected_ix(
+            connected::accounts::InitOwnerIndex {
+                payer: payer.pubkey(),
+                owner_index: owner_page.address,
+                system_program: solana_sdk::system_program::ID,
+            },
+            connected::instruction::InitOwnerIndex { owner: to, page: owner_page.page },
+        ));
+    }
+    let supply_page = free_supply_page(rpc)?;
+    if !supply_page.exists {
+        instructions.push(connected_ix(
+            connected::accounts::InitSupplyIndex {
+                payer: payer.pubkey(),
+                supply_index: supply_page.address,
+                system_program: solana_sdk::system_program::ID,
+            },
+            connected::instruction::InitSupplyIndex { page: supply_page.page },
+        ));
+    }
+
+    let accounts = connected::accounts::MintNFT {
+        payer: payer.pubkey(),
+        authority: payer.pubkey(),
+        universal_nft_state: address::universal_nft_state(),
+        pda: address::program_authority(),
+        config: address::config(),
+        treasury: address::treasury(),
+        mint,
+        recipient: to,
+        token_account: get_associated_token_address(&to, &mint),
+        nft_info: address::nft_info(token_id),
+        metadata: mpl_token_metadata::accounts::Metadata::find_pda(&mint).0,
+        owner_index: owner_page.address,
+        mint_index: address::mint_index(&mint),
+        supply_index: supply_page.address,
+        collection: None,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        metadata_program: mpl_token_metadata::ID,
+        system_program: solana_sdk::system_program::ID,
+        rent: solana_sdk::sysvar::rent::ID,
+    };
+    // mint_nft_auto takes the same accounts, derived from the next token id read above; if
+    // another mint takes that id first the transaction fails instead of reusing it
+    let data = match explicit_token_id {
+        Some(token_id) => connected::instruction::MintNft { token_id, name, symbol, uri, to, soulbound }.data(),
+        None => connected::instruction::MintNftAuto { name, symbol, uri, to, soulbound }.data(),
+    };
+    instructions.push(Instruction {
+        program_id: connected::ID,
+        accounts: accounts.to_account_metas(None),
+        data,
+    });
+    let signature = send(rpc, payer, instructions)?;
+    println!("Minted token {} (mint {}) to {}: {}", token_id, mint, to, signature);
+    Ok(())
+}
+
+fn burn(
+    rpc: &RpcClient,
+    signer: &Keypair,
+    token_id: u64,
+    destination_chain_id: u64,
+    recipient: &str,
+    gas: Option<u64>,
+) -> Result<()> {
+    let destination_receiver = parse_recipient(recipient)?;
+    let outbound =
+        outbound_accounts(rpc, &signer.pubkey(), token_id, destination_chain_id, &destination_receiver)?;
+    let ix = connected_ix(
+        outbound.accounts,
+        connected::instruction::BurnNft {
+            token_id,
+            destination_receiver,
+            destination_chain_id,
+            gas_amount: gas.unwrap_or(outbound.chain_config.gas_deposit_lamports),
+        },
+    );
+    let signature = send(
+        rpc,
+        signer,
+        vec![ComputeBudgetInstruction::set_compute_unit_limit(TRANSFER_COMPUTE_UNITS), ix],
+    )?;
+    println!("Burned token {} and sent it to chain {}: {}", token_id, destination_chain_id, signature);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn transfer(
+    rpc: &RpcClient,
+    signer: &Keypair,
+    token_id: u64,
+    destination_chain_id: u64,
+    recipient: &str,
+    gas: Option<u64>,
+    extra_data: Option<Vec<u8>>,
+    final_destination: Option<FinalDestination>,
+    amount: Option<u64>,
+) -> Result<()> {
+    let recipient_address = parse_recipient(recipient)?;
+    let outbound =
+        outbound_accounts(rpc, &signer.pubkey(), token_id, destination_chain_id, &recipient_address)?;
+    let ix = connected_ix(
+        outbound.accounts,
+        connected::instruction::TransferCrossChain {
+            token_id,
+            recipient_address,
+            destination_chain_id,
+            metadata_uri: outbound.nft_info.uri,
+            on_revert_gas_limit: None,
+            revert_params: None,
+            gas_amount: Some(gas.unwrap_or(outbound.chain_config.gas_deposit_lamports)),
+            recipient_checksum: None,
+            extra_data,
+            final_destination,
+            amount,
+        },
+    );
+    let signature = send(
+        rpc,
+        signer,
+        vec![ComputeBudgetInstruction::set_compute_unit_limit(TRANSFER_COMPUTE_UNITS), ix],
+    )?;
+    println!("Sent token {} to chain {}: {}", token_id, destination_chain_id, signature);
+    Ok(())
+}
+
+// Accounts of an outbound transfer by the NFT's owner, with the records they were derived from
+struct OutboundAccounts {
+    accounts: connected::accounts::TransferCrossChain,
+    nft_info: NFTInfo,
+    chain_config: ChainConfig,
+}
+
+fn outbound_accounts(
+    rpc: &RpcClient,
+    owner: &Pubkey,
+    token_id: u64,
+    destination_chain_id: u64,
+    recipient: &[u8],
+) -> Result<OutboundAccounts> {
+    let owner = *owner;
+    let nft_info: NFTInfo = fetch(rpc, &address::nft_info(token_id))?;
+    let config: Config = fetch(rpc, &address::config())?;
+    let chain_config: ChainConfig = fetch(rpc, &address::chain_config(destination_chain_id))
+        .with_context(|| format!("chain {} is not registered", destination_chain_id))?;
+
+    let mint = nft_info.mint;
+    let gateway_pda = Pubkey::find_program_address(&[b"meta"], &config.gateway_program).0;
+    let collection_address = match nft_info.collection {
+        Some(collection) => {
+            let address = Pubkey::find_program_address(
+                &[b"collection_address", collection.as_ref(), &destination_chain_id.to_le_bytes()],
+                &connected::ID,
+            )
+            .0;
+            account_exists(rpc, &address)?.then_some(address)
+        }
+        None => None,
+    };
+    let attributes = address::attributes(token_id);
+    let transfer_id = connected::outbound_transfer_id(token_id, destination_chain_id, nft_info.transfer_nonce);
+
+    let accounts = connected::accounts::TransferCrossChain {
+        signer: owner,
+        universal_nft_state: address::universal_nft_state(),
+        nft_info: address::nft_info(token_id),
+        token_account: get_associated_token_address(&owner, &mint),
+        mint,
+        owner_index: owner_page_holding(rpc, &owner, token_id)?,
+        supply_index: supply_page_holding(rpc, token_id)?,
+        instruction_sysvar: solana_sdk::sysvar::instructions::ID,
+        config: address::config(),
+        treasury: address::treasury(),
+        chain_config: address::chain_config(destination_chain_id),
+        chain_stats: address::chain_stats(destination_chain_id),
+        pending_transfer: address::pending_transfer(&transfer_id),
+        receipt: address::receipt(&owner, &transfer_id),
+        transfer_record: address::transfer_record(&transfer_id),
+        rate_limit: address::rate_limit(&owner),
+        owner_blocklist_entry: address::blocklist_entry(owner.as_ref()),
+        recipient_blocklist_entry: address::blocklist_entry(recipient),
+        collection_authority: None,
+        pda: None,
+        collection: nft_info.collection,
+        collection_address,
+        collection_config: nft_info.collection.as_ref().map(address::collection_config),
+        attributes: account_exists(rpc, &attributes)?.then_some(attributes),
+        gateway_pda,
+        gateway_program: config.gateway_program,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        system_program: solana_sdk::system_program::ID,
+        event_authority: address::event_authority(),
+        program: connected::ID,
+    };
+    Ok(OutboundAccounts { accounts, nft_info, chain_config })
+}
+
+fn inspect(rpc: &RpcClient, token_id: u64) -> Result<()> {
+    let nft_info: NFTInfo = fetch(rpc, &address::nft_info(token_id))
+        .with_context(|| format!("token {} not found", token_id))?;
+    println!("token_id:      {}", nft_info.token_id);
+    println!("name:          {}", nft_info.name);
+    println!("symbol:        {}", nft_info.symbol);
+    println!("uri:           {}", nft_info.uri);
+    println!("owner:         {}", nft_info.owner);
+    println!("mint:          {}", nft_info.mint);
+    println!("burned:        {}", nft_info.is_burned);
+    println!("soulbound:     {}", nft_info.soulbound);
+    if let Some(collection) = nft_info.collection {
+        println!("collection:    {}", collection);
+    }
+    if let Some(universal_id) = nft_info.universal_id {
+        println!("universal_id:  0x{}", hex::encode(universal_id));
+    }
+    let Some(data) = nft_info.cross_chain_data else {
+        return Ok(());
+    };
+    println!("last bridged:  chain {} to 0x{}", data.destination_chain_id, hex::encode(data.recipient_address));
+
+    // The latest outbound transfer is the one that may still be in flight
+    let pending_transfer = address::pending_transfer(&data.transfer_id);
+    if account_exists(rpc, &pending_transfer)? {
+        let pending: PendingTransfer = fetch(rpc, &pending_transfer)?;
+        println!("pending:       {:?} to chain {}", pending.status, pending.destination_chain_id);
+        println!("transfer_id:   0x{}", hex::encode(pending.transfer_id));
+        println!("dispatched:    {}", pending.dispatched);
+        println!("initiated:     slot {}", pending.initiated_slot);
+    }
+    Ok(())
+}
+
+fn show_config(rpc: &RpcClient) -> Result<()> {
+    let state: UniversalNFTState = fetch(rpc, &address::universal_nft_state())?;
+    let config: Config = fetch(rpc, &address::config())?;
+    println!("program:             {}", connected::ID);
+    println!("authority:           {}", state.authority);
+    println!("total_supply:        {}", state.total_supply);
+    println!("next_token_id:       {}", state.next_token_id);
+    if let Some(max_supply) = state.max_supply {
+        println!("max_supply:          {}", max_supply);
+    }
+    println!("gateway_program:     {}", config.gateway_program);
+    println!("self_chain_id:       {}", config.self_chain_id);
+    println!("universal_contract:  0x{}", hex::encode(config.universal_contract));
+    println!("paused:              {}", config.paused);
+    println!("bridge_fee_lamports: {}", config.bridge_fee_lamports);
+    println!("mint_fee_lamports:   {}", config.mint_fee_lamports);
+    println!("max_uri_len:         {}", config.max_uri_len);
+    println!("max_transfers/epoch: {}", config.max_transfers_per_epoch);
+    println!("wormhole_program:    {}", config.wormhole_program);
+    println!("layerzero_endpoint:  {}", config.layerzero_endpoint);
+    println!("debug_logs:          {}", config.debug_logs);
+    println!("lookup_table:        {}", config.lookup_table);
+    println!("treasury_deposits:   {}", config.forward_deposits_to_treasury);
+    for caller in config.allowed_callers.iter().filter(|caller| **caller != Pubkey::default()) {
+        println!("allowed_caller:      {}", caller);
+    }
+    Ok(())
+}
+
+fn update_config(rpc: &RpcClient, authority: &Keypair, params: UpdateConfigParams) -> Result<()> {
+    let ix = connected_ix(
+        connected::accounts::UpdateConfig {
+            authority: authority.pubkey(),
+            universal_nft_state: address::universal_nft_state(),
+            config: address::config(),
+        },
+        connected::instruction::UpdateConfig { params },
+    );
+    let signature = send(rpc, authority, vec![ix])?;
+    println!("Updated config: {}", signature);
+    show_config(rpc)
+}
+
+fn sync_lookup_table(rpc: &RpcClient, authority: &Keypair) -> Result<()> {
+    let config: Config = fetch(rpc, &address::config())?;
+    // Creation needs a slot still in the SlotHashes sysvar; a just-finalized one is
+    let recent_slot = rpc.get_slot_with_commitment(CommitmentConfig::finalized())?;
+    let lookup_table = if config.lookup_table == Pubkey::default() {
+        derive_lookup_table_address(&address::program_authority(), recent_slot).0
+    } else {
+        config.lookup_table
+    };
+    let ix = connected_ix(
+        connected::accounts::SyncLookupTable {
+            authority: authority.pubkey(),
+            universal_nft_state: address::universal_nft_state(),
+            config: address::config(),
+            pda: address::program_authority(),
+            lookup_table,
+            address_lookup_table_program: solana_sdk::address_lookup_table::program::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::SyncLookupTable { recent_slot },
+    );
+    let signature = send(rpc, authority, vec![ix])?;
+    println!("Synced lookup table {}: {}", lookup_table, signature);
+    Ok(())
+}
+
+fn connected_ix(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: connected::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+fn send(rpc: &RpcClient, payer: &Keypair, instructions: Vec<Instruction>) -> Result<Signature> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let transaction =
+        Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[payer], blockhash);
+    Ok(rpc.send_and_confirm_transaction(&transaction)?)
+}
+
+fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let data = rpc
+        .get_account_data(address)
+        .with_context(|| format!("failed to fetch account {}", address))?;
+    Ok(T::try_deserialize(&mut data.as_slice())?)
+}
+
+fn account_exists(rpc: &RpcClient, address: &Pubkey) -> Result<bool> {
+    Ok(rpc.get_account_with_commitment(address, rpc.commitment())?.value.is_some())
+}
+
+// An index page chosen for a new token; `exists` is false when it must be created first
+struct IndexPage {
+    page: u32,
+    address: Pubkey,
+    exists: bool,
+}
+
+fn free_owner_page(rpc: &RpcClient, owner: &Pubkey) -> Result<IndexPage> {
+    for page in 0.. {
+        let address = address::owner_index(owner, page);
+        if !account_exists(rpc, &address)? {
+            return Ok(IndexPage { page, address, exists: false });
+        }
+        let index: OwnerIndex = fetch(rpc, &address)?;
+        if index.token_ids.len() < OWNER_INDEX_PAGE_SIZE {
+            return Ok(IndexPage { page, address, exists: true });
+        }
+    }
+    unreachable!()
+}
+
+fn free_supply_page(rpc: &RpcClient) -> Result<IndexPage> {
+    for page in 0.. {
+        let address = address::supply_index(page);
+        if !account_exists(rpc, &address)? {
+            return Ok(IndexPage { page, address, exists: false });
+        }
+        let index: SupplyIndex = fetch(rpc, &address)?;
+        if index.token_ids.len() < SUPPLY_INDEX_PAGE_SIZE {
+            return Ok(IndexPage { page, address, exists: true });
+        }
+    }
+    unreachable!()
+}
+
+fn owner_page_holding(rpc: &RpcClient, owner: &Pubkey, token_id: u64) -> Result<Pubkey> {
+    for page in 0.. {
+        let address = address::owner_index(owner, page);
+        if !account_exists(rpc, &address)? {
+            break;
+        }
+        let index: OwnerIndex = fetch(rpc, &address)?;
+        if index.token_ids.contains(&token_id) {
+            return Ok(address);
+        }
+    }
+    bail!("token {} is not in any index page of {}", token_id, owner)
+}
+
+fn supply_page_holding(rpc: &RpcClient, token_id: u64) -> Result<Pubkey> {
+    for page in 0.. {
+        let address = address::supply_index(page);
+        if !account_exists(rpc, &address)? {
+            break;
+        }
+        let index: SupplyIndex = fetch(rpc, &address)?;
+        if index.token_ids.contains(&token_id) {
+            return Ok(address);
+        }
+    }
+    bail!("token {} is not in any supply index page", token_id)
+}
+
+fn parse_recipient(recipient: &str) -> Result<Vec<u8>> {
+    match recipient.strip_prefix("0x") {
+        Some(hex_address) => Ok(hex::decode(hex_address).context("recipient is not valid hex")?),
+        None => Ok(recipient
+            .parse::<Pubkey>()
+            .map_err(|_| anyhow!("recipient must be 0x-prefixed hex or a base58 address"))?
+            .to_bytes()
+            .to_vec()),
+    }
+}
+
+fn parse_evm_address(address: &str) -> Result<[u8; 20]> {
+    let bytes = hex::decode(address.trim_start_matches("0x")).context("address is not valid hex")?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("address must be 20 bytes"))
+}
+
+fn rpc_url(url: &str) -> String {
+    match url {
+        "localnet" | "l" => "http://127.0.0.1:8899",
+        "devnet" | "d" => "https://api.devnet.solana.com",
+        "testnet" | "t" => "https://api.testnet.solana.com",
+        "mainnet-beta" | "m" => "https://api.mainnet-beta.solana.com",
+        url => url,
+    }
+    .to_string()
+}
+
+fn default_keypair_path() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").ok_or_else(|| anyhow!("HOME is not set; pass --keypair"))?;
+    Ok(PathBuf::from(home).join(".config/solana/id.json"))
+}
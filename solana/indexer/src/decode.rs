@@ -0,0 +1,174 @@
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use connected::events::{parse_event, ProgramEvent};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction, UiLoadedAddresses,
+};
+
+/// One program event, flattened for storage
+///
+/// Every event is stored with its kind, sequence number and raw Borsh payload. The columns
+/// marketplaces query on are filled for the mint, burn, bridge, receive and revert events.
+#[derive(Default)]
+pub struct IndexedEvent {
+    pub kind: &'static str,
+    pub seq: u64,
+    pub token_id: Option<u64>,
+    /// Holder the event is about: minter, burner, bridge sender or receiver
+    pub owner: Option<String>,
+    /// Other side of the event: previous holder, or the address on the remote chain
+    pub counterparty: Option<String>,
+    /// Remote chain of a bridge event
+    pub chain: Option<String>,
+    pub transfer_id: Option<String>,
+    pub uri: Option<String>,
+    /// Borsh payload following the discriminator
+    pub data: Vec<u8>,
+}
+
+/// Decode an event from its discriminator-prefixed bytes
+pub fn decode_event(bytes: &[u8]) -> Option<IndexedEvent> {
+    let parsed = parse_event(bytes)?;
+    let mut event = IndexedEvent {
+        kind: parsed.name(),
+        seq: parsed.seq(),
+        data: bytes.get(8..)?.to_vec(),
+        ..Default::default()
+    };
+
+    match parsed {
+        ProgramEvent::NFTMinted(minted) => {
+            event.token_id = Some(minted.token_id);
+            event.owner = Some(minted.owner.to_string());
+            event.uri = Some(minted.uri);
+        }
+        ProgramEvent::NFTBurned(burned) => {
+            event.token_id = Some(burned.token_id);
+            event.owner = Some(burned.owner.to_string());
+            event.counterparty = Some(burned.destination_receiver).filter(|receiver| !receiver.is_empty());
+            event.chain = Some(burned.destination_chain).filter(|chain| !chain.is_empty());
+            event.uri = Some(burned.uri);
+        }
+        ProgramEvent::NFTTransferred(transferred) => {
+            event.token_id = Some(transferred.token_id);
+            event.owner = Some(transferred.to.to_string());
+            event.counterparty = Some(transferred.from.to_string());
+        }
+        ProgramEvent::CrossChainTransferEvent(bridged) => {
+            event.token_id = Some(bridged.token_id);
+            event.owner = Some(bridged.sender.to_string());
+            // A multi-hop transfer is recorded under its final chain and receiver
+            match bridged.final_destination_chain {
+                Some(final_chain) => {
+                    event.counterparty = Some(format!("0x{}", hex::encode(bridged.final_receiver)));
+                    event.chain = Some(format!("Chain-{}", final_chain));
+                }
+                None => {
+                    event.counterparty = Some(format!("0x{}", hex::encode(bridged.receiver)));
+                    event.chain = Some(bridged.to_chain);
+                }
+            }
+            event.transfer_id = Some(hex::encode(bridged.transfer_id));
+        }
+        ProgramEvent::NFTReceived(received) => {
+            event.token_id = Some(received.token_id);
+            event.owner = Some(received.owner.to_string());
+            event.chain = Some(received.from_chain);
+            event.uri = Some(received.uri);
+        }
+        ProgramEvent::CrossChainTransferReceived(received) => {
+            event.token_id = Some(received.token_id);
+            event.owner = Some(received.receiver.to_string());
+            event.counterparty = Some(format!("0x{}", hex::encode(received.sender)));
+            event.transfer_id = Some(hex::encode(received.transfer_id));
+            event.uri = Some(received.uri);
+        }
+        ProgramEvent::CrossChainTransferReverted(reverted) => {
+            event.token_id = Some(reverted.token_id);
+            event.owner = Some(reverted.original_sender.to_string());
+            event.transfer_id = Some(hex::encode(reverted.transfer_id));
+        }
+        _ => {}
+    }
+    Some(event)
+}
+
+/// Collect the program's events from a confirmed transaction, in emission order
+///
+/// `emit!` events are read from `Program data:` log lines logged while the program is on top
+/// of the invoke stack; `emit_cpi!` events from self-invocations carrying the event tag.
+pub fn transaction_events(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<IndexedEvent> {
+    let Some(meta) = &transaction.transaction.meta else {
+        return Vec::new();
+    };
+    if meta.err.is_some() {
+        return Vec::new();
+    }
+    let program_id = connected::ID.to_string();
+    let mut events = Vec::new();
+
+    let logs: Option<Vec<String>> = meta.log_messages.clone().into();
+    let mut invoke_stack: Vec<&str> = Vec::new();
+    for line in logs.iter().flatten() {
+        if let Some(rest) = line.strip_prefix("Program data: ") {
+            if invoke_stack.last() == Some(&program_id.as_str()) {
+                if let Some(event) = BASE64_STANDARD.decode(rest).ok().and_then(|bytes| decode_event(&bytes)) {
+                    events.push(event);
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => invoke_stack.push(program),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    invoke_stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let account_keys = account_keys(transaction);
+    let inner_instructions: Option<Vec<UiInnerInstructions>> = meta.inner_instructions.clone().into();
+    for instruction in inner_instructions.iter().flatten().flat_map(|inner| &inner.instructions) {
+        let UiInstruction::Compiled(instruction) = instruction else {
+            continue;
+        };
+        if account_keys.get(instruction.program_id_index as usize) != Some(&connected::ID) {
+            continue;
+        }
+        let Ok(data) = bs58::decode(&instruction.data).into_vec() else {
+            continue;
+        };
+        if let Some(event) = data.strip_prefix(EVENT_IX_TAG_LE).and_then(decode_event) {
+            events.push(event);
+        }
+    }
+
+    events.sort_by_key(|event| event.seq);
+    events
+}
+
+// Static keys followed by the keys loaded from lookup tables, as compiled instructions index them
+fn account_keys(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<Pubkey> {
+    let Some(decoded) = transaction.transaction.transaction.decode() else {
+        return Vec::new();
+    };
+    let mut keys = decoded.message.static_account_keys().to_vec();
+    let loaded: Option<UiLoadedAddresses> = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.loaded_addresses.clone().into());
+    if let Some(loaded) = loaded {
+        keys.extend(
+            loaded
+                .writable
+                .iter()
+                .chain(&loaded.readonly)
+                .filter_map(|key| key.parse::<Pubkey>().ok()),
+        );
+    }
+    keys
+}
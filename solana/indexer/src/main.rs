@@ -0,0 +1,102 @@
+mod decode;
+mod sink;
+
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Parser;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::sink::EventSink;
+
+// Largest page `getSignaturesForAddress` returns
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+
+/// Index Universal NFT program events into Postgres or SQLite
+///
+/// Transactions are indexed oldest first and the cursor is committed with each transaction's
+/// events, so a restarted indexer resumes after the last stored transaction.
+#[derive(Parser)]
+#[command(name = "universal-nft-indexer", version)]
+struct Cli {
+    /// RPC URL to poll
+    #[arg(short, long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// `postgres://` connection URL, or a SQLite database path
+    #[arg(short, long, default_value = "universal-nft-events.sqlite")]
+    database: String,
+
+    /// Seconds to wait between polls once caught up
+    #[arg(long, default_value_t = 5)]
+    poll_interval: u64,
+
+    /// Index what is new and exit instead of polling
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed());
+    let mut sink = sink::open(&cli.database)?;
+
+    loop {
+        let indexed = index_new_transactions(&rpc, sink.as_mut())?;
+        if indexed > 0 {
+            println!("indexed {} transactions", indexed);
+        }
+        if cli.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(cli.poll_interval));
+    }
+}
+
+// Index every program transaction after the cursor, returning how many were indexed
+fn index_new_transactions(rpc: &RpcClient, sink: &mut dyn EventSink) -> Result<usize> {
+    let until = sink.cursor()?.map(|signature| Signature::from_str(&signature)).transpose()?;
+
+    // Signatures come newest first; page back to the cursor, then index oldest first
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc.get_signatures_for_address_with_config(
+            &connected::ID,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(SIGNATURE_PAGE_LIMIT),
+                commitment: Some(rpc.commitment()),
+            },
+        )?;
+        let full = page.len() == SIGNATURE_PAGE_LIMIT;
+        before = page.last().map(|status| Signature::from_str(&status.signature)).transpose()?;
+        signatures.extend(page);
+        if !full {
+            break;
+        }
+    }
+
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(rpc.commitment()),
+        max_supported_transaction_version: Some(0),
+    };
+    for status in signatures.iter().rev() {
+        let signature = Signature::from_str(&status.signature)?;
+        // Failed transactions emit nothing but still advance the cursor
+        let events = match status.err {
+            Some(_) => Vec::new(),
+            None => decode::transaction_events(&rpc.get_transaction_with_config(&signature, config)?),
+        };
+        sink.commit(&status.signature, status.slot, status.block_time, &events)?;
+    }
+    Ok(signatures.len())
+}
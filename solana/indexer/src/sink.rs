@@ -0,0 +1,183 @@
+use anyhow::Result;
+
+use crate::decode::IndexedEvent;
+
+/// Storage for indexed events and the cursor of the last indexed transaction
+pub trait EventSink {
+    /// Signature of the last committed transaction, if any
+    fn cursor(&mut self) -> Result<Option<String>>;
+
+    /// Store a transaction's events and advance the cursor past it, atomically
+    fn commit(&mut self, signature: &str, slot: u64, block_time: Option<i64>, events: &[IndexedEvent]) -> Result<()>;
+}
+
+/// Open the sink for `database`: a `postgres://` URL, or a SQLite file path
+pub fn open(database: &str) -> Result<Box<dyn EventSink>> {
+    if database.starts_with("postgres://") || database.starts_with("postgresql://") {
+        Ok(Box::new(PostgresSink::connect(database)?))
+    } else {
+        Ok(Box::new(SqliteSink::open(database)?))
+    }
+}
+
+// Cursor key; one row per indexed program
+const CURSOR_KEY: &str = "connected";
+
+pub struct SqliteSink {
+    connection: rusqlite::Connection,
+}
+
+impl SqliteSink {
+    pub fn open(path: &str) -> Result<Self> {
+        let connection = rusqlite::Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS events (
+                signature TEXT NOT NULL,
+                event_index INTEGER NOT NULL,
+                slot INTEGER NOT NULL,
+                block_time INTEGER,
+                kind TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                token_id INTEGER,
+                owner TEXT,
+                counterparty TEXT,
+                chain TEXT,
+                transfer_id TEXT,
+                uri TEXT,
+                data BLOB NOT NULL,
+                PRIMARY KEY (signature, event_index)
+            );
+            CREATE INDEX IF NOT EXISTS events_token_id ON events (token_id, seq);
+            CREATE INDEX IF NOT EXISTS events_owner ON events (owner, seq);
+            CREATE TABLE IF NOT EXISTS cursors (
+                name TEXT PRIMARY KEY,
+                signature TEXT NOT NULL,
+                slot INTEGER NOT NULL
+            );",
+        )?;
+        Ok(Self { connection })
+    }
+}
+
+impl EventSink for SqliteSink {
+    fn cursor(&mut self) -> Result<Option<String>> {
+        let mut statement = self.connection.prepare("SELECT signature FROM cursors WHERE name = ?1")?;
+        let mut rows = statement.query([CURSOR_KEY])?;
+        Ok(match rows.next()? {
+            Some(row) => Some(row.get(0)?),
+            None => None,
+        })
+    }
+
+    fn commit(&mut self, signature: &str, slot: u64, block_time: Option<i64>, events: &[IndexedEvent]) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        for (index, event) in events.iter().enumerate() {
+            transaction.execute(
+                "INSERT OR IGNORE INTO events (signature, event_index, slot, block_time, kind, seq, token_id, owner,
+                    counterparty, chain, transfer_id, uri, data)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                rusqlite::params![
+                    signature,
+                    index as i64,
+                    slot as i64,
+                    block_time,
+                    event.kind,
+                    event.seq as i64,
+                    event.token_id.map(|token_id| token_id as i64),
+                    event.owner,
+                    event.counterparty,
+                    event.chain,
+                    event.transfer_id,
+                    event.uri,
+                    event.data,
+                ],
+            )?;
+        }
+        transaction.execute(
+            "INSERT INTO cursors (name, signature, slot) VALUES (?1, ?2, ?3)
+            ON CONFLICT (name) DO UPDATE SET signature = excluded.signature, slot = excluded.slot",
+            rusqlite::params![CURSOR_KEY, signature, slot as i64],
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+pub struct PostgresSink {
+    client: postgres::Client,
+}
+
+impl PostgresSink {
+    pub fn connect(url: &str) -> Result<Self> {
+        let mut client = postgres::Client::connect(url, postgres::NoTls)?;
+        client.batch_execute(
+            "CREATE TABLE IF NOT EXISTS events (
+                signature TEXT NOT NULL,
+                event_index INTEGER NOT NULL,
+                slot BIGINT NOT NULL,
+                block_time BIGINT,
+                kind TEXT NOT NULL,
+                seq BIGINT NOT NULL,
+                token_id BIGINT,
+                owner TEXT,
+                counterparty TEXT,
+                chain TEXT,
+                transfer_id TEXT,
+                uri TEXT,
+                data BYTEA NOT NULL,
+                PRIMARY KEY (signature, event_index)
+            );
+            CREATE INDEX IF NOT EXISTS events_token_id ON events (token_id, seq);
+            CREATE INDEX IF NOT EXISTS events_owner ON events (owner, seq);
+            CREATE TABLE IF NOT EXISTS cursors (
+                name TEXT PRIMARY KEY,
+                signature TEXT NOT NULL,
+                slot BIGINT NOT NULL
+            );",
+        )?;
+        Ok(Self { client })
+    }
+}
+
+impl EventSink for PostgresSink {
+    fn cursor(&mut self) -> Result<Option<String>> {
+        let row = self
+            .client
+            .query_opt("SELECT signature FROM cursors WHERE name = $1", &[&CURSOR_KEY])?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    fn commit(&mut self, signature: &str, slot: u64, block_time: Option<i64>, events: &[IndexedEvent]) -> Result<()> {
+        let mut transaction = self.client.transaction()?;
+        for (index, event) in events.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO events (signature, event_index, slot, block_time, kind, seq, token_id, owner,
+                    counterparty, chain, transfer_id, uri, data)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                ON CONFLICT DO NOTHING",
+                &[
+                    &signature,
+                    &(index as i32),
+                    &(slot as i64),
+                    &block_time,
+                    &event.kind,
+                    &(event.seq as i64),
+                    &event.token_id.map(|token_id| token_id as i64),
+                    &event.owner,
+                    &event.counterparty,
+                    &event.chain,
+                    &event.transfer_id,
+                    &event.uri,
+                    &event.data,
+                ],
+            )?;
+        }
+        transaction.execute(
+            "INSERT INTO cursors (name, signature, slot) VALUES ($1, $2, $3)
+            ON CONFLICT (name) DO UPDATE SET signature = excluded.signature, slot = excluded.slot",
+            &[&CURSOR_KEY, &signature, &(slot as i64)],
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+//! Cross-chain messages exchanged by the Universal NFT program and its counterparts
+//!
+//! The program, client SDKs, the relayer and tests all encode and decode these through this
+//! crate so the wire format has a single definition.
+
+use anchor_lang::prelude::*;
+
+/// Maximum number of on-chain attributes per token
+pub const MAX_ATTRIBUTES: usize = 8;
+/// Maximum attribute key length
+pub const MAX_ATTRIBUTE_KEY_LEN: usize = 16;
+/// Maximum attribute value length
+pub const MAX_ATTRIBUTE_VALUE_LEN: usize = 32;
+
+/// Single on-chain trait
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct Attribute {
+    #[max_len(MAX_ATTRIBUTE_KEY_LEN)]
+    pub key: String,
+    #[max_len(MAX_ATTRIBUTE_VALUE_LEN)]
+    pub value: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum MessageType {
+    Mint,
+    Burn,
+    Transfer,
+    MetadataUpdate,
+    /// Mint carrying `keccak256(uri)` in place of the URI
+    CompactMint,
+}
+
+/// Outbound message sent through the Gateway to the universal contract
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CrossChainMessage {
+    pub message_type: MessageType,
+    pub token_id: u64,
+    pub recipient_address: Vec<u8>,
+    pub metadata_uri: String,
+    /// Counterpart collection contract on the destination chain; empty for the default contract
+    pub target_collection: Vec<u8>,
+    pub attributes: Vec<Attribute>,
+    /// Collision-free uint256 identity of the NFT across chains, big-endian
+    pub universal_id: [u8; 32],
+    /// Opaque caller context, surfaced in the destination's receive event
+    pub extra_data: Vec<u8>,
+    /// Chain the universal contract forwards the NFT on to; None when the first hop is final
+    pub final_destination_chain: Option<u64>,
+    /// Receiver on `final_destination_chain` in that chain's encoding; empty without a final hop
+    pub final_receiver: Vec<u8>,
+    /// Units transferred: 1 for an NFT, any positive amount for a semi-fungible
+    pub amount: u64,
+}
+
+/// Outbound message of the compact mode: the URI is replaced by its hash
+///
+/// The destination resolves the URI from its own registry by `universal_id` and checks it
+/// against `uri_hash`, so a full URI is never carried on the wire.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CompactCrossChainMessage {
+    pub message_type: MessageType,
+    pub token_id: u64,
+    pub recipient_address: Vec<u8>,
+    /// keccak256 of the metadata URI
+    pub uri_hash: [u8; 32],
+    /// Counterpart collection contract on the destination chain; empty for the default contract
+    pub target_collection: Vec<u8>,
+    pub attributes: Vec<Attribute>,
+    /// Collision-free uint256 identity of the NFT across chains, big-endian
+    pub universal_id: [u8; 32],
+    /// Opaque caller context, surfaced in the destination's receive event
+    pub extra_data: Vec<u8>,
+    /// Chain the universal contract forwards the NFT on to; None when the first hop is final
+    pub final_destination_chain: Option<u64>,
+    /// Receiver on `final_destination_chain` in that chain's encoding; empty without a final hop
+    pub final_receiver: Vec<u8>,
+    /// Units transferred: 1 for an NFT, any positive amount for a semi-fungible
+    pub amount: u64,
+}
+
+/// Inbound Mint payload, also carried back by reverts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CrossChainNFTTransfer {
+    pub token_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub receiver: Pubkey,
+    pub source_chain: Vec<u8>,
+    /// Origin contract of a foreign NFT; empty for a Solana-native NFT returning home
+    pub origin_contract: Vec<u8>,
+    /// Token id of a foreign NFT on its origin contract, big-endian uint256
+    pub origin_token_id: [u8; 32],
+    /// Opaque context the sender attached to the transfer
+    pub extra_data: Vec<u8>,
+    /// Units transferred: 1 for an NFT, any positive amount for a semi-fungible
+    pub amount: u64,
+}
+
+/// Inbound CompactMint payload; the receiving program resolves the URI from its registry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CrossChainCompactTransfer {
+    pub token_id: u64,
+    pub name: String,
+    pub symbol: String,
+    /// keccak256 of the metadata URI
+    pub uri_hash: [u8; 32],
+    pub receiver: Pubkey,
+    pub source_chain: Vec<u8>,
+    /// Origin contract of a foreign NFT; empty for a Solana-native NFT returning home
+    pub origin_contract: Vec<u8>,
+    /// Token id of a foreign NFT on its origin contract, big-endian uint256
+    pub origin_token_id: [u8; 32],
+    /// Opaque context the sender attached to the transfer
+    pub extra_data: Vec<u8>,
+    /// Units transferred: 1 for an NFT, any positive amount for a semi-fungible
+    pub amount: u64,
+}
+
+impl CrossChainCompactTransfer {
+    /// Expand into a full transfer once the URI has been resolved
+    pub fn with_uri(self, uri: String) -> CrossChainNFTTransfer {
+        CrossChainNFTTransfer {
+            token_id: self.token_id,
+            name: self.name,
+            symbol: self.symbol,
+            uri,
+            receiver: self.receiver,
+            source_chain: self.source_chain,
+            origin_contract: self.origin_contract,
+            origin_token_id: self.origin_token_id,
+            extra_data: self.extra_data,
+            amount: self.amount,
+        }
+    }
+}
+
+/// Inbound Burn payload
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CrossChainBurn {
+    pub token_id: u64,
+}
+
+/// Inbound MetadataUpdate payload
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CrossChainMetadataUpdate {
+    pub token_id: u64,
+    pub name: String,
+    pub uri: String,
+}
+
+/// A decoded MessageType-tagged envelope
+pub enum Envelope {
+    Mint(CrossChainNFTTransfer),
+    Burn(CrossChainBurn),
+    MetadataUpdate(CrossChainMetadataUpdate),
+    CompactMint(CrossChainCompactTransfer),
+}
+
+/// Why an envelope could not be decoded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The tag is not a MessageType, or its type has no inbound payload
+    UnsupportedMessageType,
+    /// The payload does not decode as its tag's type
+    Malformed,
+}
+
+/// Encode a MessageType tag followed by its Borsh payload
+pub fn encode_envelope(message_type: MessageType, payload: &impl AnchorSerialize) -> std::io::Result<Vec<u8>> {
+    let mut data = message_type.try_to_vec()?;
+    payload.serialize(&mut data)?;
+    Ok(data)
+}
+
+/// Decode a MessageType tag followed by its Borsh payload
+///
+/// Trailing bytes after the payload are ignored, as they always have been by `on_call`.
+pub fn decode_envelope(data: &[u8]) -> std::result::Result<Envelope, EnvelopeError> {
+    let (tag, mut payload) = data.split_first().ok_or(EnvelopeError::Malformed)?;
+    let message_type =
+        MessageType::try_from_slice(&[*tag]).map_err(|_| EnvelopeError::UnsupportedMessageType)?;
+    match message_type {
+        MessageType::Mint => CrossChainNFTTransfer::deserialize(&mut payload).map(Envelope::Mint),
+        MessageType::Burn => CrossChainBurn::deserialize(&mut payload).map(Envelope::Burn),
+        MessageType::MetadataUpdate => {
+            CrossChainMetadataUpdate::deserialize(&mut payload).map(Envelope::MetadataUpdate)
+        }
+        MessageType::CompactMint => {
+            CrossChainCompactTransfer::deserialize(&mut payload).map(Envelope::CompactMint)
+        }
+        MessageType::Transfer => return Err(EnvelopeError::UnsupportedMessageType),
+    }
+    .map_err(|_| EnvelopeError::Malformed)
+}
+
+/// keccak256 of a metadata URI, as carried by compact messages
+pub fn uri_hash(uri: &str) -> [u8; 32] {
+    anchor_lang::solana_program::keccak::hash(uri.as_bytes()).to_bytes()
+}
+
+/// Decode an ABI-encoded (bytes32 receiver, uint256 tokenId, string uri, uint256 amount, address sender)
+/// transfer from an EVM universal contract, normalizing the receiver to a Pubkey. Returns None when
+/// `data` does not have that exact layout.
+pub fn decode_abi_transfer(data: &[u8], sender: [u8; 20]) -> Option<CrossChainNFTTransfer> {
+    const WORD: usize = 32;
+    const HEAD_LEN: usize = 5 * WORD;
+
+    // Read a word holding a small unsigned integer
+    let read_usize = |word: &[u8]| -> Option<usize> {
+        if word[..WORD - 8].iter().any(|b| *b != 0) {
+            return None;
+        }
+        usize::try_from(u64::from_be_bytes(word[WORD - 8..].try_into().ok()?)).ok()
+    };
+
+    if data.len() < HEAD_LEN + WORD || data.len() % WORD != 0 {
+        return None;
+    }
+    let word = |index: usize| &data[index * WORD..(index + 1) * WORD];
+    if read_usize(word(2))? != HEAD_LEN || word(4)[..12].iter().any(|b| *b != 0) {
+        return None;
+    }
+    // Bound the untrusted length by the payload before doing any arithmetic with it
+    let uri_len = read_usize(word(5))?;
+    let tail = &data[HEAD_LEN + WORD..];
+    if uri_len > tail.len() || tail.len() != uri_len.div_ceil(WORD) * WORD {
+        return None;
+    }
+    let uri_bytes = &tail[..uri_len];
+
+    Some(CrossChainNFTTransfer {
+        token_id: 0,
+        name: String::new(),
+        symbol: String::new(),
+        uri: String::from_utf8(uri_bytes.to_vec()).ok()?,
+        receiver: Pubkey::new_from_array(word(0).try_into().ok()?),
+        source_chain: Vec::new(),
+        origin_contract: sender.to_vec(),
+        origin_token_id: word(1).try_into().ok()?,
+        extra_data: Vec::new(),
+        // EVM universal contracts bridge ERC-721 tokens, one unit at a time
+        amount: 1,
+    })
+}
@@ -0,0 +1,27 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use connected::decode_abi_transfer;
+use connected_fuzz::AbiTransfer;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Input {
+    Raw(Vec<u8>, [u8; 20]),
+    Structured(AbiTransfer),
+}
+
+fuzz_target!(|input: Input| match input {
+    Input::Raw(data, sender) => {
+        let _ = decode_abi_transfer(&data, sender);
+    }
+    // Every well-formed payload decodes to the fields it was built from
+    Input::Structured(transfer) => {
+        let decoded = decode_abi_transfer(&transfer.encode(), transfer.sender)
+            .expect("well-formed ABI payload must decode");
+        assert_eq!(decoded.receiver.to_bytes(), transfer.receiver);
+        assert_eq!(decoded.origin_token_id, transfer.token_id);
+        assert_eq!(decoded.origin_contract, transfer.sender.to_vec());
+        assert_eq!(decoded.uri, transfer.uri);
+    }
+});
@@ -0,0 +1,25 @@
+#![no_main]
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use arbitrary::Arbitrary;
+use connected::CrossChainMessage;
+use connected_fuzz::ArbitraryMessage;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Input {
+    Raw(Vec<u8>),
+    Structured(ArbitraryMessage),
+}
+
+fuzz_target!(|input: Input| match input {
+    Input::Raw(data) => {
+        let _ = CrossChainMessage::try_from_slice(&data);
+    }
+    // Well-formed messages survive a round trip unchanged
+    Input::Structured(message) => {
+        let bytes = CrossChainMessage::from(message).try_to_vec().unwrap();
+        let decoded = CrossChainMessage::try_from_slice(&bytes).unwrap();
+        assert_eq!(decoded.try_to_vec().unwrap(), bytes);
+    }
+});
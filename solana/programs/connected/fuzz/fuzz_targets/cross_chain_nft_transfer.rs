@@ -0,0 +1,40 @@
+#![no_main]
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use arbitrary::Arbitrary;
+use connected::{CrossChainBurn, CrossChainMetadataUpdate, CrossChainNFTTransfer};
+use connected_fuzz::ArbitraryTransfer;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Input {
+    /// Untrusted `on_call` data: a MessageType tag followed by its payload
+    Envelope(Vec<u8>),
+    Structured(ArbitraryTransfer),
+}
+
+fuzz_target!(|input: Input| match input {
+    // Any inbound bytes must decode or fail cleanly, mirroring `receive_envelope`
+    Input::Envelope(data) => {
+        if let Some((tag, mut payload)) = data.split_first() {
+            match tag {
+                0 => {
+                    let _ = CrossChainNFTTransfer::deserialize(&mut payload);
+                }
+                1 => {
+                    let _ = CrossChainBurn::deserialize(&mut payload);
+                }
+                3 => {
+                    let _ = CrossChainMetadataUpdate::deserialize(&mut payload);
+                }
+                _ => {}
+            }
+        }
+    }
+    // Well-formed transfers survive a round trip unchanged
+    Input::Structured(transfer) => {
+        let bytes = CrossChainNFTTransfer::from(transfer).try_to_vec().unwrap();
+        let decoded = CrossChainNFTTransfer::try_from_slice(&bytes).unwrap();
+        assert_eq!(decoded.try_to_vec().unwrap(), bytes);
+    }
+});
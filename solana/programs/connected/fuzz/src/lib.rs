@@ -0,0 +1,133 @@
+//! Structured fuzz inputs for the cross-chain message codecs
+//!
+//! Program types hold `Pubkey`s and other foreign types without `Arbitrary` impls, so each
+//! input mirrors its program type with plain fields and converts into it.
+
+use anchor_lang::prelude::Pubkey;
+use arbitrary::Arbitrary;
+use connected::{Attribute, CrossChainMessage, CrossChainNFTTransfer, MessageType};
+
+#[derive(Arbitrary, Debug)]
+pub struct ArbitraryTransfer {
+    pub token_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub receiver: [u8; 32],
+    pub source_chain: Vec<u8>,
+    pub origin_contract: Vec<u8>,
+    pub origin_token_id: [u8; 32],
+    pub extra_data: Vec<u8>,
+    pub amount: u64,
+}
+
+impl From<ArbitraryTransfer> for CrossChainNFTTransfer {
+    fn from(input: ArbitraryTransfer) -> Self {
+        CrossChainNFTTransfer {
+            token_id: input.token_id,
+            name: input.name,
+            symbol: input.symbol,
+            uri: input.uri,
+            receiver: Pubkey::new_from_array(input.receiver),
+            source_chain: input.source_chain,
+            origin_contract: input.origin_contract,
+            origin_token_id: input.origin_token_id,
+            extra_data: input.extra_data,
+            amount: input.amount,
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum ArbitraryMessageType {
+    Mint,
+    Burn,
+    Transfer,
+    MetadataUpdate,
+    CompactMint,
+}
+
+impl From<ArbitraryMessageType> for MessageType {
+    fn from(input: ArbitraryMessageType) -> Self {
+        match input {
+            ArbitraryMessageType::Mint => MessageType::Mint,
+            ArbitraryMessageType::Burn => MessageType::Burn,
+            ArbitraryMessageType::Transfer => MessageType::Transfer,
+            ArbitraryMessageType::MetadataUpdate => MessageType::MetadataUpdate,
+            ArbitraryMessageType::CompactMint => MessageType::CompactMint,
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct ArbitraryMessage {
+    pub message_type: ArbitraryMessageType,
+    pub token_id: u64,
+    pub recipient_address: Vec<u8>,
+    pub metadata_uri: String,
+    pub target_collection: Vec<u8>,
+    pub attributes: Vec<(String, String)>,
+    pub universal_id: [u8; 32],
+    pub extra_data: Vec<u8>,
+    pub final_destination_chain: Option<u64>,
+    pub final_receiver: Vec<u8>,
+    pub amount: u64,
+}
+
+impl From<ArbitraryMessage> for CrossChainMessage {
+    fn from(input: ArbitraryMessage) -> Self {
+        CrossChainMessage {
+            message_type: input.message_type.into(),
+            token_id: input.token_id,
+            recipient_address: input.recipient_address,
+            metadata_uri: input.metadata_uri,
+            target_collection: input.target_collection,
+            attributes: input
+                .attributes
+                .into_iter()
+                .map(|(key, value)| Attribute { key, value })
+                .collect(),
+            universal_id: input.universal_id,
+            extra_data: input.extra_data,
+            final_destination_chain: input.final_destination_chain,
+            final_receiver: input.final_receiver,
+            amount: input.amount,
+        }
+    }
+}
+
+/// An EVM universal contract's outbound call arguments
+#[derive(Arbitrary, Debug)]
+pub struct AbiTransfer {
+    pub receiver: [u8; 32],
+    pub token_id: [u8; 32],
+    pub uri: String,
+    pub amount: [u8; 32],
+    pub sender: [u8; 20],
+}
+
+impl AbiTransfer {
+    /// `abi.encode(bytes32 receiver, uint256 tokenId, string uri, uint256 amount, address sender)`
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&self.receiver);
+        data.extend_from_slice(&self.token_id);
+        data.extend_from_slice(&word(5 * 32));
+        data.extend_from_slice(&self.amount);
+        let mut sender = [0u8; 32];
+        sender[12..].copy_from_slice(&self.sender);
+        data.extend_from_slice(&sender);
+
+        data.extend_from_slice(&word(self.uri.len() as u64));
+        data.extend_from_slice(self.uri.as_bytes());
+        data.resize(data.len().div_ceil(32) * 32, 0);
+        data
+    }
+}
+
+// Big-endian uint256 word holding `value`
+fn word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
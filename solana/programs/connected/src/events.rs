@@ -0,0 +1,433 @@
+//! Events emitted by the program
+//!
+//! With the `client` feature this module also exposes the discriminator of every event and
+//! [`parse_event`], so indexers decode events without maintaining discriminators by hand.
+
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct NFTMinted {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub uri: String,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct NFTBurned {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub destination_chain: String,
+    pub destination_receiver: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct CollectionCreated {
+    pub seq: u64,
+    pub collection_id: u64,
+    pub collection: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct NFTStaked {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub staked_at: i64,
+}
+
+#[event]
+pub struct NFTUnstaked {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub duration: u64,
+}
+
+#[event]
+pub struct NFTRented {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub renter: Pubkey,
+    pub expiry: i64,
+}
+
+#[event]
+pub struct RentalEnded {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub renter: Pubkey,
+}
+
+#[event]
+pub struct NFTTransferred {
+    pub seq: u64,
+    pub token_id: u64,
+    pub from: Pubkey,
+    pub to: Pubkey,
+}
+
+#[event]
+pub struct OperatorApproved {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub operator: Pubkey,
+}
+
+#[event]
+pub struct OperatorRevoked {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct NFTReceived {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub uri: String,
+    pub from_chain: String,
+}
+
+#[event]
+pub struct CrossChainTransferInitiated {
+    pub seq: u64,
+    pub token_id: u64,
+    pub destination_chain: String,
+    pub destination_receiver: String,
+    pub gas_amount: u64,
+}
+
+#[event]
+pub struct CrossChainTransferEvent {
+    pub seq: u64,
+    pub token_id: u64,
+    pub transfer_id: [u8; 32],
+    pub timestamp: i64,
+    pub slot: u64,
+    pub from_chain: String,
+    pub to_chain: String,
+    pub sender: Pubkey,
+    pub receiver: Vec<u8>,
+    /// Chain the NFT is forwarded on to from `to_chain`, when it is routed over several hops
+    pub final_destination_chain: Option<u64>,
+    /// Receiver on `final_destination_chain`; empty without a final hop
+    pub final_receiver: Vec<u8>,
+}
+
+#[event]
+pub struct NFTClaimed {
+    pub seq: u64,
+    pub token_id: u64,
+    pub receiver: Pubkey,
+}
+
+#[event]
+pub struct CrossChainTransferReceived {
+    pub seq: u64,
+    pub token_id: u64,
+    pub transfer_id: [u8; 32],
+    pub timestamp: i64,
+    pub slot: u64,
+    pub sender: [u8; 20],
+    pub receiver: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Opaque context the sender attached to the transfer
+    pub extra_data: Vec<u8>,
+}
+
+#[event]
+pub struct NFTMetadataUpdated {
+    pub seq: u64,
+    pub token_id: u64,
+    pub uri: String,
+}
+
+#[event]
+pub struct MetadataUpdateSent {
+    pub seq: u64,
+    pub token_id: u64,
+    pub destination_chain_id: u64,
+    pub uri: String,
+}
+
+#[event]
+pub struct BurnedNFTSwept {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub cranker: Pubkey,
+    pub owner_refund: u64,
+    pub cranker_reward: u64,
+}
+
+#[event]
+pub struct MintFeeCollected {
+    pub seq: u64,
+    pub token_id: u64,
+    pub payer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub seq: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PdaFunded {
+    pub seq: u64,
+    pub funder: Pubkey,
+    pub amount: u64,
+    /// Program PDA balance after the deposit
+    pub balance: u64,
+}
+
+#[event]
+pub struct RevertRefunded {
+    pub seq: u64,
+    pub owner: Pubkey,
+    /// Mint of the refunded SPL token; None for SOL
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TransferConfirmed {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub transfer_id: [u8; 32],
+}
+
+#[event]
+pub struct TransferDispatched {
+    pub seq: u64,
+    pub token_id: u64,
+    pub transfer_id: [u8; 32],
+    pub cranker: Pubkey,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub seq: u64,
+    pub window_start_slot: u64,
+    pub window_outbound: u64,
+    pub window_inbound: u64,
+}
+
+#[event]
+pub struct TransferReclaimed {
+    pub seq: u64,
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub transfer_id: [u8; 32],
+}
+
+#[event]
+pub struct CrossChainTransferReverted {
+    pub seq: u64,
+    pub token_id: u64,
+    pub transfer_id: [u8; 32],
+    pub timestamp: i64,
+    pub slot: u64,
+    pub original_sender: Pubkey,
+    pub reverted_amount: u64,
+}
+
+#[event]
+pub struct RescueQueued {
+    pub seq: u64,
+    /// Mint of the SPL token to rescue; None for lamports
+    pub asset: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub executable_slot: u64,
+}
+
+#[event]
+pub struct AssetsRescued {
+    pub seq: u64,
+    pub asset: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct AddressBlocked {
+    pub seq: u64,
+    pub address: Vec<u8>,
+}
+
+#[event]
+pub struct AddressUnblocked {
+    pub seq: u64,
+    pub address: Vec<u8>,
+}
+
+#[event]
+pub struct AuthorityRenounced {
+    pub seq: u64,
+    pub previous_authority: Pubkey,
+}
+
+#[event]
+pub struct ProgramInitialized {
+    pub seq: u64,
+    pub authority: Pubkey,
+    pub gateway_program: Pubkey,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub seq: u64,
+    pub authority: Pubkey,
+    /// The update as submitted; None fields were left unchanged
+    pub params: crate::UpdateConfigParams,
+}
+
+#[event]
+pub struct BridgePauseSet {
+    pub seq: u64,
+    pub authority: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct ChainConfigSet {
+    pub seq: u64,
+    pub authority: Pubkey,
+    pub chain_id: u64,
+    pub params: crate::ChainConfigParams,
+}
+
+#[event]
+pub struct MaxSupplySet {
+    pub seq: u64,
+    pub authority: Pubkey,
+    pub max_supply: u64,
+}
+
+#[event]
+pub struct CollectionAuthorityTransferred {
+    pub seq: u64,
+    pub collection: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct CollectionItemMigrated {
+    pub seq: u64,
+    pub collection: Pubkey,
+    pub token_id: u64,
+    pub destination_chain_id: u64,
+    /// Items the migration has bridged, including this one
+    pub migrated: u64,
+}
+
+// Generate the event enum, discriminator constants and decoder from one list of events
+#[cfg(feature = "client")]
+macro_rules! program_events {
+    ($($event:ident => $discriminator:ident,)*) => {
+        /// Discriminators prefixing each event's Borsh payload
+        pub mod discriminators {
+            use anchor_lang::Discriminator;
+
+            $(pub const $discriminator: &[u8] = <super::$event as Discriminator>::DISCRIMINATOR;)*
+        }
+
+        /// Any event emitted by the program
+        pub enum ProgramEvent {
+            $($event($event),)*
+        }
+
+        impl ProgramEvent {
+            /// Event name, as in the IDL
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(ProgramEvent::$event(_) => stringify!($event),)*
+                }
+            }
+
+            /// Program-wide sequence number the event was emitted with
+            pub fn seq(&self) -> u64 {
+                match self {
+                    $(ProgramEvent::$event(event) => event.seq,)*
+                }
+            }
+        }
+
+        /// Decode an event from discriminator-prefixed bytes
+        ///
+        /// Accepts the base64-decoded payload of a `Program data:` log line, or the data of an
+        /// `emit_cpi!` self-invocation including its event instruction tag. Returns None for bytes
+        /// that are not a well-formed event of this program.
+        pub fn parse_event(log_bytes: &[u8]) -> Option<ProgramEvent> {
+            let bytes = log_bytes
+                .strip_prefix(anchor_lang::event::EVENT_IX_TAG_LE)
+                .unwrap_or(log_bytes);
+            $(
+                if let Some(mut data) = bytes.strip_prefix(discriminators::$discriminator) {
+                    return $event::deserialize(&mut data).ok().map(ProgramEvent::$event);
+                }
+            )*
+            None
+        }
+    };
+}
+
+#[cfg(feature = "client")]
+program_events! {
+    NFTMinted => NFT_MINTED,
+    NFTBurned => NFT_BURNED,
+    CollectionCreated => COLLECTION_CREATED,
+    NFTStaked => NFT_STAKED,
+    NFTUnstaked => NFT_UNSTAKED,
+    NFTRented => NFT_RENTED,
+    RentalEnded => RENTAL_ENDED,
+    NFTTransferred => NFT_TRANSFERRED,
+    OperatorApproved => OPERATOR_APPROVED,
+    OperatorRevoked => OPERATOR_REVOKED,
+    NFTReceived => NFT_RECEIVED,
+    CrossChainTransferInitiated => CROSS_CHAIN_TRANSFER_INITIATED,
+    CrossChainTransferEvent => CROSS_CHAIN_TRANSFER_EVENT,
+    NFTClaimed => NFT_CLAIMED,
+    CrossChainTransferReceived => CROSS_CHAIN_TRANSFER_RECEIVED,
+    NFTMetadataUpdated => NFT_METADATA_UPDATED,
+    MetadataUpdateSent => METADATA_UPDATE_SENT,
+    BurnedNFTSwept => BURNED_NFT_SWEPT,
+    MintFeeCollected => MINT_FEE_COLLECTED,
+    FeesWithdrawn => FEES_WITHDRAWN,
+    PdaFunded => PDA_FUNDED,
+    RevertRefunded => REVERT_REFUNDED,
+    TransferConfirmed => TRANSFER_CONFIRMED,
+    TransferDispatched => TRANSFER_DISPATCHED,
+    CircuitBreakerTripped => CIRCUIT_BREAKER_TRIPPED,
+    TransferReclaimed => TRANSFER_RECLAIMED,
+    CrossChainTransferReverted => CROSS_CHAIN_TRANSFER_REVERTED,
+    RescueQueued => RESCUE_QUEUED,
+    AssetsRescued => ASSETS_RESCUED,
+    AddressBlocked => ADDRESS_BLOCKED,
+    AddressUnblocked => ADDRESS_UNBLOCKED,
+    AuthorityRenounced => AUTHORITY_RENOUNCED,
+    ProgramInitialized => PROGRAM_INITIALIZED,
+    ConfigUpdated => CONFIG_UPDATED,
+    BridgePauseSet => BRIDGE_PAUSE_SET,
+    ChainConfigSet => CHAIN_CONFIG_SET,
+    MaxSupplySet => MAX_SUPPLY_SET,
+    CollectionAuthorityTransferred => COLLECTION_AUTHORITY_TRANSFERRED,
+    CollectionItemMigrated => COLLECTION_ITEM_MIGRATED,
+}
@@ -1,16 +1,136 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::instructions;
-use std::mem::size_of;
+use anchor_lang::system_program;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::address_lookup_table;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
 use anchor_spl::{
-    token::{self, Mint, Token, TokenAccount, MintTo, mint_to, Burn, burn},
-    associated_token::AssociatedToken,
-    metadata::{create_metadata_accounts_v3, CreateMetadataAccountsV3, Metadata},
+    token::{self, spl_token::instruction::AuthorityType, Mint, Token, TokenAccount, MintTo, mint_to, Burn, burn},
+    associated_token::{self, get_associated_token_address, AssociatedToken},
+    metadata::{
+        create_metadata_accounts_v3, update_metadata_accounts_v2, CreateMetadataAccountsV3, Metadata,
+        MetadataAccount, UpdateMetadataAccountsV2,
+    },
 };
-use mpl_token_metadata::types::DataV2;
+use mpl_token_metadata::types::{Creator, DataV2};
 use gateway::{self, RevertOptions};
+pub use universal_nft_messages::{
+    decode_abi_transfer, decode_envelope, encode_envelope, uri_hash, Attribute, CompactCrossChainMessage,
+    CrossChainBurn, CrossChainCompactTransfer, CrossChainMessage, CrossChainMetadataUpdate,
+    CrossChainNFTTransfer, Envelope, EnvelopeError, MessageType, MAX_ATTRIBUTES, MAX_ATTRIBUTE_KEY_LEN,
+    MAX_ATTRIBUTE_VALUE_LEN,
+};
 
 declare_id!("9BjVGjn28E58LgSi547JYEpqpgRoo1TErkbyXiRSNDQy");
 
+pub mod events;
+pub use events::*;
+
+/// Default gas limit forwarded to `on_revert` when the caller does not supply one
+pub const DEFAULT_ON_REVERT_GAS_LIMIT: u64 = 100_000;
+/// Default upper bound for caller-supplied `on_revert` gas limits
+pub const MAX_ON_REVERT_GAS_LIMIT: u64 = 1_000_000;
+/// Maximum length of a revert message
+pub const MAX_REVERT_MESSAGE_LEN: usize = 512;
+/// Maximum number of SPL gas tokens accepted per destination chain
+pub const MAX_GAS_TOKENS: usize = 4;
+/// Maximum number of programs allowed to start outbound transfers through CPI
+pub const MAX_ALLOWED_CALLERS: usize = 4;
+/// Default minimum age in slots (~1 day) before a burned NFT can be swept
+pub const DEFAULT_SWEEP_MIN_AGE_SLOTS: u64 = 216_000;
+/// Default share of swept rent paid to the cranker, in basis points
+pub const DEFAULT_SWEEP_REWARD_BPS: u16 = 1_000;
+/// Default slots (~1 hour) after which an unconfirmed outbound transfer may be reclaimed
+pub const DEFAULT_TRANSFER_TIMEOUT_SLOTS: u64 = 9_000;
+/// Slots (~1 day) a queued rescue must wait before it can be executed
+pub const RESCUE_TIMELOCK_SLOTS: u64 = 216_000;
+/// Authority of a program whose authority was renounced; no key can sign for it
+pub const RENOUNCED_AUTHORITY: Pubkey = Pubkey::new_from_array([0; 32]);
+/// High bit marking token ids derived for NFTs that originate on another chain
+pub const FOREIGN_TOKEN_ID_FLAG: u64 = 1 << 63;
+/// Default chain id of this Solana deployment (ZetaChain's id for Solana devnet)
+pub const DEFAULT_SELF_CHAIN_ID: u64 = 901;
+/// Basis point denominator
+pub const MAX_BPS: u16 = 10_000;
+/// Maximum number of recipients in a single airdrop
+pub const MAX_AIRDROP_RECIPIENTS: usize = 5;
+/// Remaining accounts per airdrop recipient: mint, token account, nft_info, metadata, recipient,
+/// recipient owner index page, mint index
+pub const AIRDROP_ACCOUNTS_PER_RECIPIENT: usize = 7;
+/// Number of token ids stored per owner index page
+pub const OWNER_INDEX_PAGE_SIZE: usize = 32;
+/// Number of token ids stored per global supply index page
+pub const SUPPLY_INDEX_PAGE_SIZE: usize = 64;
+/// Maximum NFT name length, matching Metaplex metadata limits
+pub const MAX_NAME_LEN: usize = 32;
+/// Maximum NFT symbol length, matching Metaplex metadata limits
+pub const MAX_SYMBOL_LEN: usize = 10;
+/// Maximum NFT metadata URI length, matching Metaplex metadata limits
+pub const MAX_URI_LEN: usize = 200;
+/// Maximum length of an encoded destination receiver (bech32 Bitcoin addresses are the longest)
+pub const MAX_RECEIVER_LEN: usize = 90;
+/// Maximum length of a CAIP-2 chain identifier (8-char namespace, colon, 32-char reference)
+pub const MAX_CAIP2_LEN: usize = 41;
+/// Maximum base URI length for sale mints; the token id is appended
+pub const MAX_BASE_URI_LEN: usize = 180;
+/// Maximum number of phases in the mint schedule
+pub const MAX_MINT_PHASES: usize = 8;
+/// Maximum length of the opaque `extra_data` carried with a transfer
+pub const MAX_EXTRA_DATA_LEN: usize = 128;
+/// Maximum serialized outbound message size stored for retries
+pub const MAX_OUTBOUND_MESSAGE_LEN: usize = 904 + 4 + MAX_EXTRA_DATA_LEN + 9 + 4 + MAX_RECEIVER_LEN + 8;
+/// Number of provenance entries kept per NFT
+pub const PROVENANCE_LEN: usize = 8;
+/// Current schema version of UniversalNFTState; 0 marks the baseline layout, `LegacyUniversalNFTState`
+pub const UNIVERSAL_NFT_STATE_VERSION: u8 = 1;
+/// Current schema version of NFTInfo; 0 marks the baseline layout, `LegacyNFTInfo`
+///
+/// Version 2 appends `reserved`, so later fields can be carved out of it without resizing.
+/// Version 3 carves `semi_fungible` out of it, version 4 `metadata_hash`, version 5
+/// `transfer_nonce`.
+pub const NFT_INFO_VERSION: u8 = 5;
+
+// `msg!` only when `Config.debug_logs` is set; log formatting costs compute and exposes internals
+macro_rules! debug_msg {
+    ($config:expr, $($arg:tt)+) => {
+        if $config.debug_logs {
+            msg!($($arg)+);
+        }
+    };
+}
+
+// AuthorityMint of a `MintNFT` or `MintNFTAuto` context, whose accounts share their names
+macro_rules! authority_mint {
+    ($ctx:expr) => {
+        AuthorityMint {
+            nft: NewNftAccounts {
+                payer: $ctx.accounts.payer.to_account_info(),
+                pda: $ctx.accounts.pda.to_account_info(),
+                pda_bump: $ctx.bumps.pda,
+                mint: $ctx.accounts.mint.to_account_info(),
+                token_account: $ctx.accounts.token_account.to_account_info(),
+                metadata: $ctx.accounts.metadata.to_account_info(),
+                update_authority: $ctx.accounts.authority.to_account_info(),
+                update_authority_is_signer: true,
+                token_program: $ctx.accounts.token_program.to_account_info(),
+                metadata_program: $ctx.accounts.metadata_program.to_account_info(),
+                system_program: $ctx.accounts.system_program.to_account_info(),
+                rent: $ctx.accounts.rent.to_account_info(),
+            },
+            authority: $ctx.accounts.authority.key(),
+            mint_fee_lamports: $ctx.accounts.config.mint_fee_lamports,
+            universal_nft_state: &mut $ctx.accounts.universal_nft_state,
+            treasury: &mut $ctx.accounts.treasury,
+            nft_info: &mut $ctx.accounts.nft_info,
+            owner_index: &mut $ctx.accounts.owner_index,
+            supply_index: &mut $ctx.accounts.supply_index,
+            mint_index: &mut $ctx.accounts.mint_index,
+            collection: $ctx.accounts.collection.as_deref_mut(),
+        }
+    };
+}
+
 #[program]
 pub mod connected {
     use super::*;
@@ -18,13 +138,61 @@ pub mod connected {
     /// Initialize the Universal NFT program
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         let universal_nft_state = &mut ctx.accounts.universal_nft_state;
-        universal_nft_state.authority = ctx.accounts.signer.key();
+        universal_nft_state.authority = ctx.accounts.authority.key();
         universal_nft_state.total_supply = 0;
         universal_nft_state.next_token_id = 1;
+        universal_nft_state.event_seq = 0;
+        universal_nft_state.max_supply = None;
+        universal_nft_state.version = UNIVERSAL_NFT_STATE_VERSION;
+
+        let config = &mut ctx.accounts.config;
+        // Localnet builds with `mock-gateway` talk to the workspace mock instead of the real Gateway
+        #[cfg(feature = "mock-gateway")]
+        let gateway_program = mock_gateway::ID;
+        #[cfg(not(feature = "mock-gateway"))]
+        let gateway_program = gateway::ID;
+        config.gateway_program = gateway_program;
+        config.default_on_revert_gas_limit = DEFAULT_ON_REVERT_GAS_LIMIT;
+        config.max_on_revert_gas_limit = MAX_ON_REVERT_GAS_LIMIT;
+        config.abort_address = [0u8; 20];
+        config.bridge_fee_lamports = 0;
+        config.mint_fee_lamports = 0;
+        config.universal_contract = [0u8; 20];
+        config.max_uri_len = MAX_URI_LEN as u32;
+        config.sweep_min_age_slots = DEFAULT_SWEEP_MIN_AGE_SLOTS;
+        config.sweep_reward_bps = DEFAULT_SWEEP_REWARD_BPS;
+        config.transfer_timeout_slots = DEFAULT_TRANSFER_TIMEOUT_SLOTS;
+        config.max_transfers_per_epoch = 0;
+        config.paused = false;
+        config.circuit_breaker_window_slots = 0;
+        config.circuit_breaker_threshold = 0;
+        config.self_chain_id = DEFAULT_SELF_CHAIN_ID;
+        config.wormhole_program = Pubkey::default();
+        config.layerzero_endpoint = Pubkey::default();
+        config.debug_logs = false;
+        config.lookup_table = Pubkey::default();
+        config.forward_deposits_to_treasury = false;
+        config.allowed_callers = [Pubkey::default(); MAX_ALLOWED_CALLERS];
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_collected = 0;
+        treasury.total_withdrawn = 0;
+
+        let outbound_queue = &mut ctx.accounts.outbound_queue;
+        outbound_queue.head = 0;
+        outbound_queue.tail = 0;
+
+        emit!(ProgramInitialized {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            authority: ctx.accounts.authority.key(),
+            gateway_program,
+        });
         Ok(())
     }
 
     /// Mint a new Universal NFT
+    ///
+    /// `token_id` must be the state's `next_token_id`; `mint_nft_auto` assigns it instead.
     pub fn mint_nft(
         ctx: Context<MintNFT>,
         token_id: u64,
@@ -32,637 +200,8126 @@ pub mod connected {
         symbol: String,
         uri: String,
         to: Pubkey,
+        soulbound: bool,
     ) -> Result<()> {
-        let universal_nft_state = &mut ctx.accounts.universal_nft_state;
-        
-        // Ensure token ID is unique
-        require!(token_id >= universal_nft_state.next_token_id, UniversalNFTError::TokenIdTaken);
-        
-        // Create mint account
-        let cpi_accounts = MintTo {
-            mint: ctx.accounts.mint.to_account_info(),
-            to: ctx.accounts.token_account.to_account_info(),
-            authority: ctx.accounts.signer.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        mint_to(cpi_ctx, 1)?;
+        // Explicit ids must still be the next sequential id, see `allocate_token_ids`
+        ctx.accounts.universal_nft_state.check_next_token_id(token_id)?;
+        mint_authority_nft(authority_mint!(ctx), token_id, name, symbol, uri, to, soulbound, None)
+    }
 
-        // Create metadata
-        let data_v2 = DataV2 {
-            name: name.clone(),
-            symbol: symbol.clone(),
-            uri: uri.clone(),
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        };
+    /// Mint a new Universal NFT under the next unused token id, returned via return data
+    ///
+    /// Clients need not guess the next id, so concurrent mints never race for the same one.
+    pub fn mint_nft_auto(
+        ctx: Context<MintNFTAuto>,
+        name: String,
+        symbol: String,
+        uri: String,
+        to: Pubkey,
+        soulbound: bool,
+    ) -> Result<()> {
+        let token_id = ctx.accounts.universal_nft_state.next_token_id;
+        mint_authority_nft(authority_mint!(ctx), token_id, name, symbol, uri, to, soulbound, None)?;
 
-        let cpi_accounts = CreateMetadataAccountsV3 {
-            metadata: ctx.accounts.metadata.to_account_info(),
-            mint: ctx.accounts.mint.to_account_info(),
-            mint_authority: ctx.accounts.signer.to_account_info(),
-            update_authority: ctx.accounts.signer.to_account_info(),
-            payer: ctx.accounts.signer.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-            rent: ctx.accounts.rent.to_account_info(),
-        };
+        anchor_lang::solana_program::program::set_return_data(&token_id.to_le_bytes());
+        Ok(())
+    }
 
-        let cpi_program = ctx.accounts.metadata_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    /// Mint a semi-fungible Universal token with `amount` interchangeable units
+    ///
+    /// Like `mint_nft`, but the token id is ERC-1155 style: its units can be held by many wallets
+    /// and bridged out in partial amounts. `token_id` must be the state's `next_token_id`.
+    pub fn mint_semi_fungible(
+        ctx: Context<MintNFT>,
+        token_id: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+        to: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.universal_nft_state.check_next_token_id(token_id)?;
+        mint_authority_nft(authority_mint!(ctx), token_id, name, symbol, uri, to, false, Some(amount))
+    }
 
-        create_metadata_accounts_v3(cpi_ctx, data_v2, true, true, None)?;
+    /// Publish or replace the allowlist Merkle root and sale parameters (authority only)
+    pub fn set_allowlist(ctx: Context<SetAllowlist>, params: AllowlistParams) -> Result<()> {
+        require_gte!(MAX_NAME_LEN, params.name.len(), UniversalNFTError::NameTooLong);
+        require_gte!(MAX_SYMBOL_LEN, params.symbol.len(), UniversalNFTError::SymbolTooLong);
+        require_gte!(MAX_BASE_URI_LEN, params.base_uri.len(), UniversalNFTError::UriTooLong);
 
-        // Store NFT information
-        let nft_info = &mut ctx.accounts.nft_info;
-        nft_info.token_id = token_id;
-        nft_info.name = name;
-        nft_info.symbol = symbol;
-        nft_info.uri = uri;
-        nft_info.owner = to;
-        nft_info.is_burned = false;
-        nft_info.mint = ctx.accounts.mint.key();
+        let allowlist = &mut ctx.accounts.allowlist;
+        allowlist.merkle_root = params.merkle_root;
+        allowlist.max_claims_per_address = params.max_claims_per_address;
+        allowlist.name = params.name;
+        allowlist.symbol = params.symbol;
+        allowlist.base_uri = params.base_uri;
+        Ok(())
+    }
 
-        universal_nft_state.total_supply += 1;
-        if token_id >= universal_nft_state.next_token_id {
-            universal_nft_state.next_token_id = token_id + 1;
-        }
+    /// Mint the next token id to an allowlisted caller
+    ///
+    /// The leaf is `keccak(claimer)`; proofs use sorted-pair keccak hashing.
+    pub fn mint_allowlisted(ctx: Context<MintAllowlisted>, proof: Vec<[u8; 32]>) -> Result<()> {
+        let claimer = ctx.accounts.sale.minter.key();
+        let leaf = anchor_lang::solana_program::keccak::hash(claimer.as_ref()).to_bytes();
+        require!(
+            verify_merkle_proof(&proof, &ctx.accounts.allowlist.merkle_root, leaf),
+            UniversalNFTError::NotAllowlisted
+        );
 
-        emit!(NFTMinted {
-            token_id,
-            owner: to,
-            uri: nft_info.uri.clone(),
-            mint: ctx.accounts.mint.key(),
-        });
+        let claim_counter = &mut ctx.accounts.claim_counter;
+        claim_counter.claimer = claimer;
+        require!(
+            claim_counter.claimed < ctx.accounts.allowlist.max_claims_per_address,
+            UniversalNFTError::ClaimLimitReached
+        );
+        claim_counter.claimed = claim_counter.claimed.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+
+        let allowlist = &ctx.accounts.allowlist;
+        mint_sale_nft(&mut ctx.accounts.sale, &ctx.bumps.sale, &allowlist.name, &allowlist.symbol, &allowlist.base_uri)
+    }
+
+    /// Configure the paid public sale (authority only)
+    pub fn set_public_sale(ctx: Context<SetPublicSale>, params: PublicSaleParams) -> Result<()> {
+        require_gte!(MAX_NAME_LEN, params.name.len(), UniversalNFTError::NameTooLong);
+        require_gte!(MAX_SYMBOL_LEN, params.symbol.len(), UniversalNFTError::SymbolTooLong);
+        require_gte!(MAX_BASE_URI_LEN, params.base_uri.len(), UniversalNFTError::UriTooLong);
+        require!(params.creator_share_bps <= MAX_BPS, UniversalNFTError::InvalidBasisPoints);
+        require!(params.start_ts < params.end_ts, UniversalNFTError::SaleNotActive);
 
+        let public_sale = &mut ctx.accounts.public_sale;
+        public_sale.price = params.price;
+        public_sale.payment_mint = params.payment_mint;
+        public_sale.start_ts = params.start_ts;
+        public_sale.end_ts = params.end_ts;
+        public_sale.max_per_wallet = params.max_per_wallet;
+        public_sale.creator = params.creator;
+        public_sale.creator_share_bps = params.creator_share_bps;
+        public_sale.name = params.name;
+        public_sale.symbol = params.symbol;
+        public_sale.base_uri = params.base_uri;
         Ok(())
     }
 
-    /// Burn NFT for cross-chain transfer
-    pub fn burn_nft(
-        ctx: Context<BurnNFT>,
-        token_id: u64,
-        destination_chain: String,
-        destination_receiver: String,
-    ) -> Result<()> {
-        let nft_info = &mut ctx.accounts.nft_info;
-        let universal_nft_state = &mut ctx.accounts.universal_nft_state;
+    /// Buy the next token id in the public sale
+    ///
+    /// The price is split between the creator and the treasury, in lamports or in the
+    /// sale's SPL payment mint.
+    pub fn public_mint(ctx: Context<PublicMint>) -> Result<()> {
+        let public_sale = &ctx.accounts.public_sale;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= public_sale.start_ts && now < public_sale.end_ts,
+            UniversalNFTError::SaleNotActive
+        );
 
-        // Verify ownership
-        require!(nft_info.owner == ctx.accounts.signer.key(), UniversalNFTError::NotOwner);
-        require!(!nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+        let mint_counter = &mut ctx.accounts.mint_counter;
+        mint_counter.wallet = ctx.accounts.sale.minter.key();
+        require!(
+            public_sale.max_per_wallet == 0 || mint_counter.minted < public_sale.max_per_wallet,
+            UniversalNFTError::ClaimLimitReached
+        );
+        mint_counter.minted = mint_counter.minted.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
 
-        // Burn the token
-        let cpi_accounts = Burn {
-            mint: ctx.accounts.mint.to_account_info(),
-            from: ctx.accounts.token_account.to_account_info(),
-            authority: ctx.accounts.signer.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        burn(cpi_ctx, 1)?;
+        // Split the price between the creator and the treasury
+        let creator_amount = (public_sale.price as u128 * public_sale.creator_share_bps as u128
+            / MAX_BPS as u128) as u64;
+        let treasury_amount = public_sale.price - creator_amount;
+        match public_sale.payment_mint {
+            None => {
+                let minter = ctx.accounts.sale.minter.to_account_info();
+                let system_program_info = ctx.accounts.sale.system_program.to_account_info();
+                for (to, amount) in [
+                    (ctx.accounts.creator.to_account_info(), creator_amount),
+                    (ctx.accounts.sale.treasury.to_account_info(), treasury_amount),
+                ] {
+                    if amount > 0 {
+                        let cpi_ctx = CpiContext::new(
+                            system_program_info.clone(),
+                            system_program::Transfer { from: minter.clone(), to },
+                        );
+                        system_program::transfer(cpi_ctx, amount)?;
+                    }
+                }
+                let treasury = &mut ctx.accounts.sale.treasury;
+                treasury.total_collected =
+                    treasury.total_collected.checked_add(treasury_amount).ok_or(UniversalNFTError::FeeOverflow)?;
+            }
+            Some(_) => {
+                let (Some(from), Some(treasury_account), Some(creator_account)) = (
+                    &ctx.accounts.minter_payment_account,
+                    &ctx.accounts.treasury_payment_account,
+                    &ctx.accounts.creator_payment_account,
+                ) else {
+                    return err!(UniversalNFTError::InvalidSalePayment);
+                };
+                for (to, amount) in [(creator_account, creator_amount), (treasury_account, treasury_amount)] {
+                    if amount > 0 {
+                        let cpi_ctx = CpiContext::new(
+                            ctx.accounts.sale.token_program.to_account_info(),
+                            token::Transfer {
+                                from: from.to_account_info(),
+                                to: to.to_account_info(),
+                                authority: ctx.accounts.sale.minter.to_account_info(),
+                            },
+                        );
+                        token::transfer(cpi_ctx, amount)?;
+                    }
+                }
+            }
+        }
 
-        // Mark as burned
-        nft_info.is_burned = true;
-        universal_nft_state.total_supply -= 1;
+        let public_sale = &ctx.accounts.public_sale;
+        mint_sale_nft(&mut ctx.accounts.sale, &ctx.bumps.sale, &public_sale.name, &public_sale.symbol, &public_sale.base_uri)
+    }
 
-        emit!(NFTBurned {
-            token_id,
-            owner: nft_info.owner,
-            destination_chain,
-            destination_receiver,
-            uri: nft_info.uri.clone(),
-        });
+    /// Publish or replace the ordered mint phase schedule (authority only)
+    pub fn set_mint_phases(ctx: Context<SetMintPhases>, params: MintPhasesParams) -> Result<()> {
+        require_gte!(MAX_NAME_LEN, params.name.len(), UniversalNFTError::NameTooLong);
+        require_gte!(MAX_SYMBOL_LEN, params.symbol.len(), UniversalNFTError::SymbolTooLong);
+        require_gte!(MAX_BASE_URI_LEN, params.base_uri.len(), UniversalNFTError::UriTooLong);
+        require!(params.phases.len() <= MAX_MINT_PHASES, UniversalNFTError::InvalidMintPhases);
+
+        // Phases must be non-empty windows in chronological order without overlap
+        let mut previous_end = i64::MIN;
+        for phase in params.phases.iter() {
+            require!(
+                phase.start_ts < phase.end_ts && phase.start_ts >= previous_end,
+                UniversalNFTError::InvalidMintPhases
+            );
+            previous_end = phase.end_ts;
+        }
 
+        let mint_phases = &mut ctx.accounts.mint_phases;
+        mint_phases.phases = params.phases;
+        mint_phases.name = params.name;
+        mint_phases.symbol = params.symbol;
+        mint_phases.base_uri = params.base_uri;
         Ok(())
     }
 
-    /// Handle incoming cross-chain calls from ZetaChain
-    /// Official signature from ZetaChain documentation
-    pub fn on_call(
-        ctx: Context<OnCall>,
-        amount: u64,
-        sender: [u8; 20],
-        data: Vec<u8>,
+    /// Mint the next token id under the currently active phase
+    ///
+    /// `phase_index` must name the active phase; allowlist phases also require a Merkle
+    /// proof of the minter's address.
+    pub fn mint_in_phase(
+        ctx: Context<MintInPhase>,
+        phase_index: u8,
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
-        // Use amount parameter to track the deposited amount
-        msg!("Received cross-chain call with amount: {}", amount);
-        
-        // Decode the NFT transfer data
-        let transfer_data = CrossChainNFTTransfer::deserialize(&mut &data[..])
-            .map_err(|_| ErrorCode::DecodingError)?;
-
-        // Mint the NFT on Solana
-        let mint_accounts = MintTo {
-            mint: ctx.accounts.mint_account.to_account_info(),
-            to: ctx.accounts.pda_ata.to_account_info(),
-            authority: ctx.accounts.pda.to_account_info(),
-        };
+        let mint_phases = &ctx.accounts.mint_phases;
+        let now = Clock::get()?.unix_timestamp;
+        let active = mint_phases
+            .active_phase(now)
+            .ok_or(UniversalNFTError::SaleNotActive)?;
+        require!(active == phase_index as usize, UniversalNFTError::PhaseNotActive);
+        let phase = &mint_phases.phases[active];
 
-        let seeds = &[b"connected".as_ref(), &[ctx.bumps.pda]];
-        let signer_seeds = &[&seeds[..]];
+        let minter = ctx.accounts.sale.minter.key();
+        if phase.kind == MintPhaseKind::Allowlist {
+            let leaf = anchor_lang::solana_program::keccak::hash(minter.as_ref()).to_bytes();
+            require!(
+                verify_merkle_proof(&proof, &phase.merkle_root, leaf),
+                UniversalNFTError::NotAllowlisted
+            );
+        }
 
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            mint_accounts,
-            signer_seeds,
+        let mint_counter = &mut ctx.accounts.mint_counter;
+        mint_counter.wallet = minter;
+        require!(
+            phase.max_per_wallet == 0 || mint_counter.minted < phase.max_per_wallet,
+            UniversalNFTError::ClaimLimitReached
         );
+        mint_counter.minted = mint_counter.minted.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
 
-        mint_to(cpi_ctx, 1)?;
-
-        emit!(CrossChainTransferReceived {
-            token_id: transfer_data.token_id,
-            sender,
-            receiver: ctx.accounts.pda.key(),
-            name: transfer_data.name,
-            symbol: transfer_data.symbol,
-            uri: transfer_data.uri,
-        });
+        if phase.price > 0 {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.sale.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.sale.minter.to_account_info(),
+                    to: ctx.accounts.sale.treasury.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_ctx, phase.price)?;
+            let treasury = &mut ctx.accounts.sale.treasury;
+            treasury.total_collected = treasury.total_collected.checked_add(phase.price).ok_or(UniversalNFTError::FeeOverflow)?;
+        }
 
-        Ok(())
+        mint_sale_nft(
+            &mut ctx.accounts.sale,
+            &ctx.bumps.sale,
+            &mint_phases.name,
+            &mint_phases.symbol,
+            &mint_phases.base_uri,
+        )
     }
 
-    /// Handle transaction reverts from ZetaChain
-    /// Official signature from ZetaChain documentation
-    pub fn on_revert(
-        ctx: Context<OnRevert>,
-        amount: u64,        // Asset quantity originally deposited (lamports or SPL)
-        sender: Pubkey,     // The account that triggered the deposit/call from Solana
-        data: Vec<u8>,      // Arbitrary bytes supplied via revert_message
+    /// Airdrop sequential token ids to a list of recipients (authority only)
+    ///
+    /// Each token is minted as by `mint_nft`: the mint fee is charged per token and collection
+    /// mints count against the collection's supply. Expects `AIRDROP_ACCOUNTS_PER_RECIPIENT`
+    /// remaining accounts per recipient, in order: mint PDA, recipient ATA, nft_info PDA,
+    /// metadata account, recipient wallet, a writable owner index page belonging to the
+    /// recipient and the mint index PDA.
+    pub fn airdrop<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Airdrop<'info>>,
+        token_ids_start: u64,
+        recipients: Vec<Pubkey>,
+        name: String,
+        symbol: String,
+        base_uri: String,
     ) -> Result<()> {
-        // Handle the revert scenario
-        // This could involve refunding tokens, updating state, or emitting events
-        
-        msg!("Cross-chain transaction reverted for PDA: {}", ctx.accounts.pda.key());
-        msg!("Original sender: {}", sender);
-        msg!("Reverted amount: {}", amount);
-        
-        // Use the amount parameter to avoid warnings
-        let _reverted_amount = amount;
-        
-        // Attempt to decode the original transfer data if possible
-        if let Ok(transfer_data) = CrossChainNFTTransfer::deserialize(&mut &data[..]) {
-            msg!("Reverted NFT transfer for token_id: {}", transfer_data.token_id);
-            
-            // You could implement logic here to:
-            // - Restore the burned NFT
-            // - Refund any associated tokens
-            // - Update application state
-            
-            emit!(CrossChainTransferReverted {
-                token_id: transfer_data.token_id,
-                original_sender: sender,
-                reverted_amount: _reverted_amount,
-            });
+        require!(
+            !recipients.is_empty() && recipients.len() <= MAX_AIRDROP_RECIPIENTS,
+            UniversalNFTError::InvalidAirdrop
+        );
+        require!(
+            ctx.remaining_accounts.len() == recipients.len() * AIRDROP_ACCOUNTS_PER_RECIPIENT,
+            UniversalNFTError::InvalidAirdrop
+        );
+        ctx.accounts.universal_nft_state.check_supply(recipients.len() as u64)?;
+        ctx.accounts.universal_nft_state.check_next_token_id(token_ids_start)?;
+
+        let rent = Rent::get()?;
+        let payer = ctx.accounts.payer.to_account_info();
+        let system_program_info = ctx.accounts.system_program.to_account_info();
+        let token_program_info = ctx.accounts.token_program.to_account_info();
+        let pda_key = ctx.accounts.pda.key();
+
+        for (i, (recipient, accounts)) in recipients
+            .iter()
+            .zip(ctx.remaining_accounts.chunks(AIRDROP_ACCOUNTS_PER_RECIPIENT))
+            .enumerate()
+        {
+            let [mint, token_account, nft_info_account, metadata, recipient_account, owner_index_info, mint_index_account] = accounts else {
+                return err!(UniversalNFTError::InvalidAirdrop);
+            };
+            // Sequential from `token_ids_start`, which `record_minted_nft` allocates one by one
+            let token_id = token_ids_start + i as u64;
+            let token_id_bytes = token_id.to_le_bytes();
+            let uri = format!("{}{}", base_uri, token_id);
+
+            // Validate the supplied accounts against their expected addresses
+            let (mint_key, mint_bump) =
+                Pubkey::find_program_address(&[b"nft_mint", token_id_bytes.as_ref()], ctx.program_id);
+            let (nft_info_key, nft_info_bump) =
+                Pubkey::find_program_address(&[b"nft_info", token_id_bytes.as_ref()], ctx.program_id);
+            require_keys_eq!(mint.key(), mint_key, UniversalNFTError::InvalidAirdrop);
+            require_keys_eq!(nft_info_account.key(), nft_info_key, UniversalNFTError::InvalidAirdrop);
+            let (mint_index_key, mint_index_bump) =
+                Pubkey::find_program_address(&[b"mint_index", mint_key.as_ref()], ctx.program_id);
+            require_keys_eq!(mint_index_account.key(), mint_index_key, UniversalNFTError::InvalidAirdrop);
+            require_keys_eq!(recipient_account.key(), *recipient, UniversalNFTError::InvalidAirdrop);
+            require_keys_eq!(
+                token_account.key(),
+                get_associated_token_address(recipient, &mint_key),
+                UniversalNFTError::InvalidAirdrop
+            );
+
+            // Create and initialize the mint PDA
+            let mint_seeds: &[&[u8]] = &[b"nft_mint", token_id_bytes.as_ref(), &[mint_bump]];
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program_info.clone(),
+                    system_program::CreateAccount { from: payer.clone(), to: mint.clone() },
+                    &[mint_seeds],
+                ),
+                rent.minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &token::ID,
+            )?;
+            token::initialize_mint2(
+                CpiContext::new(token_program_info.clone(), token::InitializeMint2 { mint: mint.clone() }),
+                0,
+                &pda_key,
+                Some(&pda_key),
+            )?;
+
+            // Create the recipient's associated token account for `mint_authority_nft` to mint into
+            associated_token::create(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: payer.clone(),
+                    associated_token: token_account.clone(),
+                    authority: recipient_account.clone(),
+                    mint: mint.clone(),
+                    system_program: system_program_info.clone(),
+                    token_program: token_program_info.clone(),
+                },
+            ))?;
+
+            // Create the NFTInfo and mint index PDAs, filled in by `record_minted_nft`
+            let nft_info_space = 8 + NFTInfo::INIT_SPACE;
+            let nft_info_seeds: &[&[u8]] = &[b"nft_info", token_id_bytes.as_ref(), &[nft_info_bump]];
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program_info.clone(),
+                    system_program::CreateAccount { from: payer.clone(), to: nft_info_account.clone() },
+                    &[nft_info_seeds],
+                ),
+                rent.minimum_balance(nft_info_space),
+                nft_info_space as u64,
+                ctx.program_id,
+            )?;
+            let mint_index_space = 8 + MintIndex::INIT_SPACE;
+            let mint_index_seeds: &[&[u8]] = &[b"mint_index", mint_key.as_ref(), &[mint_index_bump]];
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program_info.clone(),
+                    system_program::CreateAccount { from: payer.clone(), to: mint_index_account.clone() },
+                    &[mint_index_seeds],
+                ),
+                rent.minimum_balance(mint_index_space),
+                mint_index_space as u64,
+                ctx.program_id,
+            )?;
+
+            let mut nft_info = NFTInfo::default();
+            let mut mint_index = MintIndex { mint: mint_key, token_id };
+            let mut owner_index = Account::<OwnerIndex>::try_from(owner_index_info)?;
+            require_keys_eq!(owner_index.owner, *recipient, UniversalNFTError::OwnerIndexMismatch);
+
+            let accounts = AuthorityMint {
+                nft: NewNftAccounts {
+                    payer: payer.clone(),
+                    pda: ctx.accounts.pda.to_account_info(),
+                    pda_bump: ctx.bumps.pda,
+                    mint: mint.clone(),
+                    token_account: token_account.clone(),
+                    metadata: metadata.clone(),
+                    update_authority: ctx.accounts.authority.to_account_info(),
+                    update_authority_is_signer: true,
+                    token_program: token_program_info.clone(),
+                    metadata_program: ctx.accounts.metadata_program.to_account_info(),
+                    system_program: system_program_info.clone(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                authority: ctx.accounts.authority.key(),
+                mint_fee_lamports: ctx.accounts.config.mint_fee_lamports,
+                universal_nft_state: &mut ctx.accounts.universal_nft_state,
+                treasury: &mut ctx.accounts.treasury,
+                nft_info: &mut nft_info,
+                owner_index: &mut owner_index,
+                supply_index: &mut ctx.accounts.supply_index,
+                mint_index: &mut mint_index,
+                collection: ctx.accounts.collection.as_deref_mut(),
+            };
+            mint_authority_nft(accounts, token_id, name.clone(), symbol.clone(), uri, *recipient, false, None)?;
+
+            nft_info.try_serialize(&mut &mut nft_info_account.try_borrow_mut_data()?[..])?;
+            mint_index.try_serialize(&mut &mut mint_index_account.try_borrow_mut_data()?[..])?;
+            owner_index.exit(ctx.program_id)?;
         }
 
         Ok(())
     }
-}
 
-// Helper function to decode NFT transfer data
-fn decode_nft_transfer(data: &[u8]) -> Result<CrossChainNFTTransfer> {
-    CrossChainNFTTransfer::deserialize(&mut &data[..]).map_err(|_| ErrorCode::DecodingError.into())
-}
+    /// Create a page of an owner's token index; anyone may pay for it
+    pub fn init_owner_index(ctx: Context<InitOwnerIndex>, owner: Pubkey, page: u32) -> Result<()> {
+        let owner_index = &mut ctx.accounts.owner_index;
+        owner_index.owner = owner;
+        owner_index.page = page;
+        owner_index.token_ids = Vec::new();
+        Ok(())
+    }
 
-    /// Transfer NFT cross-chain using ZetaChain Gateway
-    pub fn transfer_cross_chain(
+    /// Return a page of an owner's token ids via return data
+    pub fn view_owner_tokens(ctx: Context<ViewOwnerTokens>, _owner: Pubkey, _page: u32) -> Result<()> {
+        let data = ctx
+            .accounts
+            .owner_index
+            .token_ids
+            .try_to_vec()
+            .map_err(|_| UniversalNFTError::SerializationError)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+        Ok(())
+    }
+
+    /// Create a page of the global supply index; anyone may pay for it
+    pub fn init_supply_index(ctx: Context<InitSupplyIndex>, page: u32) -> Result<()> {
+        let supply_index = &mut ctx.accounts.supply_index;
+        supply_index.page = page;
+        supply_index.token_ids = Vec::new();
+        Ok(())
+    }
+
+    /// Return a page of live token ids via return data
+    pub fn view_supply_page(ctx: Context<ViewSupplyPage>, _page: u32) -> Result<()> {
+        let data = ctx
+            .accounts
+            .supply_index
+            .token_ids
+            .try_to_vec()
+            .map_err(|_| UniversalNFTError::SerializationError)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+        Ok(())
+    }
+
+    /// Return a Borsh-encoded NftView for a token via return data
+    ///
+    /// The current user is the renter while a rental is active, otherwise the owner.
+    pub fn view_nft(ctx: Context<ViewNFT>, _token_id: u64) -> Result<()> {
+        let nft_info = &ctx.accounts.nft_info;
+        let now = Clock::get()?.unix_timestamp;
+        let current_user = match &ctx.accounts.rental {
+            Some(rental) if now < rental.expiry => rental.renter,
+            _ => nft_info.owner,
+        };
+        let view = NftView {
+            info: (**nft_info).clone(),
+            current_user,
+        };
+        let data = view.try_to_vec().map_err(|_| UniversalNFTError::SerializationError)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+        Ok(())
+    }
+
+    /// Return the Borsh-encoded TransferStatus of an outbound transfer via return data
+    ///
+    /// A transfer still initiated past the configured timeout reads as Expired.
+    pub fn view_transfer(ctx: Context<ViewTransfer>, _transfer_id: [u8; 32]) -> Result<()> {
+        let transfer_record = &ctx.accounts.transfer_record;
+        let expires_at = transfer_record
+            .initiated_slot
+            .saturating_add(ctx.accounts.config.transfer_timeout_slots);
+        let status = match transfer_record.status {
+            TransferStatus::Initiated if Clock::get()?.slot >= expires_at => TransferStatus::Expired,
+            status => status,
+        };
+        let data = status.try_to_vec().map_err(|_| UniversalNFTError::SerializationError)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+        Ok(())
+    }
+
+    /// Create or refresh the zero-copy mirror of a token's NFTInfo (permissionless)
+    ///
+    /// Hot paths that only need the fixed-size fields load the mirror with an AccountLoader
+    /// instead of Borsh-decoding NFTInfo with its provenance history.
+    pub fn sync_nft_info_compact(ctx: Context<SyncNFTInfoCompact>, _token_id: u64) -> Result<()> {
+        let nft_info = &ctx.accounts.nft_info;
+        let mut compact = match ctx.accounts.nft_info_compact.load_init() {
+            Ok(compact) => compact,
+            Err(_) => ctx.accounts.nft_info_compact.load_mut()?,
+        };
+        compact.copy_from(nft_info)
+    }
+
+    /// Burn an NFT and send it to `destination_chain_id` through the Gateway
+    ///
+    /// The SOL-gas counterpart of `transfer_cross_chain` for callers that only know the
+    /// receiver: the NFT's recorded URI travels with it, `gas_amount` lamports are deposited
+    /// for destination gas, and revert handling uses the configured defaults.
+    pub fn burn_nft(
         ctx: Context<TransferCrossChain>,
         token_id: u64,
-        recipient_address: [u8; 20], // Ethereum address on destination chain
+        destination_receiver: Vec<u8>,
         destination_chain_id: u64,
-        metadata_uri: String,
+        gas_amount: u64,
     ) -> Result<()> {
-        msg!("Starting cross-chain NFT transfer");
-        
-        // Verify caller authentication (in production, this would verify Gateway program)
-        let current_ix = instructions::get_instruction_relative(0, &ctx.accounts.instruction_sysvar)?;
-        msg!("Current instruction program ID: {}", current_ix.program_id);
-        
-        let nft_info = &mut ctx.accounts.nft_info;
-        
-        // Verify NFT exists and is owned by correct owner
-        if nft_info.owner != *ctx.accounts.signer.key {
-            return Err(ErrorCode::NotOwner.into());
-        }
-        
-        // Ensure NFT is not already burned
-        require!(!nft_info.is_burned, UniversalNFTError::AlreadyBurned);
-        
-        // Prepare cross-chain message for ZetaChain
-        let message_data = CrossChainMessage {
-            message_type: MessageType::Mint,
-            token_id,
+        require!(gas_amount > 0, UniversalNFTError::InvalidGasAmount);
+        let metadata_uri = ctx.accounts.nft_info.uri.clone();
+        let OutboundTransfer {
+            serialized_message,
+            revert_options,
+            seq,
+            transfer_id,
             recipient_address,
-            metadata_uri: metadata_uri.clone(),
-        };
-        
-        let serialized_message = message_data.try_to_vec()
-            .map_err(|_| ErrorCode::SerializationError)?;
-        
-        msg!("Serialized cross-chain message: {} bytes", serialized_message.len());
-        
-        // Burn the NFT on source chain first
-        let token_account = &ctx.accounts.token_account;
-        let mint_account = &ctx.accounts.mint;
-        
-        // Burn token using token program
-        let cpi_accounts = token::Burn {
-            mint: mint_account.to_account_info(),
-            from: token_account.to_account_info(),
-            authority: ctx.accounts.signer.to_account_info(),
-        };
-        
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        
-        token::burn(cpi_ctx, 1)?;
-        msg!("NFT burned successfully on source chain");
-        
-        // Update NFT state to indicate cross-chain transfer
-        nft_info.is_burned = true;
-        nft_info.cross_chain_data = Some(CrossChainData {
+        } = prepare_outbound_transfer(
+            ctx.accounts,
+            OutboundAuthority::Signer,
+            token_id,
+            destination_receiver,
             destination_chain_id,
-            recipient_address,
-            transfer_timestamp: Clock::get()?.unix_timestamp,
-        });
-        
-        // Create CPI context for Gateway deposit call
-        let gateway_cpi_accounts = gateway::cpi::accounts::DepositSplToken {
-            signer: ctx.accounts.signer.to_account_info(),
-            pda: ctx.accounts.gateway_pda.to_account_info(),
-            whitelist_entry: ctx.accounts.whitelist_entry.to_account_info(),
-            mint_account: ctx.accounts.mint.to_account_info(),
-            token_program: ctx.accounts.token_program.to_account_info(),
-            from: ctx.accounts.token_account.to_account_info(),
-            to: ctx.accounts.gateway_token_account.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-        };
-        
-        let gateway_cpi_ctx = CpiContext::new(
-            ctx.accounts.gateway_program.to_account_info(),
-            gateway_cpi_accounts,
-        );
-        
-        // Create revert options for cross-chain call
-        let revert_options = Some(RevertOptions {
-            revert_address: ctx.accounts.signer.key(),
-            call_on_revert: true,
-            abort_address: recipient_address,
-            revert_message: b"NFT transfer failed".to_vec(),
-            on_revert_gas_limit: 100000,
-        });
-        
-        // Call Gateway deposit_spl_token_and_call for cross-chain transfer
-        gateway::cpi::deposit_spl_token_and_call(
-            gateway_cpi_ctx,
-            1, // amount (1 NFT)
-            recipient_address,
-            serialized_message.clone(),
-            revert_options,
+            metadata_uri,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None,
+            1,
+            Transport::Gateway,
         )?;
-        
-        msg!("Gateway CPI call executed successfully");
-        msg!("Amount: 1 NFT token");
-        msg!("Recipient: {:?}", recipient_address);
-        msg!("Message size: {} bytes", serialized_message.len());
-        
-        // Emit cross-chain transfer event
-        emit!(CrossChainTransferEvent {
+        send_gateway_message(ctx.accounts, gas_amount, serialized_message, Some(revert_options))?;
+        ctx.accounts.pending_transfer.dispatched = true;
+        close_source_token_account(ctx.accounts)?;
+
+        let destination_chain = format!("Chain-{}", destination_chain_id);
+        emit!(NFTBurned {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: ctx.accounts.nft_info.owner,
+            destination_chain: destination_chain.clone(),
+            destination_receiver: encode_hex(&recipient_address),
+            uri: ctx.accounts.nft_info.uri.clone(),
+        });
+        let clock = Clock::get()?;
+        emit_cpi!(CrossChainTransferEvent {
+            seq,
             token_id,
+            transfer_id,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
             from_chain: "Solana".to_string(),
-            to_chain: format!("Chain-{}", destination_chain_id),
+            to_chain: destination_chain,
             sender: *ctx.accounts.signer.key,
             receiver: recipient_address,
+            final_destination_chain: None,
+            final_receiver: Vec::new(),
         });
-        
-        msg!("NFT transferred cross-chain successfully via Gateway pattern");
-        msg!("Token ID: {}, Destination Chain: {}", token_id, destination_chain_id);
-        msg!("Recipient Address: {:?}", recipient_address);
-        
+
         Ok(())
     }
 
-// Cross-chain message types and data structures
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub enum MessageType {
-    Mint,
-    Burn,
-    Transfer,
-}
+    /// Transfer an NFT to another Solana wallet, keeping NFTInfo.owner in sync
+    pub fn transfer_local(ctx: Context<TransferLocal>, token_id: u64, new_owner: Pubkey) -> Result<()> {
+        let nft_info = &mut ctx.accounts.nft_info;
+        require!(!nft_info.semi_fungible, UniversalNFTError::SemiFungibleUnsupported);
+        require!(ctx.accounts.token_account.amount == 1, UniversalNFTError::NotOwner);
+        require!(!nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+        require!(!nft_info.soulbound, UniversalNFTError::Soulbound);
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct CrossChainMessage {
-    pub message_type: MessageType,
-    pub token_id: u64,
-    pub recipient_address: [u8; 20],
-    pub metadata_uri: String,
-}
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.token_account.to_account_info(),
+            to: ctx.accounts.destination_token_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, 1)?;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct CrossChainData {
-    pub destination_chain_id: u64,
-    pub recipient_address: [u8; 20],
-    pub transfer_timestamp: i64,
-}
+        let previous_owner = ctx.accounts.signer.key();
+        nft_info.owner = new_owner;
+        nft_info.operator = None;
+        nft_info.record_provenance(ProvenanceAction::Transfer, 0, new_owner)?;
 
-// ZetaChain Gateway integration structs
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub struct GatewayCallInstruction {
-    pub receiver: [u8; 20],
-    pub message: Vec<u8>,
-    pub revert_options: Option<RevertOptions>,
-}
+        ctx.accounts.from_owner_index.remove(token_id)?;
+        ctx.accounts.to_owner_index.insert(token_id)?;
 
-// Account contexts
+        emit!(NFTTransferred {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            from: previous_owner,
+            to: new_owner,
+        });
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
+        Ok(())
+    }
 
-    #[account(
-        init,
-        payer = signer,
-        space = 8 + size_of::<UniversalNFTState>(),
-        seeds = [b"universal_nft_state"],
-        bump
+    /// Approve an operator to bridge the NFT on the owner's behalf
+    pub fn approve_operator(ctx: Context<ManageOperator>, token_id: u64, operator: Pubkey) -> Result<()> {
+        require!(!ctx.accounts.nft_info.semi_fungible, UniversalNFTError::SemiFungibleUnsupported);
+        require!(ctx.accounts.token_account.amount == 1, UniversalNFTError::NotOwner);
+        require!(!ctx.accounts.nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+
+        let cpi_accounts = token::Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: ctx.accounts.operator_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::approve(cpi_ctx, 1)?;
+
+        let nft_info = &mut ctx.accounts.nft_info;
+        nft_info.owner = ctx.accounts.owner.key();
+        nft_info.operator = Some(operator);
+
+        emit!(OperatorApproved {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: nft_info.owner,
+            operator,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a previously approved operator
+    pub fn revoke_operator(ctx: Context<RevokeOperator>, token_id: u64) -> Result<()> {
+        let cpi_accounts = token::Revoke {
+            source: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::revoke(cpi_ctx)?;
+
+        ctx.accounts.nft_info.operator = None;
+
+        emit!(OperatorRevoked {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: ctx.accounts.owner.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Stake an NFT in place: the program PDA becomes its delegate and freezes it in the owner's wallet
+    pub fn stake_nft(ctx: Context<StakeNFT>, token_id: u64) -> Result<()> {
+        let nft_info = &mut ctx.accounts.nft_info;
+        require!(!nft_info.semi_fungible, UniversalNFTError::SemiFungibleUnsupported);
+        require!(ctx.accounts.token_account.amount == 1, UniversalNFTError::NotOwner);
+        require!(!nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+        require!(!nft_info.soulbound, UniversalNFTError::Soulbound);
+        require!(
+            ctx.accounts.mint.freeze_authority == COption::Some(ctx.accounts.pda.key()),
+            UniversalNFTError::NotFreezable
+        );
+        require!(!ctx.accounts.stake_info.is_staked, UniversalNFTError::AlreadyStaked);
+
+        // Delegating to the PDA replaces any operator approval
+        let cpi_accounts = token::Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: ctx.accounts.pda.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::approve(cpi_ctx, 1)?;
+        nft_info.owner = ctx.accounts.owner.key();
+        nft_info.operator = None;
+
+        let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+        let cpi_accounts = token::FreezeAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.pda.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[pda_seeds],
+        );
+        token::freeze_account(cpi_ctx)?;
+
+        let stake_info = &mut ctx.accounts.stake_info;
+        stake_info.token_id = token_id;
+        stake_info.owner = ctx.accounts.owner.key();
+        stake_info.is_staked = true;
+        stake_info.staked_at = Clock::get()?.unix_timestamp;
+
+        emit!(NFTStaked {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: stake_info.owner,
+            staked_at: stake_info.staked_at,
+        });
+
+        Ok(())
+    }
+
+    /// Unstake an NFT, thawing it and revoking the PDA delegate
+    pub fn unstake_nft(ctx: Context<UnstakeNFT>, token_id: u64) -> Result<()> {
+        require!(ctx.accounts.stake_info.is_staked, UniversalNFTError::NotStaked);
+
+        let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+        let cpi_accounts = token::ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.pda.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[pda_seeds],
+        );
+        token::thaw_account(cpi_ctx)?;
+
+        let cpi_accounts = token::Revoke {
+            source: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::revoke(cpi_ctx)?;
+
+        let stake_info = &mut ctx.accounts.stake_info;
+        let duration = Clock::get()?.unix_timestamp.saturating_sub(stake_info.staked_at).max(0) as u64;
+        stake_info.is_staked = false;
+        stake_info.total_staked_seconds =
+            stake_info.total_staked_seconds.checked_add(duration).ok_or(UniversalNFTError::CounterOverflow)?;
+        settle_stake_rewards(stake_info, duration)?;
+
+        emit!(NFTUnstaked {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: stake_info.owner,
+            duration,
+        });
+
+        Ok(())
+    }
+
+    /// Grant `renter` a time-boxed usage right until `expiry`, optionally delegating the token to them
+    pub fn rent_out(
+        ctx: Context<RentOut>,
+        token_id: u64,
+        renter: Pubkey,
+        expiry: i64,
+        delegate_token: bool,
+    ) -> Result<()> {
+        require!(!ctx.accounts.nft_info.semi_fungible, UniversalNFTError::SemiFungibleUnsupported);
+        require!(ctx.accounts.token_account.amount == 1, UniversalNFTError::NotOwner);
+        require!(!ctx.accounts.nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+        require!(expiry > Clock::get()?.unix_timestamp, UniversalNFTError::InvalidRentalExpiry);
+
+        if delegate_token {
+            let cpi_accounts = token::Approve {
+                to: ctx.accounts.token_account.to_account_info(),
+                delegate: ctx.accounts.renter_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token::approve(cpi_ctx, 1)?;
+        }
+
+        let rental = &mut ctx.accounts.rental;
+        rental.token_id = token_id;
+        rental.owner = ctx.accounts.owner.key();
+        rental.renter = renter;
+        rental.expiry = expiry;
+        rental.delegated = delegate_token;
+
+        emit!(NFTRented {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: rental.owner,
+            renter,
+            expiry,
+        });
+
+        Ok(())
+    }
+
+    /// End a rental once it has expired, or early with the renter's co-signature
+    pub fn end_rental(ctx: Context<EndRental>, token_id: u64) -> Result<()> {
+        let rental = &ctx.accounts.rental;
+        let renter_signed = ctx
+            .accounts
+            .renter
+            .as_ref()
+            .is_some_and(|renter| renter.key() == rental.renter);
+        require!(
+            renter_signed || Clock::get()?.unix_timestamp >= rental.expiry,
+            UniversalNFTError::RentalActive
+        );
+
+        if rental.delegated {
+            let cpi_accounts = token::Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token::revoke(cpi_ctx)?;
+        }
+
+        emit!(RentalEnded {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: rental.owner,
+            renter: rental.renter,
+        });
+
+        Ok(())
+    }
+
+    /// Set or overwrite an on-chain attribute (update authority only)
+    pub fn set_attribute(
+        ctx: Context<SetAttribute>,
+        token_id: u64,
+        key: String,
+        value: String,
+    ) -> Result<()> {
+        require!(
+            !key.is_empty() && key.len() <= MAX_ATTRIBUTE_KEY_LEN,
+            UniversalNFTError::InvalidAttribute
+        );
+        require!(value.len() <= MAX_ATTRIBUTE_VALUE_LEN, UniversalNFTError::InvalidAttribute);
+        check_update_authority(
+            &ctx.accounts.universal_nft_state,
+            &ctx.accounts.nft_info,
+            ctx.accounts.collection.as_ref(),
+            &ctx.accounts.authority,
+        )?;
+
+        let attributes = &mut ctx.accounts.attributes;
+        attributes.token_id = token_id;
+        match attributes.entries.iter_mut().find(|entry| entry.key == key) {
+            Some(entry) => entry.value = value,
+            None => {
+                require!(
+                    attributes.entries.len() < MAX_ATTRIBUTES,
+                    UniversalNFTError::TooManyAttributes
+                );
+                attributes.entries.push(Attribute { key, value });
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove an on-chain attribute (update authority only)
+    pub fn clear_attribute(ctx: Context<ClearAttribute>, _token_id: u64, key: String) -> Result<()> {
+        check_update_authority(
+            &ctx.accounts.universal_nft_state,
+            &ctx.accounts.nft_info,
+            ctx.accounts.collection.as_ref(),
+            &ctx.accounts.authority,
+        )?;
+
+        let entries = &mut ctx.accounts.attributes.entries;
+        let position = entries
+            .iter()
+            .position(|entry| entry.key == key)
+            .ok_or(UniversalNFTError::InvalidAttribute)?;
+        entries.remove(position);
+        Ok(())
+    }
+
+    /// Handle incoming cross-chain calls from ZetaChain
+    /// Official signature from ZetaChain documentation
+    pub fn on_call(
+        ctx: Context<OnCall>,
+        amount: u64,
+        sender: [u8; 20],
+        data: Vec<u8>,
+    ) -> Result<()> {
+        // Only the Gateway may deliver calls; `amount` is what it credited just before invoking
+        require_gateway_caller(&ctx.accounts.instruction_sysvar, &ctx.accounts.config.gateway_program)?;
+        require!(!ctx.accounts.config.paused, UniversalNFTError::BridgePaused);
+
+        // EVM senders ABI-encode the transfer with a bytes32 receiver; anything else is a
+        // Borsh envelope
+        let envelope = match decode_abi_transfer(&data, sender) {
+            Some(transfer_data) => Envelope::Mint(transfer_data),
+            None => decode_inbound_envelope(&data)?,
+        };
+
+        // Gas change deposited with the call goes to the NFT's receiver, or to the treasury
+        let receiver = match &envelope {
+            Envelope::Mint(transfer_data) => Some(transfer_data.receiver),
+            Envelope::CompactMint(compact) => Some(compact.receiver),
+            Envelope::Burn(_) | Envelope::MetadataUpdate(_) => None,
+        };
+        forward_inbound_deposit(ctx.accounts, ctx.bumps.pda, amount, receiver)?;
+
+        dispatch_envelope(ctx, sender, envelope)
+    }
+
+    /// LayerZero inbound delivery, the `lz_receive` counterpart of `on_call`
+    ///
+    /// The message must come from the registered peer of a LayerZero-routed chain. Clearing it
+    /// through the endpoint verifies the payload and prevents replay; the remaining accounts are
+    /// the endpoint's `clear` accounts. The payload uses the same envelope as `on_call`.
+    pub fn lz_receive<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LzReceive<'info>>,
+        params: LzReceiveParams,
+    ) -> Result<()> {
+        require!(!ctx.accounts.inbound.config.paused, UniversalNFTError::BridgePaused);
+
+        invoke_layerzero_endpoint(
+            &ctx.accounts.layerzero_endpoint,
+            ctx.bumps.lz_oapp,
+            ctx.remaining_accounts,
+            "clear",
+            LzClearParams {
+                receiver: ctx.accounts.lz_oapp.key(),
+                src_eid: params.src_eid,
+                sender: params.sender,
+                nonce: params.nonce,
+                guid: params.guid,
+                message: params.message.clone(),
+            },
+        )?;
+
+        // Inbound events name the peer by its trailing 20 bytes, the EVM address form
+        let mut sender = [0u8; 20];
+        sender.copy_from_slice(&params.sender[12..]);
+        let inbound = Context::new(ctx.program_id, &mut ctx.accounts.inbound, &[], ctx.bumps.inbound);
+        receive_envelope(inbound, sender, &params.message)
+    }
+
+    /// Claim an inbound NFT held in escrow by the program PDA into the receiver's wallet
+    pub fn claim_nft(ctx: Context<ClaimNFT>, token_id: u64) -> Result<()> {
+        let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.pda_ata.to_account_info(),
+            to: ctx.accounts.receiver_token_account.to_account_info(),
+            authority: ctx.accounts.pda.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[pda_seeds],
+        );
+        token::transfer(cpi_ctx, ctx.accounts.pending_claim.amount)?;
+
+        emit!(NFTClaimed {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            receiver: ctx.accounts.receiver.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Handle transaction reverts from ZetaChain
+    /// Official signature from ZetaChain documentation
+    pub fn on_revert(
+        ctx: Context<OnRevert>,
+        amount: u64,        // Asset quantity originally deposited (lamports or SPL)
+        sender: Pubkey,     // The account that triggered the deposit/call from Solana
+        data: Vec<u8>,      // The revert_message: the Borsh-encoded CrossChainNFTTransfer
+    ) -> Result<()> {
+        // Only the Gateway may report a revert; it decides what is refunded and restored
+        require_gateway_caller(&ctx.accounts.instruction_sysvar, &ctx.accounts.config.gateway_program)?;
+
+        // Handle the revert scenario
+        // This could involve refunding tokens, updating state, or emitting events
+        debug_msg!(ctx.accounts.config, "Cross-chain transaction reverted for PDA: {}", ctx.accounts.pda.key());
+        debug_msg!(ctx.accounts.config, "Original sender: {}", sender);
+        debug_msg!(ctx.accounts.config, "Reverted amount: {}", amount);
+        
+        // The Gateway returns the reverted deposit to the program PDA; pass it on to the sender
+        if amount > 0 {
+            let owner = ctx
+                .accounts
+                .owner
+                .as_ref()
+                .filter(|owner| owner.key() == sender)
+                .ok_or(UniversalNFTError::InvalidRefundAccounts)?;
+            let mint = match (
+                ctx.accounts.pda_token_account.as_ref(),
+                ctx.accounts.owner_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) {
+                (Some(from), Some(to), Some(token_program)) => {
+                    require!(
+                        to.mint == from.mint && to.owner == sender,
+                        UniversalNFTError::InvalidRefundAccounts
+                    );
+                    let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+                    let cpi_accounts = token::Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.pda.to_account_info(),
+                    };
+                    let cpi_ctx = CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        cpi_accounts,
+                        &[pda_seeds],
+                    );
+                    token::transfer(cpi_ctx, amount)?;
+                    Some(from.mint)
+                }
+                (None, None, _) => {
+                    debit_program_funds(&ctx.accounts.pda.to_account_info(), amount)?;
+                    owner.add_lamports(amount)?;
+                    None
+                }
+                _ => return err!(UniversalNFTError::InvalidRefundAccounts),
+            };
+            emit_cpi!(RevertRefunded {
+                seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+                owner: sender,
+                mint,
+                amount,
+            });
+        }
+        
+        // Attempt to decode the original transfer data if possible; the transfer's records were
+        // located by the transfer id it carries
+        let transfer_id = revert_target(&data).transfer_id;
+        if let Ok(transfer_data) = CrossChainNFTTransfer::deserialize(&mut &data[..]) {
+            debug_msg!(ctx.accounts.config, "Reverted NFT transfer for token_id: {}", transfer_data.token_id);
+
+            if let Some(chain_stats) = ctx.accounts.chain_stats.as_mut() {
+                chain_stats.reverted_count =
+                    chain_stats.reverted_count.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+            }
+
+            if let Some(pending_transfer) = ctx.accounts.pending_transfer.as_mut() {
+                require!(
+                    pending_transfer.transfer_id == transfer_id
+                        && pending_transfer.status == TransferStatus::Initiated,
+                    UniversalNFTError::TransferNotPending
+                );
+                pending_transfer.status = TransferStatus::Reverted;
+                pending_transfer.updated_slot = Clock::get()?.slot;
+            }
+
+            if let Some(transfer_record) = ctx.accounts.transfer_record.as_mut() {
+                require!(
+                    transfer_record.status == TransferStatus::Initiated,
+                    UniversalNFTError::TransferNotPending
+                );
+                transfer_record.status = TransferStatus::Reverted;
+                transfer_record.updated_slot = Clock::get()?.slot;
+            }
+
+            // The revert message names the holder whose units left, which for a semi-fungible
+            // need not be the NFTInfo owner
+            if let Some(nft_info) = ctx.accounts.nft_info.as_mut() {
+                let chain_id = revert_target(&data).destination_chain_id;
+                nft_info.record_provenance(ProvenanceAction::Revert, chain_id, transfer_data.receiver)?;
+            }
+            
+            // The burned NFT is restored to its owner by reclaim_expired
+            
+            let seq = ctx.accounts.universal_nft_state.next_event_seq()?;
+            let clock = Clock::get()?;
+            emit_cpi!(CrossChainTransferReverted {
+                seq,
+                token_id: transfer_data.token_id,
+                transfer_id,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+                original_sender: sender,
+                reverted_amount: amount,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Transfer NFT cross-chain using ZetaChain Gateway
+    pub fn transfer_cross_chain(
+        ctx: Context<TransferCrossChain>,
+        token_id: u64,
+        recipient_address: Vec<u8>, // Receiver encoded per the destination chain's format
+        destination_chain_id: u64,
+        metadata_uri: String,
+        on_revert_gas_limit: Option<u64>,
+        revert_params: Option<RevertParams>,
+        gas_amount: Option<u64>,
+        recipient_checksum: Option<String>,
+        extra_data: Option<Vec<u8>>, // Opaque caller context carried to the destination
+        final_destination: Option<FinalDestination>, // Onward hop from the destination chain
+        amount: Option<u64>, // Units of a semi-fungible to send; defaults to 1
+    ) -> Result<()> {
+        let OutboundTransfer {
+            serialized_message,
+            revert_options,
+            seq,
+            transfer_id,
+            recipient_address,
+        } = prepare_outbound_transfer(
+            ctx.accounts,
+            OutboundAuthority::Signer,
+            token_id,
+            recipient_address,
+            destination_chain_id,
+            metadata_uri,
+            on_revert_gas_limit,
+            revert_params,
+            recipient_checksum,
+            extra_data.unwrap_or_default(),
+            final_destination.clone(),
+            amount.unwrap_or(1),
+            Transport::Gateway,
+        )?;
+        let revert_options = Some(revert_options);
+        
+        let gas_amount = gas_amount.unwrap_or(0);
+        debug_msg!(ctx.accounts.config, "Gas deposit: {} lamports, message: {} bytes", gas_amount, serialized_message.len());
+        // The NFT travels in the message; SOL is deposited only to prepay destination gas
+        send_gateway_message(ctx.accounts, gas_amount, serialized_message, revert_options)?;
+        ctx.accounts.pending_transfer.dispatched = true;
+
+        // Reclaim rent from the now-empty source token account
+        close_source_token_account(ctx.accounts)?;
+
+        // Emit cross-chain transfer event
+        let clock = Clock::get()?;
+        emit_cpi!(CrossChainTransferEvent {
+            seq,
+            token_id,
+            transfer_id,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+            from_chain: "Solana".to_string(),
+            to_chain: format!("Chain-{}", destination_chain_id),
+            sender: *ctx.accounts.signer.key,
+            receiver: recipient_address,
+            final_destination_chain: final_destination.as_ref().map(|hop| hop.chain_id),
+            final_receiver: final_destination.map(|hop| hop.receiver).unwrap_or_default(),
+        });
+        debug_msg!(ctx.accounts.config, "Token {} sent to chain {}", token_id, destination_chain_id);
+
+        Ok(())
+    }
+
+    /// Transfer NFT cross-chain, paying destination gas with a whitelisted SPL token
+    pub fn transfer_cross_chain_with_spl_gas(
+        ctx: Context<TransferCrossChainWithSplGas>,
+        token_id: u64,
+        recipient_address: Vec<u8>,
+        destination_chain_id: u64,
+        metadata_uri: String,
+        on_revert_gas_limit: Option<u64>,
+        revert_params: Option<RevertParams>,
+        gas_amount: u64,
+        recipient_checksum: Option<String>,
+    ) -> Result<()> {
+        // Gas token must be accepted by the destination chain's registry entry
+        let gas_mint = ctx.accounts.gas_mint.key();
+        let chain_config = load_enabled_chain_config(&ctx.accounts.transfer.chain_config)?;
+        require!(
+            chain_config.accepts_gas_token(&gas_mint),
+            UniversalNFTError::UnsupportedGasToken
+        );
+        require!(gas_amount > 0, UniversalNFTError::InvalidGasAmount);
+
+        let OutboundTransfer {
+            serialized_message,
+            revert_options,
+            seq,
+            transfer_id,
+            ..
+        } = prepare_outbound_transfer(
+            &mut ctx.accounts.transfer,
+            OutboundAuthority::Signer,
+            token_id,
+            recipient_address.clone(),
+            destination_chain_id,
+            metadata_uri,
+            on_revert_gas_limit,
+            revert_params,
+            recipient_checksum,
+            Vec::new(),
+            None,
+            1,
+            Transport::Gateway,
+        )?;
+
+        // Deposit the SPL gas token alongside the message
+        let transfer = &ctx.accounts.transfer;
+        let gateway_cpi_accounts = gateway::cpi::accounts::DepositSplToken {
+            signer: transfer.signer.to_account_info(),
+            pda: transfer.gateway_pda.to_account_info(),
+            whitelist_entry: ctx.accounts.gas_whitelist_entry.to_account_info(),
+            mint_account: ctx.accounts.gas_mint.to_account_info(),
+            token_program: transfer.token_program.to_account_info(),
+            from: ctx.accounts.gas_token_account.to_account_info(),
+            to: ctx.accounts.gas_gateway_token_account.to_account_info(),
+            system_program: transfer.system_program.to_account_info(),
+        };
+
+        let gateway_cpi_ctx = CpiContext::new(
+            transfer.gateway_program.to_account_info(),
+            gateway_cpi_accounts,
+        );
+
+        gateway::cpi::deposit_spl_token_and_call(
+            gateway_cpi_ctx,
+            gas_amount,
+            transfer.config.universal_contract,
+            serialized_message,
+            Some(revert_options),
+        )?;
+
+        debug_msg!(ctx.accounts.transfer.config, "Gas deposit: {} of SPL token {}", gas_amount, gas_mint);
+        ctx.accounts.transfer.pending_transfer.dispatched = true;
+
+        // Reclaim rent from the now-empty source token account
+        close_source_token_account(transfer)?;
+
+        let clock = Clock::get()?;
+        emit_cpi_nested(
+            &transfer.event_authority,
+            ctx.bumps.transfer.event_authority,
+            &CrossChainTransferEvent {
+                seq,
+                token_id,
+                transfer_id,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+                from_chain: "Solana".to_string(),
+                to_chain: format!("Chain-{}", destination_chain_id),
+                sender: transfer.signer.key(),
+                receiver: recipient_address,
+                final_destination_chain: None,
+                final_receiver: Vec::new(),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Dispatch a queued transfer ahead of its turn in the outbound queue
+    ///
+    /// Permissionless; the Gateway's gas deposit is paid from the transfer's escrow, so the
+    /// cranker only pays the transaction fee. `dispatch_next` later skips the transfer.
+    pub fn retry_dispatch(ctx: Context<RetryDispatch>, transfer_id: [u8; 32]) -> Result<()> {
+        let pending_info = ctx.accounts.pending_transfer.to_account_info();
+        dispatch_pending_transfer(
+            &mut ctx.accounts.pending_transfer,
+            &pending_info,
+            &ctx.accounts.cranker.to_account_info(),
+            &ctx.accounts.gateway_pda,
+            &ctx.accounts.gateway_program,
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.config.universal_contract,
+        )?;
+
+        emit!(TransferDispatched {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id: ctx.accounts.pending_transfer.token_id,
+            transfer_id,
+            cranker: ctx.accounts.cranker.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Burn an NFT and queue its Gateway call for a later `dispatch_next`
+    ///
+    /// The SOL gas deposit is escrowed in the transfer's PendingTransfer account.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_cross_chain_queued(
+        ctx: Context<TransferCrossChainQueued>,
+        token_id: u64,
+        recipient_address: Vec<u8>,
+        destination_chain_id: u64,
+        metadata_uri: String,
+        on_revert_gas_limit: Option<u64>,
+        revert_params: Option<RevertParams>,
+        gas_amount: u64,
+        recipient_checksum: Option<String>,
+    ) -> Result<()> {
+        require!(gas_amount > 0, UniversalNFTError::InvalidGasAmount);
+
+        let OutboundTransfer { seq, transfer_id, .. } = prepare_outbound_transfer(
+            &mut ctx.accounts.transfer,
+            OutboundAuthority::Signer,
+            token_id,
+            recipient_address.clone(),
+            destination_chain_id,
+            metadata_uri,
+            on_revert_gas_limit,
+            revert_params,
+            recipient_checksum,
+            Vec::new(),
+            None,
+            1,
+            Transport::Gateway,
+        )?;
+
+        // Escrow the destination gas deposit until dispatch
+        let escrow_accounts = system_program::Transfer {
+            from: ctx.accounts.transfer.signer.to_account_info(),
+            to: ctx.accounts.transfer.pending_transfer.to_account_info(),
+        };
+        let escrow_ctx = CpiContext::new(ctx.accounts.transfer.system_program.to_account_info(), escrow_accounts);
+        system_program::transfer(escrow_ctx, gas_amount)?;
+        ctx.accounts.transfer.pending_transfer.gas_escrow = gas_amount;
+
+        let outbound_queue = &mut ctx.accounts.outbound_queue;
+        ctx.accounts.queue_entry.position = outbound_queue.tail;
+        ctx.accounts.queue_entry.token_id = token_id;
+        ctx.accounts.queue_entry.transfer_id = transfer_id;
+        outbound_queue.tail = outbound_queue.tail.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+
+        close_source_token_account(&ctx.accounts.transfer)?;
+
+        let clock = Clock::get()?;
+        emit_cpi_nested(
+            &ctx.accounts.transfer.event_authority,
+            ctx.bumps.transfer.event_authority,
+            &CrossChainTransferEvent {
+                seq,
+                token_id,
+                transfer_id,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+                from_chain: "Solana".to_string(),
+                to_chain: format!("Chain-{}", destination_chain_id),
+                sender: ctx.accounts.transfer.signer.key(),
+                receiver: recipient_address,
+                final_destination_chain: None,
+                final_receiver: Vec::new(),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Dispatch the oldest queued transfer through the Gateway (permissionless)
+    ///
+    /// Transfers reclaimed or already dispatched before their turn are skipped. The queue
+    /// entry's rent is paid to the cranker.
+    pub fn dispatch_next(ctx: Context<DispatchNext>) -> Result<()> {
+        let token_id = ctx.accounts.queue_entry.token_id;
+        let outbound_queue = &mut ctx.accounts.outbound_queue;
+        outbound_queue.head = outbound_queue.head.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+
+        let pending_info = ctx.accounts.pending_transfer.to_account_info();
+        if pending_info.owner != ctx.program_id || pending_info.data_is_empty() {
+            debug_msg!(ctx.accounts.config, "Skipping token {}: transfer no longer pending", token_id);
+            return Ok(());
+        }
+        let mut pending_transfer = PendingTransfer::try_deserialize(&mut &pending_info.try_borrow_data()?[..])?;
+        if pending_transfer.dispatched || pending_transfer.status != TransferStatus::Initiated {
+            debug_msg!(ctx.accounts.config, "Skipping token {}: transfer already handled", token_id);
+            return Ok(());
+        }
+
+        dispatch_pending_transfer(
+            &mut pending_transfer,
+            &pending_info,
+            &ctx.accounts.cranker.to_account_info(),
+            &ctx.accounts.gateway_pda,
+            &ctx.accounts.gateway_program,
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.config.universal_contract,
+        )?;
+        pending_transfer.try_serialize(&mut &mut pending_info.try_borrow_mut_data()?[..])?;
+
+        emit!(TransferDispatched {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            transfer_id: pending_transfer.transfer_id,
+            cranker: ctx.accounts.cranker.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Mark an outbound transfer as delivered and refund its tracking rent (authority only)
+    pub fn confirm_transfer(ctx: Context<ConfirmTransfer>, transfer_id: [u8; 32]) -> Result<()> {
+        let transfer_record = &mut ctx.accounts.transfer_record;
+        transfer_record.status = TransferStatus::Confirmed;
+        transfer_record.updated_slot = Clock::get()?.slot;
+
+        let pending_transfer = &ctx.accounts.pending_transfer;
+        emit!(TransferConfirmed {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id: pending_transfer.token_id,
+            owner: pending_transfer.owner,
+            transfer_id,
+        });
+        Ok(())
+    }
+
+    /// Restore an NFT whose outbound transfer was reverted or never confirmed in time
+    pub fn reclaim_expired(ctx: Context<ReclaimExpired>, transfer_id: [u8; 32]) -> Result<()> {
+        let pending_transfer = &ctx.accounts.pending_transfer;
+        let token_id = pending_transfer.token_id;
+        let current_slot = Clock::get()?.slot;
+        let expired = current_slot
+            >= pending_transfer
+                .initiated_slot
+                .saturating_add(ctx.accounts.config.transfer_timeout_slots);
+
+        // Only a revert reported by the Gateway, or a timeout with no outcome recorded, releases
+        // the NFT again; a delivered transfer must never be minted back
+        let transfer_record = &mut ctx.accounts.transfer_record;
+        require!(
+            matches!(transfer_record.status, TransferStatus::Initiated | TransferStatus::Reverted),
+            UniversalNFTError::TransferNotPending
+        );
+        let reverted = pending_transfer.status == TransferStatus::Reverted;
+        require!(
+            reverted || (expired && pending_transfer.status == TransferStatus::Initiated),
+            UniversalNFTError::TransferNotExpired
+        );
+
+        // Settle a record the revert did not reach; otherwise the transfer ran out of time
+        if transfer_record.status == TransferStatus::Initiated {
+            transfer_record.status = if reverted {
+                TransferStatus::Reverted
+            } else {
+                TransferStatus::Expired
+            };
+            transfer_record.updated_slot = current_slot;
+        }
+
+        // Re-mint the burned units back to their owner
+        let restored = ctx.accounts.nft_info.is_burned;
+        if ctx.accounts.nft_info.semi_fungible {
+            require!(ctx.accounts.mint.decimals == 0, UniversalNFTError::InvalidNftSupply);
+        } else {
+            require_nft_supply(&ctx.accounts.mint, 0)?;
+        }
+        let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.pda.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[pda_seeds],
+        );
+        mint_to(cpi_ctx, pending_transfer.amount)?;
+
+        // A semi-fungible with units left on Solana never left the supply; its units have many
+        // holders, so it keeps no single owner
+        if restored {
+            let nft_info = &mut ctx.accounts.nft_info;
+            if !nft_info.semi_fungible {
+                nft_info.owner = ctx.accounts.owner.key();
+            }
+            nft_info.is_burned = false;
+            nft_info.burned_slot = 0;
+            nft_info.cross_chain_data = None;
+
+            if !nft_info.semi_fungible {
+                ctx.accounts.owner_index.insert(token_id)?;
+            }
+            ctx.accounts.supply_index.insert(token_id)?;
+            ctx.accounts.universal_nft_state.increase_supply(1)?;
+        }
+
+        emit!(TransferReclaimed {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: ctx.accounts.owner.key(),
+            transfer_id,
+        });
+
+        Ok(())
+    }
+
+    /// Close the NFTInfo account of a destroyed NFT, refunding rent to its owner
+    ///
+    /// A bridged-out NFT is also burned but keeps its `cross_chain_data` until it returns or is
+    /// reclaimed, and its NFTInfo is needed for either, so it cannot be closed. The mint account
+    /// itself is left in place: SPL Token mints cannot be closed.
+    pub fn close_burned(ctx: Context<CloseBurned>, token_id: u64) -> Result<()> {
+        debug_msg!(ctx.accounts.config, "Closed NFTInfo for burned token_id: {}", token_id);
+        Ok(())
+    }
+
+    /// Permissionless crank closing a long-destroyed NFTInfo, splitting its rent
+    /// between the original owner and the cranker
+    ///
+    /// Like `close_burned`, never closes the NFTInfo of an NFT that was bridged out.
+    pub fn sweep_burned(ctx: Context<SweepBurned>, token_id: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let current_slot = Clock::get()?.slot;
+        require!(
+            current_slot.saturating_sub(ctx.accounts.nft_info.burned_slot) >= config.sweep_min_age_slots,
+            UniversalNFTError::SweepTooEarly
+        );
+
+        let nft_info_account = ctx.accounts.nft_info.to_account_info();
+        let reward = (nft_info_account.lamports() as u128 * config.sweep_reward_bps as u128
+            / MAX_BPS as u128) as u64;
+        nft_info_account.sub_lamports(reward)?;
+        ctx.accounts.cranker.add_lamports(reward)?;
+
+        let refund = nft_info_account.lamports();
+        ctx.accounts.nft_info.close(ctx.accounts.owner.to_account_info())?;
+
+        emit!(BurnedNFTSwept {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            owner: ctx.accounts.owner.key(),
+            cranker: ctx.accounts.cranker.key(),
+            owner_refund: refund,
+            cranker_reward: reward,
+        });
+
+        Ok(())
+    }
+
+    /// Update the metadata URI of an NFT, growing its account if needed (authority only)
+    pub fn update_metadata(ctx: Context<UpdateMetadata>, token_id: u64, uri: String) -> Result<()> {
+        require!(
+            uri.len() <= ctx.accounts.config.max_uri_len as usize,
+            UniversalNFTError::UriTooLong
+        );
+
+        let nft_info = &mut ctx.accounts.nft_info;
+        require!(!nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+        nft_info.uri = uri;
+        // Units of a semi-fungible abroad are expected back with the updated metadata
+        if nft_info.metadata_hash != [0; 32] {
+            nft_info.metadata_hash = metadata_hash(&nft_info.name, &nft_info.symbol, &nft_info.uri);
+        }
+
+        // Point the Metaplex metadata wallets read at the new URI, keeping everything else
+        let metadata = &ctx.accounts.metadata;
+        let data_v2 = DataV2 {
+            name: nft_info.name.clone(),
+            symbol: nft_info.symbol.clone(),
+            uri: nft_info.uri.clone(),
+            seller_fee_basis_points: metadata.seller_fee_basis_points,
+            creators: metadata.creators.clone(),
+            collection: metadata.collection.clone(),
+            uses: metadata.uses.clone(),
+        };
+        let cpi_accounts = UpdateMetadataAccountsV2 {
+            metadata: metadata.to_account_info(),
+            update_authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.metadata_program.to_account_info(), cpi_accounts);
+        update_metadata_accounts_v2(cpi_ctx, None, Some(data_v2), None, None)?;
+
+        emit!(NFTMetadataUpdated {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            uri: nft_info.uri.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Broadcast an NFT's current name and URI to its copy on a connected chain (update authority only)
+    pub fn send_metadata_update(
+        ctx: Context<SendMetadataUpdate>,
+        token_id: u64,
+        destination_chain_id: u64,
+        gas_amount: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, UniversalNFTError::BridgePaused);
+        require!(
+            destination_chain_id != ctx.accounts.config.self_chain_id,
+            UniversalNFTError::SelfChainDestination
+        );
+        load_enabled_chain_config(&ctx.accounts.chain_config)?;
+        check_update_authority(
+            &ctx.accounts.universal_nft_state,
+            &ctx.accounts.nft_info,
+            ctx.accounts.collection.as_ref(),
+            &ctx.accounts.authority,
+        )?;
+
+        let nft_info = &ctx.accounts.nft_info;
+        let message = encode_envelope(
+            MessageType::MetadataUpdate,
+            &CrossChainMetadataUpdate {
+                token_id,
+                name: nft_info.name.clone(),
+                uri: nft_info.uri.clone(),
+            },
+        )
+        .map_err(|_| UniversalNFTError::SerializationError)?;
+
+        let gateway_cpi_accounts = gateway::cpi::accounts::Deposit {
+            signer: ctx.accounts.authority.to_account_info(),
+            pda: ctx.accounts.gateway_pda.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let gateway_cpi_ctx = CpiContext::new(
+            ctx.accounts.gateway_program.to_account_info(),
+            gateway_cpi_accounts,
+        );
+        gateway::cpi::deposit_and_call(
+            gateway_cpi_ctx,
+            gas_amount,
+            ctx.accounts.config.universal_contract,
+            message,
+            None,
+        )?;
+
+        emit!(MetadataUpdateSent {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            token_id,
+            destination_chain_id,
+            uri: ctx.accounts.nft_info.uri.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Set or lower the max supply (authority only); it can never be raised or removed
+    pub fn set_max_supply(ctx: Context<SetMaxSupply>, max_supply: u64) -> Result<()> {
+        let universal_nft_state = &mut ctx.accounts.universal_nft_state;
+        require!(
+            max_supply >= universal_nft_state.total_supply,
+            UniversalNFTError::MaxSupplyReached
+        );
+        if let Some(current) = universal_nft_state.max_supply {
+            require!(max_supply <= current, UniversalNFTError::MaxSupplyLocked);
+        }
+        universal_nft_state.max_supply = Some(max_supply);
+
+        emit!(MaxSupplySet {
+            seq: universal_nft_state.next_event_seq()?,
+            authority: ctx.accounts.authority.key(),
+            max_supply,
+        });
+        Ok(())
+    }
+
+    /// Create a collection owned by the signer, with its own authority, supply and bridge mode
+    pub fn create_collection(ctx: Context<CreateCollection>, params: CollectionParams) -> Result<()> {
+        require_gte!(MAX_NAME_LEN, params.name.len(), UniversalNFTError::NameTooLong);
+        require_gte!(MAX_SYMBOL_LEN, params.symbol.len(), UniversalNFTError::SymbolTooLong);
+        if let Some(royalty) = &params.royalty {
+            require!(royalty.basis_points <= MAX_BPS, UniversalNFTError::InvalidBasisPoints);
+        }
+
+        let universal_nft_state = &mut ctx.accounts.universal_nft_state;
+        let collection = &mut ctx.accounts.collection;
+        collection.collection_id = universal_nft_state.next_collection_id;
+        collection.authority = ctx.accounts.authority.key();
+        collection.name = params.name;
+        collection.symbol = params.symbol;
+        collection.supply = 0;
+        collection.max_supply = params.max_supply;
+        collection.bridge_mode = params.bridge_mode;
+        collection.royalty = params.royalty;
+        universal_nft_state.next_collection_id = universal_nft_state
+            .next_collection_id
+            .checked_add(1)
+            .ok_or(UniversalNFTError::CounterOverflow)?;
+
+        emit!(CollectionCreated {
+            seq: universal_nft_state.next_event_seq()?,
+            collection_id: collection.collection_id,
+            collection: collection.key(),
+            authority: collection.authority,
+        });
+
+        Ok(())
+    }
+
+    /// Update a collection (collection authority only)
+    pub fn update_collection(ctx: Context<UpdateCollection>, params: UpdateCollectionParams) -> Result<()> {
+        let collection = &mut ctx.accounts.collection;
+
+        if let Some(authority) = params.authority {
+            emit!(CollectionAuthorityTransferred {
+                seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+                collection: collection.key(),
+                previous_authority: collection.authority,
+                new_authority: authority,
+            });
+            collection.authority = authority;
+        }
+        if let Some(max_supply) = params.max_supply {
+            require!(max_supply >= collection.supply, UniversalNFTError::MaxSupplyReached);
+            collection.max_supply = Some(max_supply);
+        }
+        if let Some(bridge_mode) = params.bridge_mode {
+            collection.bridge_mode = bridge_mode;
+        }
+        if let Some(royalty) = params.royalty {
+            require!(royalty.basis_points <= MAX_BPS, UniversalNFTError::InvalidBasisPoints);
+            collection.royalty = Some(royalty);
+        }
+
+        Ok(())
+    }
+
+    /// Map a collection to its counterpart contract on a connected chain (collection authority only)
+    pub fn set_collection_address(
+        ctx: Context<SetCollectionAddress>,
+        chain_id: u64,
+        address: Vec<u8>,
+    ) -> Result<()> {
+        let chain_config = load_enabled_chain_config(&ctx.accounts.chain_config)?;
+        chain_config.validate_receiver(&address)?;
+
+        let collection_address = &mut ctx.accounts.collection_address;
+        collection_address.collection = ctx.accounts.collection.key();
+        collection_address.chain_id = chain_id;
+        collection_address.address = address;
+        Ok(())
+    }
+
+    /// Cap how many of a collection's NFTs can bridge out per epoch (collection authority only)
+    ///
+    /// Zero lifts the cap.
+    pub fn set_collection_outbound_cap(
+        ctx: Context<SetCollectionOutboundCap>,
+        max_outbound_per_epoch: u32,
+    ) -> Result<()> {
+        let collection_config = &mut ctx.accounts.collection_config;
+        collection_config.collection = ctx.accounts.collection.key();
+        collection_config.max_outbound_per_epoch = max_outbound_per_epoch;
+        Ok(())
+    }
+
+    /// Record a metadata URI under its keccak256 hash so compact inbound messages can resolve it
+    ///
+    /// Entries are content-addressed, so anyone may register one.
+    pub fn register_uri(ctx: Context<RegisterUri>, uri: String) -> Result<()> {
+        require_gte!(MAX_URI_LEN, uri.len(), UniversalNFTError::UriTooLong);
+        ctx.accounts.uri_registry.uri = uri;
+        Ok(())
+    }
+
+    /// Update program configuration (authority only)
+    pub fn update_config(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let universal_nft_state = &mut ctx.accounts.universal_nft_state;
+        emit!(ConfigUpdated {
+            seq: universal_nft_state.next_event_seq()?,
+            authority,
+            params: params.clone(),
+        });
+        let config = &mut ctx.accounts.config;
+
+        if let Some(gateway_program) = params.gateway_program {
+            config.gateway_program = gateway_program;
+        }
+        if let Some(max_on_revert_gas_limit) = params.max_on_revert_gas_limit {
+            config.max_on_revert_gas_limit = max_on_revert_gas_limit;
+        }
+        if let Some(default_on_revert_gas_limit) = params.default_on_revert_gas_limit {
+            config.default_on_revert_gas_limit = default_on_revert_gas_limit;
+        }
+        require!(
+            config.default_on_revert_gas_limit <= config.max_on_revert_gas_limit,
+            UniversalNFTError::RevertGasLimitTooHigh
+        );
+        if let Some(abort_address) = params.abort_address {
+            config.abort_address = abort_address;
+        }
+        if let Some(bridge_fee_lamports) = params.bridge_fee_lamports {
+            config.bridge_fee_lamports = bridge_fee_lamports;
+        }
+        if let Some(mint_fee_lamports) = params.mint_fee_lamports {
+            config.mint_fee_lamports = mint_fee_lamports;
+        }
+        if let Some(universal_contract) = params.universal_contract {
+            config.universal_contract = universal_contract;
+        }
+        if let Some(sweep_min_age_slots) = params.sweep_min_age_slots {
+            config.sweep_min_age_slots = sweep_min_age_slots;
+        }
+        if let Some(sweep_reward_bps) = params.sweep_reward_bps {
+            require!(sweep_reward_bps <= MAX_BPS, UniversalNFTError::InvalidBasisPoints);
+            config.sweep_reward_bps = sweep_reward_bps;
+        }
+        if let Some(transfer_timeout_slots) = params.transfer_timeout_slots {
+            config.transfer_timeout_slots = transfer_timeout_slots;
+        }
+        if let Some(max_transfers_per_epoch) = params.max_transfers_per_epoch {
+            config.max_transfers_per_epoch = max_transfers_per_epoch;
+        }
+        if let Some(paused) = params.paused {
+            config.paused = paused;
+            emit!(BridgePauseSet {
+                seq: universal_nft_state.next_event_seq()?,
+                authority,
+                paused,
+            });
+        }
+        if let Some(circuit_breaker_window_slots) = params.circuit_breaker_window_slots {
+            config.circuit_breaker_window_slots = circuit_breaker_window_slots;
+        }
+        if let Some(circuit_breaker_threshold) = params.circuit_breaker_threshold {
+            config.circuit_breaker_threshold = circuit_breaker_threshold;
+        }
+        if let Some(self_chain_id) = params.self_chain_id {
+            config.self_chain_id = self_chain_id;
+        }
+        if let Some(wormhole_program) = params.wormhole_program {
+            config.wormhole_program = wormhole_program;
+        }
+        if let Some(layerzero_endpoint) = params.layerzero_endpoint {
+            config.layerzero_endpoint = layerzero_endpoint;
+        }
+        if let Some(debug_logs) = params.debug_logs {
+            config.debug_logs = debug_logs;
+        }
+        if let Some(forward_deposits_to_treasury) = params.forward_deposits_to_treasury {
+            config.forward_deposits_to_treasury = forward_deposits_to_treasury;
+        }
+        if let Some(allowed_callers) = params.allowed_callers {
+            require!(
+                allowed_callers.len() <= MAX_ALLOWED_CALLERS,
+                UniversalNFTError::TooManyAllowedCallers
+            );
+            config.allowed_callers = [Pubkey::default(); MAX_ALLOWED_CALLERS];
+            for (slot, caller) in config.allowed_callers.iter_mut().zip(allowed_callers) {
+                *slot = caller;
+            }
+        }
+        if let Some(max_uri_len) = params.max_uri_len {
+            // Outbound messages and the compact mirror hold at most MAX_URI_LEN bytes
+            require!(
+                max_uri_len > 0 && max_uri_len as usize <= MAX_URI_LEN,
+                UniversalNFTError::UriTooLong
+            );
+            config.max_uri_len = max_uri_len;
+        }
+
+        Ok(())
+    }
+
+    /// Transfer NFT cross-chain authorized by the owner's off-chain ed25519 signature
+    ///
+    /// The transaction must include an Ed25519 program instruction immediately before this
+    /// one, verifying the owner's signature over the borsh-encoded `PermitMessage`. The owner
+    /// must have approved the program PDA as the token account's delegate. The relayer
+    /// submitting the transaction pays all fees and the SOL gas deposit, but chooses nothing
+    /// the owner did not sign: the token's own URI travels, and reverts refund the owner.
+    pub fn transfer_cross_chain_with_permit(
+        ctx: Context<TransferCrossChainWithPermit>,
+        token_id: u64,
+        recipient_address: Vec<u8>,
+        destination_chain_id: u64,
+        nonce: u64,
+        expiry: i64,
+        gas_amount: u64,
+    ) -> Result<()> {
+        require!(Clock::get()?.unix_timestamp <= expiry, UniversalNFTError::PermitExpired);
+        require!(gas_amount > 0, UniversalNFTError::InvalidGasAmount);
+
+        let owner = ctx.accounts.transfer.token_account.owner;
+        let permit_nonce = &mut ctx.accounts.permit_nonce;
+        require!(permit_nonce.nonce == nonce, UniversalNFTError::InvalidPermitNonce);
+        permit_nonce.owner = owner;
+        permit_nonce.nonce = permit_nonce.nonce.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+
+        let permit = PermitMessage {
+            program_id: crate::ID,
+            token_id,
+            destination_chain_id,
+            recipient_address: recipient_address.clone(),
+            nonce,
+            expiry,
+        };
+        let permit_bytes = permit.try_to_vec().map_err(|_| UniversalNFTError::SerializationError)?;
+        verify_ed25519_permit(&ctx.accounts.transfer.instruction_sysvar, &owner, &permit_bytes)?;
+
+        let metadata_uri = ctx.accounts.transfer.nft_info.uri.clone();
+        let revert_params = RevertParams {
+            revert_address: Some(owner),
+            call_on_revert: None,
+        };
+        let OutboundTransfer {
+            serialized_message,
+            revert_options,
+            seq,
+            transfer_id,
+            ..
+        } = prepare_outbound_transfer(
+            &mut ctx.accounts.transfer,
+            OutboundAuthority::Permit {
+                owner,
+                pda: ctx.accounts.pda.to_account_info(),
+                pda_bump: ctx.bumps.pda,
+            },
+            token_id,
+            recipient_address.clone(),
+            destination_chain_id,
+            metadata_uri,
+            None,
+            Some(revert_params),
+            None,
+            Vec::new(),
+            None,
+            1,
+            Transport::Gateway,
+        )?;
+
+        send_gateway_message(&ctx.accounts.transfer, gas_amount, serialized_message, Some(revert_options))?;
+        ctx.accounts.transfer.pending_transfer.dispatched = true;
+
+        let clock = Clock::get()?;
+        emit_cpi_nested(
+            &ctx.accounts.transfer.event_authority,
+            ctx.bumps.transfer.event_authority,
+            &CrossChainTransferEvent {
+                seq,
+                token_id,
+                transfer_id,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+                from_chain: "Solana".to_string(),
+                to_chain: format!("Chain-{}", destination_chain_id),
+                sender: owner,
+                receiver: recipient_address,
+                final_destination_chain: None,
+                final_receiver: Vec::new(),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Opt a token account into its collection's migration, to `receiver` on the new chain
+    ///
+    /// Registers the holder's receiver and hands the program PDA delegation over the account's
+    /// units and its close authority, so `migrate_collection` can burn them and close the
+    /// emptied account, refunding its rent to the holder.
+    pub fn opt_into_migration(ctx: Context<OptIntoMigration>, receiver: Vec<u8>) -> Result<()> {
+        require!(!receiver.is_empty(), UniversalNFTError::InvalidReceiverLength);
+        require_gte!(MAX_RECEIVER_LEN, receiver.len(), UniversalNFTError::InvalidReceiverLength);
+
+        let migration_receiver = &mut ctx.accounts.migration_receiver;
+        migration_receiver.collection = ctx.accounts.collection.key();
+        migration_receiver.holder = ctx.accounts.holder.key();
+        migration_receiver.receiver = receiver;
+
+        let cpi_accounts = token::Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: ctx.accounts.pda.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::approve(cpi_ctx, ctx.accounts.token_account.amount)?;
+
+        let cpi_accounts = token::SetAuthority {
+            current_authority: ctx.accounts.holder.to_account_info(),
+            account_or_mint: ctx.accounts.token_account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::set_authority(cpi_ctx, AuthorityType::CloseAccount, Some(ctx.accounts.pda.key()))
+    }
+
+    /// Bridge the next item of a collection to `destination_chain_id` (collection authority only)
+    ///
+    /// Cranked one item per instruction, in ascending token id order, with progress kept in the
+    /// collection's `Migration` PDA; a crank packs as many instructions per transaction as fit.
+    /// Each holder must have opted in with `opt_into_migration`, and `recipient_address` must be
+    /// the receiver they registered. The message's extra data carries the Solana holder.
+    /// Semi-fungibles move the holder's whole balance.
+    pub fn migrate_collection(
+        ctx: Context<MigrateCollection>,
+        token_id: u64,
+        recipient_address: Vec<u8>,
+        destination_chain_id: u64,
+        gas_amount: u64,
+    ) -> Result<()> {
+        require!(gas_amount > 0, UniversalNFTError::InvalidGasAmount);
+        let collection = ctx.accounts.collection.key();
+        require!(
+            ctx.accounts.transfer.nft_info.collection == Some(collection),
+            UniversalNFTError::CollectionMismatch
+        );
+
+        let migration = &mut ctx.accounts.migration;
+        let clock = Clock::get()?;
+        if migration.migrated == 0 {
+            migration.collection = collection;
+            migration.destination_chain_id = destination_chain_id;
+            migration.started_slot = clock.slot;
+        } else {
+            require!(
+                migration.destination_chain_id == destination_chain_id,
+                UniversalNFTError::MigrationMismatch
+            );
+            require!(token_id > migration.last_token_id, UniversalNFTError::MigrationOutOfOrder);
+        }
+        migration.last_token_id = token_id;
+        migration.migrated = migration.migrated.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+        migration.updated_slot = clock.slot;
+        let migrated = migration.migrated;
+
+        let owner = ctx.accounts.transfer.token_account.owner;
+        let amount = if ctx.accounts.transfer.nft_info.semi_fungible {
+            ctx.accounts.transfer.token_account.amount
+        } else {
+            1
+        };
+        let metadata_uri = ctx.accounts.transfer.nft_info.uri.clone();
+        let OutboundTransfer {
+            serialized_message,
+            revert_options,
+            seq,
+            transfer_id,
+            ..
+        } = prepare_outbound_transfer(
+            &mut ctx.accounts.transfer,
+            OutboundAuthority::Permit {
+                owner,
+                pda: ctx.accounts.pda.to_account_info(),
+                pda_bump: ctx.bumps.pda,
+            },
+            token_id,
+            recipient_address.clone(),
+            destination_chain_id,
+            metadata_uri,
+            None,
+            None,
+            None,
+            owner.to_bytes().to_vec(),
+            None,
+            amount,
+            Transport::Gateway,
+        )?;
+
+        send_gateway_message(&ctx.accounts.transfer, gas_amount, serialized_message, Some(revert_options))?;
+        ctx.accounts.transfer.pending_transfer.dispatched = true;
+
+        // The holder made the program PDA the close authority when opting in; return the rent of
+        // the emptied token account to them
+        let token_account = ctx.accounts.transfer.token_account.to_account_info();
+        if ctx.accounts.transfer.token_account.close_authority == COption::Some(ctx.accounts.pda.key())
+            && token::accessor::amount(&token_account)? == 0
+        {
+            let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+            let cpi_accounts = token::CloseAccount {
+                account: token_account,
+                destination: ctx.accounts.holder.to_account_info(),
+                authority: ctx.accounts.pda.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.transfer.token_program.to_account_info(),
+                cpi_accounts,
+                &[pda_seeds],
+            );
+            token::close_account(cpi_ctx)?;
+        }
+
+        emit_cpi_nested(
+            &ctx.accounts.transfer.event_authority,
+            ctx.bumps.transfer.event_authority,
+            &CrossChainTransferEvent {
+                seq,
+                token_id,
+                transfer_id,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+                from_chain: "Solana".to_string(),
+                to_chain: format!("Chain-{}", destination_chain_id),
+                sender: owner,
+                receiver: recipient_address,
+                final_destination_chain: None,
+                final_receiver: Vec::new(),
+            },
+        )?;
+        emit!(CollectionItemMigrated {
+            seq: ctx.accounts.transfer.universal_nft_state.next_event_seq()?,
+            collection,
+            token_id,
+            destination_chain_id,
+            migrated,
+        });
+
+        Ok(())
+    }
+
+    /// Transfer NFT cross-chain through Wormhole, for chains the Gateway does not reach
+    ///
+    /// The NFT is burned and tracked by a PendingTransfer exactly as for Gateway transfers.
+    /// Wormhole has no revert callback, so an undelivered transfer is reclaimed after the
+    /// transfer timeout. `wormhole_message` is a fresh keypair that receives the posted message.
+    pub fn transfer_cross_chain_wormhole(
+        ctx: Context<TransferCrossChainWormhole>,
+        token_id: u64,
+        recipient_address: Vec<u8>,
+        destination_chain_id: u64,
+        metadata_uri: String,
+        nonce: u32,
+    ) -> Result<()> {
+        let OutboundTransfer {
+            serialized_message,
+            seq,
+            transfer_id,
+            ..
+        } = prepare_outbound_transfer(
+            &mut ctx.accounts.transfer,
+            OutboundAuthority::Signer,
+            token_id,
+            recipient_address.clone(),
+            destination_chain_id,
+            metadata_uri,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None,
+            1,
+            Transport::Wormhole,
+        )?;
+
+        // Wormhole messages are broadcast, so the payload names its target chain and contract
+        let chain_config = load_enabled_chain_config(&ctx.accounts.transfer.chain_config)?;
+        let target_chain =
+            u16::try_from(chain_config.transport_chain_id).map_err(|_| UniversalNFTError::UnsupportedChain)?;
+        let envelope = WormholeEnvelope {
+            target_chain,
+            target_address: chain_config.peer,
+            message: serialized_message,
+        };
+        let payload = envelope.try_to_vec().map_err(|_| UniversalNFTError::SerializationError)?;
+
+        post_wormhole_message(
+            WormholeAccounts {
+                program: ctx.accounts.wormhole_program.to_account_info(),
+                bridge: ctx.accounts.wormhole_bridge.to_account_info(),
+                message: ctx.accounts.wormhole_message.to_account_info(),
+                emitter: ctx.accounts.wormhole_emitter.to_account_info(),
+                emitter_bump: ctx.bumps.wormhole_emitter,
+                sequence: ctx.accounts.wormhole_sequence.to_account_info(),
+                payer: ctx.accounts.transfer.signer.to_account_info(),
+                fee_collector: ctx.accounts.wormhole_fee_collector.to_account_info(),
+                clock: ctx.accounts.clock.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+                system_program: ctx.accounts.transfer.system_program.to_account_info(),
+            },
+            nonce,
+            payload,
+        )?;
+        ctx.accounts.transfer.pending_transfer.dispatched = true;
+
+        close_source_token_account(&ctx.accounts.transfer)?;
+
+        let clock = Clock::get()?;
+        emit_cpi_nested(
+            &ctx.accounts.transfer.event_authority,
+            ctx.bumps.transfer.event_authority,
+            &CrossChainTransferEvent {
+                seq,
+                token_id,
+                transfer_id,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+                from_chain: "Solana".to_string(),
+                to_chain: format!("Chain-{}", destination_chain_id),
+                sender: ctx.accounts.transfer.signer.key(),
+                receiver: recipient_address,
+                final_destination_chain: None,
+                final_receiver: Vec::new(),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Transfer NFT cross-chain through a LayerZero endpoint
+    ///
+    /// The NFT is burned and tracked by a PendingTransfer as for Gateway transfers. The
+    /// remaining accounts are the endpoint's `send` accounts, which pay `native_fee`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_cross_chain_layerzero<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TransferCrossChainLayerZero<'info>>,
+        token_id: u64,
+        recipient_address: Vec<u8>,
+        destination_chain_id: u64,
+        metadata_uri: String,
+        native_fee: u64,
+        options: Vec<u8>,
+    ) -> Result<()> {
+        let OutboundTransfer {
+            serialized_message,
+            seq,
+            transfer_id,
+            ..
+        } = prepare_outbound_transfer(
+            &mut ctx.accounts.transfer,
+            OutboundAuthority::Signer,
+            token_id,
+            recipient_address.clone(),
+            destination_chain_id,
+            metadata_uri,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None,
+            1,
+            Transport::LayerZero,
+        )?;
+
+        let chain_config = load_enabled_chain_config(&ctx.accounts.transfer.chain_config)?;
+        invoke_layerzero_endpoint(
+            &ctx.accounts.layerzero_endpoint,
+            ctx.bumps.lz_oapp,
+            ctx.remaining_accounts,
+            "send",
+            LzSendParams {
+                dst_eid: chain_config.transport_chain_id,
+                receiver: chain_config.peer,
+                message: serialized_message,
+                options,
+                native_fee,
+                lz_token_fee: 0,
+            },
+        )?;
+        ctx.accounts.transfer.pending_transfer.dispatched = true;
+
+        close_source_token_account(&ctx.accounts.transfer)?;
+
+        let clock = Clock::get()?;
+        emit_cpi_nested(
+            &ctx.accounts.transfer.event_authority,
+            ctx.bumps.transfer.event_authority,
+            &CrossChainTransferEvent {
+                seq,
+                token_id,
+                transfer_id,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+                from_chain: "Solana".to_string(),
+                to_chain: format!("Chain-{}", destination_chain_id),
+                sender: ctx.accounts.transfer.signer.key(),
+                receiver: recipient_address,
+                final_destination_chain: None,
+                final_receiver: Vec::new(),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Register the program's OApp PDA with the LayerZero endpoint (authority only)
+    ///
+    /// The remaining accounts are the endpoint's `register_oapp` accounts.
+    pub fn register_layerzero_oapp<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RegisterLayerZeroOApp<'info>>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        invoke_layerzero_endpoint(
+            &ctx.accounts.layerzero_endpoint,
+            ctx.bumps.lz_oapp,
+            ctx.remaining_accounts,
+            "register_oapp",
+            LzRegisterOAppParams { delegate },
+        )
+    }
+
+    /// Upgrade the global state account to the current schema in place (authority only)
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        let state_info = ctx.accounts.universal_nft_state.to_account_info();
+        // Baseline accounts were allocated at their packed size, below the current layout's
+        let mut state = if state_info.data_len() < 8 + UniversalNFTState::INIT_SPACE {
+            UniversalNFTState::from(decode_baseline::<UniversalNFTState, LegacyUniversalNFTState>(
+                &state_info.try_borrow_data()?,
+            )?)
+        } else {
+            UniversalNFTState::try_deserialize(&mut &state_info.try_borrow_data()?[..])?
+        };
+        require_keys_eq!(state.authority, ctx.accounts.authority.key(), UniversalNFTError::Unauthorized);
+        state.version = UNIVERSAL_NFT_STATE_VERSION;
+        store_migrated_account(
+            &state_info,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + UniversalNFTState::INIT_SPACE,
+            &state,
+        )
+    }
+
+    /// Upgrade an NFTInfo account to the current schema in place
+    ///
+    /// Permissionless and idempotent, so operators can sweep every NFT; the payer covers any
+    /// rent the larger layout needs.
+    pub fn migrate_nft_info(ctx: Context<MigrateNFTInfo>, _token_id: u64) -> Result<()> {
+        let nft_info_account = ctx.accounts.nft_info.to_account_info();
+        let mut nft_info = if nft_info_account.data_len() < 8 + NFTInfo::INIT_SPACE {
+            NFTInfo::from(decode_baseline::<NFTInfo, LegacyNFTInfo>(&nft_info_account.try_borrow_data()?)?)
+        } else {
+            NFTInfo::try_deserialize(&mut &nft_info_account.try_borrow_data()?[..])?
+        };
+        // Older versions may have decoded leftover bytes into fields carved from the reserved region
+        if nft_info.version < 3 {
+            nft_info.semi_fungible = false;
+            nft_info._padding = Default::default();
+        }
+        if nft_info.version < 4 {
+            nft_info.metadata_hash = [0; 32];
+        }
+        if nft_info.version < NFT_INFO_VERSION {
+            nft_info.transfer_nonce = 0;
+            nft_info.reserved = Default::default();
+        }
+        nft_info.version = NFT_INFO_VERSION;
+        store_migrated_account(
+            &nft_info_account,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            NFTInfo::space_for_uri(nft_info.uri.len()),
+            &nft_info,
+        )
+    }
+
+    /// Create or extend the address lookup table of static program accounts (authority only)
+    ///
+    /// The table is owned by the program PDA and recorded in `Config.lookup_table` so clients
+    /// can compile bridge transactions as v0 messages. `recent_slot` is only used on creation;
+    /// later calls append whatever static accounts the table is missing, e.g. after the
+    /// Gateway or a transport program changes.
+    pub fn sync_lookup_table(ctx: Context<SyncLookupTable>, recent_slot: u64) -> Result<()> {
+        let pda_key = ctx.accounts.pda.key();
+        let authority_key = ctx.accounts.authority.key();
+        let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+        let table_accounts = [
+            ctx.accounts.lookup_table.to_account_info(),
+            ctx.accounts.pda.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.address_lookup_table_program.to_account_info(),
+        ];
+
+        let mut existing = Vec::new();
+        if ctx.accounts.config.lookup_table == Pubkey::default() {
+            let (create_ix, table_key) = address_lookup_table::instruction::create_lookup_table_signed(
+                pda_key,
+                authority_key,
+                recent_slot,
+            );
+            require_keys_eq!(ctx.accounts.lookup_table.key(), table_key, UniversalNFTError::InvalidLookupTable);
+            anchor_lang::solana_program::program::invoke_signed(&create_ix, &table_accounts, &[pda_seeds])?;
+            ctx.accounts.config.lookup_table = table_key;
+        } else {
+            require_keys_eq!(
+                ctx.accounts.lookup_table.key(),
+                ctx.accounts.config.lookup_table,
+                UniversalNFTError::InvalidLookupTable
+            );
+            let data = ctx.accounts.lookup_table.try_borrow_data()?;
+            let entries = data
+                .get(address_lookup_table::state::LOOKUP_TABLE_META_SIZE..)
+                .ok_or(UniversalNFTError::InvalidLookupTable)?;
+            existing.extend(entries.chunks_exact(32).map(|key| Pubkey::try_from(key).unwrap()));
+        }
+
+        let missing: Vec<Pubkey> = static_lookup_addresses(&ctx.accounts.config)
+            .into_iter()
+            .filter(|key| !existing.contains(key))
+            .collect();
+        if !missing.is_empty() {
+            let extend_ix = address_lookup_table::instruction::extend_lookup_table(
+                ctx.accounts.config.lookup_table,
+                pda_key,
+                Some(authority_key),
+                missing,
+            );
+            anchor_lang::solana_program::program::invoke_signed(&extend_ix, &table_accounts, &[pda_seeds])?;
+        }
+        Ok(())
+    }
+
+    /// Quote the total cost of a cross-chain transfer, returned via return data
+    pub fn quote_transfer(ctx: Context<QuoteTransfer>, destination_chain_id: u64) -> Result<()> {
+        let protocol_fee = ctx.accounts.config.bridge_fee_lamports;
+        let gas_deposit = load_enabled_chain_config(&ctx.accounts.chain_config)?.gas_deposit_lamports;
+        // transfer_cross_chain funds the PendingTransfer record for the token
+        let rent = Rent::get()?.minimum_balance(8 + PendingTransfer::INIT_SPACE);
+
+        let total = protocol_fee
+            .checked_add(gas_deposit)
+            .and_then(|total| total.checked_add(rent))
+            .ok_or(UniversalNFTError::FeeOverflow)?;
+
+        let quote = TransferQuote {
+            destination_chain_id,
+            protocol_fee,
+            gas_deposit,
+            rent,
+            total,
+        };
+
+        let data = quote.try_to_vec().map_err(|_| UniversalNFTError::SerializationError)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+
+        Ok(())
+    }
+
+    /// Register or update a destination chain in the chain registry (authority only)
+    pub fn set_chain_config(
+        ctx: Context<SetChainConfig>,
+        chain_id: u64,
+        params: ChainConfigParams,
+    ) -> Result<()> {
+        require!(
+            params.gas_token_mints.len() <= MAX_GAS_TOKENS,
+            UniversalNFTError::TooManyGasTokens
+        );
+        require!(chain_id != ctx.accounts.config.self_chain_id, UniversalNFTError::SelfChainDestination);
+        validate_caip2(&params.caip2)?;
+        emit!(ChainConfigSet {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            authority: ctx.accounts.authority.key(),
+            chain_id,
+            params: params.clone(),
+        });
+
+        // A CAIP-2 identifier names exactly one chain, and a chain keeps its identifier once set
+        let caip2_mapping = &mut ctx.accounts.caip2_mapping;
+        require!(
+            caip2_mapping.caip2.is_empty() || caip2_mapping.chain_id == chain_id,
+            UniversalNFTError::Caip2InUse
+        );
+        require!(
+            ctx.accounts.chain_config.caip2.is_empty() || ctx.accounts.chain_config.caip2 == params.caip2,
+            UniversalNFTError::Caip2InUse
+        );
+        caip2_mapping.caip2 = params.caip2.clone();
+        caip2_mapping.chain_id = chain_id;
+
+        let chain_config = &mut ctx.accounts.chain_config;
+        chain_config.chain_id = chain_id;
+        chain_config.caip2 = params.caip2;
+        chain_config.enabled = params.enabled;
+        chain_config.gas_deposit_lamports = params.gas_deposit_lamports;
+        chain_config.receiver_format = params.receiver_format;
+        chain_config.transport = params.transport;
+        chain_config.transport_chain_id = params.transport_chain_id;
+        chain_config.peer = params.peer;
+        chain_config.compact_messages = params.compact_messages;
+        chain_config.gas_token_mints = [Pubkey::default(); MAX_GAS_TOKENS];
+        for (slot, mint) in chain_config.gas_token_mints.iter_mut().zip(params.gas_token_mints) {
+            *slot = mint;
+        }
+
+        ctx.accounts.chain_stats.chain_id = chain_id;
+
+        Ok(())
+    }
+
+    /// Return the ZetaChain chain id registered for a CAIP-2 identifier via return data
+    pub fn resolve_caip2(ctx: Context<ResolveCaip2>, _caip2: String) -> Result<()> {
+        let data = ctx
+            .accounts
+            .caip2_mapping
+            .chain_id
+            .try_to_vec()
+            .map_err(|_| UniversalNFTError::SerializationError)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+        Ok(())
+    }
+
+    /// Withdraw accumulated protocol fees from the treasury (authority only)
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(treasury_info.data_len());
+        let available = treasury_info.lamports().saturating_sub(rent_exempt_minimum);
+        require!(amount <= available, UniversalNFTError::InsufficientFees);
+
+        treasury_info.sub_lamports(amount)?;
+        ctx.accounts.recipient.add_lamports(amount)?;
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_withdrawn = treasury.total_withdrawn.checked_add(amount).ok_or(UniversalNFTError::FeeOverflow)?;
+
+        emit!(FeesWithdrawn {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            recipient: ctx.accounts.recipient.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Top up the program PDA, which pays rent for accounts created on inbound deliveries
+    ///
+    /// Inbound mints create the receiver's token account, NFTInfo and index entries with rent
+    /// from the PDA; deliveries fail with `PdaUnderfunded` once it runs low.
+    pub fn fund_pda(ctx: Context<FundPda>, amount: u64) -> Result<()> {
+        let transfer_accounts = system_program::Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.pda.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_accounts),
+            amount,
+        )?;
+
+        emit!(PdaFunded {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            funder: ctx.accounts.funder.key(),
+            amount,
+            balance: ctx.accounts.pda.to_account_info().lamports(),
+        });
+        Ok(())
+    }
+
+    /// Queue the rescue of assets stranded on the program PDA (authority only)
+    ///
+    /// `asset` is the mint of an SPL token, or None for lamports. The rescue can be executed
+    /// with `rescue` once `RESCUE_TIMELOCK_SLOTS` have passed; queuing again replaces it.
+    pub fn queue_rescue(
+        ctx: Context<QueueRescue>,
+        asset: Option<Pubkey>,
+        amount: u64,
+        destination: Pubkey,
+    ) -> Result<()> {
+        let executable_slot = Clock::get()?.slot + RESCUE_TIMELOCK_SLOTS;
+        let rescue_request = &mut ctx.accounts.rescue_request;
+        rescue_request.asset = asset;
+        rescue_request.amount = amount;
+        rescue_request.destination = destination;
+        rescue_request.executable_slot = executable_slot;
+
+        emit!(RescueQueued {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            asset,
+            amount,
+            destination,
+            executable_slot,
+        });
+        Ok(())
+    }
+
+    /// Execute a queued rescue whose timelock has passed (authority only)
+    ///
+    /// Lamports are paid down to the PDA's rent-exempt minimum. NFTs of this program, including
+    /// those held in escrow for a claim, can never be rescued.
+    pub fn rescue(ctx: Context<Rescue>, asset: Option<Pubkey>, amount: u64, destination: Pubkey) -> Result<()> {
+        let rescue_request = &ctx.accounts.rescue_request;
+        require!(
+            rescue_request.asset == asset
+                && rescue_request.amount == amount
+                && rescue_request.destination == destination,
+            UniversalNFTError::RescueMismatch
+        );
+        require!(
+            Clock::get()?.slot >= rescue_request.executable_slot,
+            UniversalNFTError::RescueTimelocked
+        );
+
+        match asset {
+            Some(mint) => {
+                let (Some(mint_index), Some(from), Some(to), Some(token_program)) = (
+                    ctx.accounts.mint_index.as_ref(),
+                    ctx.accounts.pda_token_account.as_ref(),
+                    ctx.accounts.destination_token_account.as_ref(),
+                    ctx.accounts.token_program.as_ref(),
+                ) else {
+                    return err!(UniversalNFTError::InvalidRescueAccounts);
+                };
+                let (mint_index_key, _) =
+                    Pubkey::find_program_address(&[b"mint_index", mint.as_ref()], ctx.program_id);
+                require_keys_eq!(mint_index.key(), mint_index_key, UniversalNFTError::InvalidRescueAccounts);
+                require!(mint_index.data_is_empty(), UniversalNFTError::RescueEscrowedNft);
+                require!(
+                    from.mint == mint && to.mint == mint && to.owner == destination,
+                    UniversalNFTError::InvalidRescueAccounts
+                );
+
+                let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+                let cpi_accounts = token::Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.pda.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    cpi_accounts,
+                    &[pda_seeds],
+                );
+                token::transfer(cpi_ctx, amount)?;
+            }
+            None => {
+                let recipient = ctx
+                    .accounts
+                    .destination
+                    .as_ref()
+                    .filter(|recipient| recipient.key() == destination)
+                    .ok_or(UniversalNFTError::InvalidRescueAccounts)?;
+                debit_program_funds(&ctx.accounts.pda.to_account_info(), amount)?;
+                recipient.add_lamports(amount)?;
+            }
+        }
+
+        emit!(AssetsRescued {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            asset,
+            amount,
+            destination,
+        });
+        Ok(())
+    }
+
+    /// Put an address on the blocklist (authority only)
+    ///
+    /// `address` is raw bytes: a 32-byte Solana pubkey, or a receiver on another chain in the
+    /// encoding outbound transfers use. Listed owners cannot bridge out or claim escrowed NFTs,
+    /// and no transfer can be sent to a listed receiver.
+    pub fn block_address(ctx: Context<BlockAddress>, address: Vec<u8>) -> Result<()> {
+        require!(
+            !address.is_empty() && address.len() <= MAX_RECEIVER_LEN,
+            UniversalNFTError::InvalidBlockedAddress
+        );
+        let blocklist_entry = &mut ctx.accounts.blocklist_entry;
+        blocklist_entry.address = address.clone();
+        blocklist_entry.blocked_slot = Clock::get()?.slot;
+
+        emit!(AddressBlocked {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            address,
+        });
+        Ok(())
+    }
+
+    /// Remove an address from the blocklist (authority only)
+    pub fn unblock_address(ctx: Context<UnblockAddress>, address: Vec<u8>) -> Result<()> {
+        emit!(AddressUnblocked {
+            seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+            address,
+        });
+        Ok(())
+    }
+
+    /// Permanently give up the program authority (authority only)
+    ///
+    /// The authority becomes `RENOUNCED_AUTHORITY`, which no one can sign for, so every
+    /// authority-gated instruction (config, chain registry, minting, pausing, rescues, state
+    /// migrations) is disabled for good. This cannot be undone. Bridging must be unpaused with the
+    /// circuit breaker disabled, since nobody could unpause it afterwards.
+    pub fn renounce_authority(ctx: Context<RenounceAuthority>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(
+            !config.paused && config.circuit_breaker_threshold == 0,
+            UniversalNFTError::CircuitBreakerArmed
+        );
+
+        let universal_nft_state = &mut ctx.accounts.universal_nft_state;
+        let previous_authority = universal_nft_state.authority;
+        universal_nft_state.authority = RENOUNCED_AUTHORITY;
+
+        emit!(AuthorityRenounced {
+            seq: universal_nft_state.next_event_seq()?,
+            previous_authority,
+        });
+        Ok(())
+    }
+}
+
+/// Program-derived addresses, for clients and for programs composing with this one over CPI
+pub mod address {
+    use super::*;
+
+    fn find(seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &crate::ID).0
+    }
+
+    /// Global program state
+    pub fn universal_nft_state() -> Pubkey {
+        find(&[b"universal_nft_state"])
+    }
+
+    /// Program PDA holding mint authority over every NFT mint
+    pub fn program_authority() -> Pubkey {
+        find(&[b"connected"])
+    }
+
+    pub fn config() -> Pubkey {
+        find(&[b"config"])
+    }
+
+    pub fn treasury() -> Pubkey {
+        find(&[b"treasury"])
+    }
+
+    /// Mint of a token minted through `mint_nft`
+    pub fn nft_mint(token_id: u64) -> Pubkey {
+        find(&[b"nft_mint", &token_id.to_le_bytes()])
+    }
+
+    pub fn nft_info(token_id: u64) -> Pubkey {
+        find(&[b"nft_info", &token_id.to_le_bytes()])
+    }
+
+    /// Zero-copy mirror of `nft_info`, created by `sync_nft_info_compact`
+    pub fn nft_info_compact(token_id: u64) -> Pubkey {
+        find(&[b"nft_info_compact", &token_id.to_le_bytes()])
+    }
+
+    pub fn mint_index(mint: &Pubkey) -> Pubkey {
+        find(&[b"mint_index", mint.as_ref()])
+    }
+
+    pub fn owner_index(owner: &Pubkey, page: u32) -> Pubkey {
+        find(&[b"owner_index", owner.as_ref(), &page.to_le_bytes()])
+    }
+
+    pub fn supply_index(page: u32) -> Pubkey {
+        find(&[b"supply_index", &page.to_le_bytes()])
+    }
+
+    pub fn chain_config(chain_id: u64) -> Pubkey {
+        find(&[b"chain_config", &chain_id.to_le_bytes()])
+    }
+
+    pub fn chain_stats(chain_id: u64) -> Pubkey {
+        find(&[b"chain_stats", &chain_id.to_le_bytes()])
+    }
+
+    /// In-flight record of the outbound transfer with `transfer_id`
+    pub fn pending_transfer(transfer_id: &[u8; 32]) -> Pubkey {
+        find(&[b"pending_transfer", transfer_id])
+    }
+
+    /// Status record of the outbound transfer with `transfer_id`
+    pub fn transfer_record(transfer_id: &[u8; 32]) -> Pubkey {
+        find(&[b"transfer_record", transfer_id])
+    }
+
+    /// Receipt of `owner`'s in-flight transfer with `transfer_id`
+    pub fn receipt(owner: &Pubkey, transfer_id: &[u8; 32]) -> Pubkey {
+        find(&[b"receipt", owner.as_ref(), transfer_id])
+    }
+
+    /// Outbound rate limit record of the wallet owning the bridged token
+    pub fn rate_limit(wallet: &Pubkey) -> Pubkey {
+        find(&[b"rate_limit", wallet.as_ref()])
+    }
+
+    pub fn attributes(token_id: u64) -> Pubkey {
+        find(&[b"attributes", &token_id.to_le_bytes()])
+    }
+
+    pub fn collection_config(collection: &Pubkey) -> Pubkey {
+        find(&[b"collection_config", collection.as_ref()])
+    }
+
+    /// Migration progress of `collection`
+    pub fn migration(collection: &Pubkey) -> Pubkey {
+        find(&[b"migration", collection.as_ref()])
+    }
+
+    /// Receiver `holder` registered for the migration of `collection`
+    pub fn migration_receiver(collection: &Pubkey, holder: &Pubkey) -> Pubkey {
+        find(&[b"migration_receiver", collection.as_ref(), holder.as_ref()])
+    }
+
+    pub fn uri_registry(uri_hash: &[u8; 32]) -> Pubkey {
+        find(&[b"uri_registry", uri_hash])
+    }
+
+    /// Blocklist entry of a raw address, which exists only while the address is blocked
+    pub fn blocklist_entry(address: &[u8]) -> Pubkey {
+        find(&[b"blocked", &blocklist_seed(address)])
+    }
+
+    /// Event authority passed as `event_authority` to instructions emitting CPI events
+    pub fn event_authority() -> Pubkey {
+        find(&[b"__event_authority"])
+    }
+}
+
+// Copy `value` into a zero-padded fixed buffer, returning its length; callers bound the length
+fn copy_padded(buffer: &mut [u8], value: &str) -> u8 {
+    let bytes = &value.as_bytes()[..value.len().min(buffer.len())];
+    buffer.fill(0);
+    buffer[..bytes.len()].copy_from_slice(bytes);
+    bytes.len() as u8
+}
+
+// Helper function to decode NFT transfer data
+fn decode_nft_transfer(data: &[u8]) -> Result<CrossChainNFTTransfer> {
+    CrossChainNFTTransfer::deserialize(&mut &data[..]).map_err(|_| UniversalNFTError::DecodingError.into())
+}
+
+// Check a CAIP-2 chain identifier: `namespace:reference` with a 3-8 char [-a-z0-9] namespace and a
+// 1-32 char [-_a-zA-Z0-9] reference
+fn validate_caip2(caip2: &str) -> Result<()> {
+    let valid = match caip2.split_once(':') {
+        Some((namespace, reference)) => {
+            (3..=8).contains(&namespace.len())
+                && namespace
+                    .bytes()
+                    .all(|b| b == b'-' || b.is_ascii_lowercase() || b.is_ascii_digit())
+                && (1..=32).contains(&reference.len())
+                && reference
+                    .bytes()
+                    .all(|b| b == b'-' || b == b'_' || b.is_ascii_alphanumeric())
+        }
+        None => false,
+    };
+    require!(valid, UniversalNFTError::InvalidCaip2);
+    Ok(())
+}
+
+// PDA seed for a CAIP-2 identifier, hashed since identifiers can exceed the 32-byte seed limit
+fn caip2_seed(caip2: &str) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hash(caip2.as_bytes()).to_bytes()
+}
+
+// PDA seed for a blocklisted address, hashed since receivers can exceed the 32-byte seed limit
+fn blocklist_seed(address: &[u8]) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hash(address).to_bytes()
+}
+
+// Load a chain registry entry, mapping unregistered or disabled chains to UnsupportedChain
+fn load_enabled_chain_config(account: &AccountInfo) -> Result<ChainConfig> {
+    require!(
+        account.owner == &crate::ID && !account.data_is_empty(),
+        UniversalNFTError::UnsupportedChain
+    );
+    let chain_config = ChainConfig::try_deserialize(&mut &account.data.borrow()[..])?;
+    require!(chain_config.enabled, UniversalNFTError::UnsupportedChain);
+    Ok(chain_config)
+}
+
+// Accounts every bridge transaction references whichever token it moves
+fn static_lookup_addresses(config: &Config) -> Vec<Pubkey> {
+    let mut addresses = vec![
+        crate::ID,
+        address::universal_nft_state(),
+        address::program_authority(),
+        address::config(),
+        address::treasury(),
+        address::event_authority(),
+        config.gateway_program,
+        Pubkey::find_program_address(&[b"meta"], &config.gateway_program).0,
+        Token::id(),
+        AssociatedToken::id(),
+        System::id(),
+        Metadata::id(),
+        instructions::ID,
+        anchor_lang::solana_program::sysvar::rent::ID,
+    ];
+    for program in [config.wormhole_program, config.layerzero_endpoint] {
+        if program != Pubkey::default() {
+            addresses.push(program);
+        }
+    }
+    addresses
+}
+
+// 0x-prefixed lowercase hex, as receivers are shown in events
+fn encode_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(2 + bytes.len() * 2);
+    hex.push_str("0x");
+    for byte in bytes {
+        hex.push(DIGITS[(byte >> 4) as usize] as char);
+        hex.push(DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    hex
+}
+
+// Verify that `checksummed` is the EIP-55 encoding of the 20-byte EVM `address`
+fn verify_eip55_checksum(address: &[u8], checksummed: &str) -> Result<()> {
+    let hex = checksummed.strip_prefix("0x").unwrap_or(checksummed);
+    require!(
+        address.len() == 20 && hex.len() == 40 && hex.is_ascii(),
+        UniversalNFTError::InvalidChecksum
+    );
+
+    // The checksummed string must decode to the same address bytes
+    for (i, byte) in address.iter().enumerate() {
+        let decoded = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| UniversalNFTError::InvalidChecksum)?;
+        require!(decoded == *byte, UniversalNFTError::InvalidChecksum);
+    }
+
+    // Letters are uppercase iff the matching nibble of keccak(lowercase hex) is >= 8
+    let lower = hex.to_ascii_lowercase();
+    let hash = anchor_lang::solana_program::keccak::hash(lower.as_bytes()).to_bytes();
+    for (i, c) in hex.chars().enumerate() {
+        if c.is_ascii_alphabetic() {
+            let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+            require!(c.is_ascii_uppercase() == (nibble >= 8), UniversalNFTError::InvalidChecksum);
+        }
+    }
+
+    Ok(())
+}
+
+// Self-CPI an event through the event authority of a nested TransferCrossChain, mirroring
+// emit_cpi! which only resolves top-level context accounts
+fn emit_cpi_nested<E: anchor_lang::Event>(
+    event_authority: &AccountInfo,
+    event_authority_bump: u8,
+    event: &E,
+) -> Result<()> {
+    let ix_data: Vec<u8> = anchor_lang::event::EVENT_IX_TAG_LE
+        .iter()
+        .copied()
+        .chain(event.data())
+        .collect();
+    let ix = anchor_lang::solana_program::instruction::Instruction::new_with_bytes(
+        crate::ID,
+        &ix_data,
+        vec![AccountMeta::new_readonly(event_authority.key(), true)],
+    );
+    anchor_lang::solana_program::program::invoke_signed(
+        &ix,
+        &[event_authority.clone()],
+        &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[event_authority_bump]]],
+    )
+    .map_err(Into::into)
+}
+
+// Unique id for a cross-chain transfer, derived from the token, counterparty and event sequence
+fn compute_transfer_id(token_id: u64, counterparty: &[u8], seq: u64) -> [u8; 32] {
+    anchor_lang::solana_program::keccak::hashv(&[
+        b"universal_nft_transfer",
+        &token_id.to_le_bytes(),
+        counterparty,
+        &seq.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Id of the `nonce`-th outbound transfer of `token_id` to `destination_chain_id`, keying its
+/// `TransferRecord`; the nonce is the token's `NFTInfo::transfer_nonce` when it leaves
+pub fn outbound_transfer_id(token_id: u64, destination_chain_id: u64, nonce: u64) -> [u8; 32] {
+    compute_transfer_id(token_id, &destination_chain_id.to_le_bytes(), nonce)
+}
+
+/// Id of an inbound transfer: keccak256 over the sender and the Borsh-encoded transfer as
+/// delivered, so an indexer holding the message on the source side recomputes the same id
+pub fn inbound_transfer_id(sender: &[u8; 20], transfer: &CrossChainNFTTransfer) -> Result<[u8; 32]> {
+    let transfer_bytes = transfer.try_to_vec().map_err(|_| UniversalNFTError::SerializationError)?;
+    Ok(anchor_lang::solana_program::keccak::hashv(&[b"universal_nft_transfer", sender, &transfer_bytes]).to_bytes())
+}
+
+/// Local id of a foreign NFT: the low 63 bits of keccak256(origin chain || contract || token id),
+/// flagged so it can never collide with a sequentially minted Solana id
+pub fn derive_foreign_token_id(origin_chain: &[u8], origin_contract: &[u8], origin_token_id: &[u8; 32]) -> u64 {
+    let hash = anchor_lang::solana_program::keccak::hashv(&[origin_chain, origin_contract, origin_token_id]);
+    let mut id_bytes = [0u8; 8];
+    id_bytes.copy_from_slice(&hash.to_bytes()[..8]);
+    u64::from_be_bytes(id_bytes) | FOREIGN_TOKEN_ID_FLAG
+}
+
+// Hash of the metadata a token carries across chains, checked when it returns to Solana
+fn metadata_hash(name: &str, symbol: &str, uri: &str) -> [u8; 32] {
+    anchor_lang::solana_program::keccak::hashv(&[name.as_bytes(), symbol.as_bytes(), uri.as_bytes()])
+        .to_bytes()
+}
+
+// Universal id of a Solana-native NFT: keccak256(mint || chain id || slot), with the integers
+// big-endian so EVM contracts can recompute it
+fn compute_universal_id(mint: &Pubkey, chain_id: u64, slot: u64) -> [u8; 32] {
+    anchor_lang::solana_program::keccak::hashv(&[
+        mint.as_ref(),
+        &chain_id.to_be_bytes(),
+        &slot.to_be_bytes(),
+    ])
+    .to_bytes()
+}
+
+// Count a bridge operation in the current volume window and pause bridging once the
+// configured threshold is exceeded; the tripping operation itself still completes
+fn record_bridge_volume(state: &mut UniversalNFTState, config: &mut Config, outbound: bool) -> Result<()> {
+    if config.circuit_breaker_threshold == 0 {
+        return Ok(());
+    }
+
+    let slot = Clock::get()?.slot;
+    if slot >= state.window_start_slot.saturating_add(config.circuit_breaker_window_slots) {
+        state.window_start_slot = slot;
+        state.window_outbound = 0;
+        state.window_inbound = 0;
+    }
+    if outbound {
+        state.window_outbound = state.window_outbound.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+    } else {
+        state.window_inbound = state.window_inbound.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+    }
+
+    if state.window_outbound.saturating_add(state.window_inbound) > config.circuit_breaker_threshold {
+        config.paused = true;
+        emit!(CircuitBreakerTripped {
+            seq: state.next_event_seq()?,
+            window_start_slot: state.window_start_slot,
+            window_outbound: state.window_outbound,
+            window_inbound: state.window_inbound,
+        });
+    }
+    Ok(())
+}
+
+// The update authority of a collection NFT is its collection authority, otherwise the program authority
+fn update_authority_of(
+    state: &UniversalNFTState,
+    nft_info: &NFTInfo,
+    collection: Option<&Account<Collection>>,
+) -> Result<Pubkey> {
+    match nft_info.collection {
+        Some(collection_key) => {
+            let collection = collection.ok_or(UniversalNFTError::CollectionMismatch)?;
+            require_keys_eq!(collection.key(), collection_key, UniversalNFTError::CollectionMismatch);
+            Ok(collection.authority)
+        }
+        None => Ok(state.authority),
+    }
+}
+
+fn check_update_authority(
+    state: &UniversalNFTState,
+    nft_info: &NFTInfo,
+    collection: Option<&Account<Collection>>,
+    authority: &Signer,
+) -> Result<()> {
+    let update_authority = update_authority_of(state, nft_info, collection)?;
+    require_keys_eq!(authority.key(), update_authority, UniversalNFTError::Unauthorized);
+    Ok(())
+}
+
+// Extension point for reward distribution, called with the length of each completed stake;
+// staking currently only accrues `total_staked_seconds`
+fn settle_stake_rewards(_stake_info: &mut StakeInfo, _duration: u64) -> Result<()> {
+    Ok(())
+}
+
+// Soulbound NFTs only leave their wallet with the co-signature of their collection's authority,
+// or the program authority outside a collection; the frozen token account is thawed through the
+// program PDA so it can be burned
+#[allow(clippy::too_many_arguments)]
+fn thaw_soulbound<'info>(
+    state: &UniversalNFTState,
+    nft_info: &NFTInfo,
+    collection: Option<&Account<Collection>>,
+    collection_authority: Option<&Signer<'info>>,
+    pda: Option<&UncheckedAccount<'info>>,
+    token_account: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    let update_authority = update_authority_of(state, nft_info, collection)?;
+    require!(
+        collection_authority.is_some_and(|authority| authority.key() == update_authority),
+        UniversalNFTError::Soulbound
+    );
+    let pda = pda.ok_or(UniversalNFTError::Soulbound)?;
+    let (pda_key, pda_bump) = Pubkey::find_program_address(&[b"connected"], &crate::ID);
+    require_keys_eq!(pda.key(), pda_key, UniversalNFTError::Soulbound);
+
+    let pda_seeds: &[&[u8]] = &[b"connected", &[pda_bump]];
+    let cpi_accounts = token::ThawAccount {
+        account: token_account,
+        mint,
+        authority: pda.to_account_info(),
+    };
+    token::thaw_account(CpiContext::new_with_signer(token_program, cpi_accounts, &[pda_seeds]))
+}
+
+// Interpret an inbound source chain as a little-endian chain id
+fn source_chain_id(source_chain: &[u8]) -> Option<u64> {
+    source_chain.try_into().ok().map(u64::from_le_bytes)
+}
+
+// Accounts used to mint a freshly created NFT mint and attach its metadata
+struct NewNftAccounts<'info> {
+    payer: AccountInfo<'info>,
+    pda: AccountInfo<'info>,
+    pda_bump: u8,
+    mint: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+    metadata: AccountInfo<'info>,
+    update_authority: AccountInfo<'info>,
+    update_authority_is_signer: bool,
+    token_program: AccountInfo<'info>,
+    metadata_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    rent: AccountInfo<'info>,
+}
+
+// Mint `amount` units of a new token (one for an NFT) and create its Metaplex metadata, signing
+// as the program PDA
+fn mint_new_nft(
+    accounts: NewNftAccounts,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    royalty: Option<&RoyaltyConfig>,
+    amount: u64,
+) -> Result<()> {
+    let pda_seeds: &[&[u8]] = &[b"connected", &[accounts.pda_bump]];
+
+    let cpi_accounts = MintTo {
+        mint: accounts.mint.clone(),
+        to: accounts.token_account,
+        authority: accounts.pda.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(accounts.token_program, cpi_accounts, &[pda_seeds]);
+    mint_to(cpi_ctx, amount)?;
+
+    let data_v2 = DataV2 {
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        uri: uri.to_string(),
+        seller_fee_basis_points: royalty.map_or(0, |royalty| royalty.basis_points),
+        creators: royalty.map(|royalty| {
+            vec![Creator {
+                address: royalty.recipient,
+                verified: false,
+                share: 100,
+            }]
+        }),
+        collection: None,
+        uses: None,
+    };
+    let cpi_accounts = CreateMetadataAccountsV3 {
+        metadata: accounts.metadata,
+        mint: accounts.mint,
+        mint_authority: accounts.pda,
+        update_authority: accounts.update_authority,
+        payer: accounts.payer,
+        system_program: accounts.system_program,
+        rent: accounts.rent,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(accounts.metadata_program, cpi_accounts, &[pda_seeds]);
+    create_metadata_accounts_v3(cpi_ctx, data_v2, true, accounts.update_authority_is_signer, None)
+}
+
+// Only the program PDA holds mint authority, so a Universal NFT mint has supply 1 while the NFT
+// lives on Solana and 0 while it is away; the PDA mints only into an empty mint
+fn require_nft_supply(mint: &Mint, supply: u64) -> Result<()> {
+    require!(
+        mint.decimals == 0 && mint.supply == supply,
+        UniversalNFTError::InvalidNftSupply
+    );
+    Ok(())
+}
+
+// Identity of a freshly minted NFT
+struct MintedNft {
+    token_id: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+    owner: Pubkey,
+    mint: Pubkey,
+    semi_fungible: bool,
+}
+
+// Record a freshly minted NFT in its NFTInfo, the owner/supply/mint indexes and global supply.
+// Semi-fungibles are spread over many holders, so they are left out of owner indexes.
+fn record_minted_nft(
+    state: &mut UniversalNFTState,
+    nft_info: &mut NFTInfo,
+    owner_index: &mut OwnerIndex,
+    supply_index: &mut SupplyIndex,
+    mint_index: &mut MintIndex,
+    minted: MintedNft,
+) -> Result<()> {
+    let MintedNft { token_id, name, symbol, uri, owner, mint, semi_fungible } = minted;
+
+    nft_info.token_id = token_id;
+    nft_info.name = name;
+    nft_info.symbol = symbol;
+    nft_info.uri = uri;
+    nft_info.owner = owner;
+    nft_info.is_burned = false;
+    nft_info.mint = mint;
+    nft_info.version = NFT_INFO_VERSION;
+    nft_info.semi_fungible = semi_fungible;
+    nft_info.record_provenance(ProvenanceAction::Mint, 0, owner)?;
+
+    if !semi_fungible {
+        owner_index.insert(token_id)?;
+    }
+    supply_index.insert(token_id)?;
+
+    mint_index.mint = mint;
+    mint_index.token_id = token_id;
+
+    state.increase_supply(1)?;
+    state.allocate_token_ids(token_id, 1)?;
+
+    emit!(NFTMinted {
+        seq: state.next_event_seq()?,
+        token_id,
+        owner,
+        uri: nft_info.uri.clone(),
+        mint,
+    });
+    Ok(())
+}
+
+// Charge the configured mint fee into the treasury
+fn collect_mint_fee<'info>(
+    payer: AccountInfo<'info>,
+    treasury: &mut Account<'info, Treasury>,
+    system_program_info: AccountInfo<'info>,
+    state: &mut UniversalNFTState,
+    token_id: u64,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let payer_key = payer.key();
+    let fee_accounts = system_program::Transfer {
+        from: payer,
+        to: treasury.to_account_info(),
+    };
+    let fee_ctx = CpiContext::new(system_program_info, fee_accounts);
+    system_program::transfer(fee_ctx, amount)?;
+    treasury.total_collected = treasury.total_collected.checked_add(amount).ok_or(UniversalNFTError::FeeOverflow)?;
+
+    emit!(MintFeeCollected {
+        seq: state.next_event_seq()?,
+        token_id,
+        payer: payer_key,
+        amount,
+    });
+    Ok(())
+}
+
+// Accounts of a mint by the collection authority, shared by `mint_nft`, `mint_nft_auto` and
+// `mint_semi_fungible`; built by `authority_mint!`
+struct AuthorityMint<'a, 'info> {
+    nft: NewNftAccounts<'info>,
+    authority: Pubkey,
+    mint_fee_lamports: u64,
+    universal_nft_state: &'a mut UniversalNFTState,
+    treasury: &'a mut Account<'info, Treasury>,
+    nft_info: &'a mut NFTInfo,
+    owner_index: &'a mut OwnerIndex,
+    supply_index: &'a mut SupplyIndex,
+    mint_index: &'a mut MintIndex,
+    collection: Option<&'a mut Account<'info, Collection>>,
+}
+
+// Mint `token_id` to `to` on behalf of the authority after checking its metadata lengths,
+// charging the protocol mint fee and counting it against its collection. `supply` of Some mints a semi-fungible with that many units.
+#[allow(clippy::too_many_arguments)]
+fn mint_authority_nft(
+    accounts: AuthorityMint,
+    token_id: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+    to: Pubkey,
+    soulbound: bool,
+    supply: Option<u64>,
+) -> Result<()> {
+    let AuthorityMint {
+        nft,
+        authority,
+        mint_fee_lamports,
+        universal_nft_state,
+        treasury,
+        nft_info,
+        owner_index,
+        supply_index,
+        mint_index,
+        collection,
+    } = accounts;
+    require_gte!(MAX_NAME_LEN, name.len(), UniversalNFTError::NameTooLong);
+    require_gte!(MAX_SYMBOL_LEN, symbol.len(), UniversalNFTError::SymbolTooLong);
+    require_gte!(MAX_URI_LEN, uri.len(), UniversalNFTError::UriTooLong);
+    universal_nft_state.check_supply(1)?;
+    if let Some(supply) = supply {
+        require!(supply > 0, UniversalNFTError::InvalidAmount);
+        require!(!soulbound, UniversalNFTError::SemiFungibleUnsupported);
+    }
+
+    // Collection mints are gated by the collection authority and its supply cap
+    let royalty = match collection {
+        Some(collection) => {
+            require_keys_eq!(collection.authority, authority, UniversalNFTError::Unauthorized);
+            collection.check_supply()?;
+            collection.supply = collection.supply.checked_add(1).ok_or(UniversalNFTError::SupplyOverflow)?;
+            nft_info.collection = Some(collection.key());
+            collection.royalty.clone()
+        }
+        None => None,
+    };
+
+    // Collect the mint fee into the treasury
+    collect_mint_fee(
+        nft.payer.clone(),
+        treasury,
+        nft.system_program.clone(),
+        universal_nft_state,
+        token_id,
+        mint_fee_lamports,
+    )?;
+
+    let pda = nft.pda.clone();
+    let pda_bump = nft.pda_bump;
+    let mint = nft.mint.clone();
+    let token_account = nft.token_account.clone();
+    let token_program = nft.token_program.clone();
+    mint_new_nft(nft, &name, &symbol, &uri, royalty.as_ref(), supply.unwrap_or(1))?;
+
+    // Soulbound NFTs are frozen in the recipient's wallet for good
+    if soulbound {
+        let pda_seeds: &[&[u8]] = &[b"connected", &[pda_bump]];
+        let cpi_accounts = token::FreezeAccount {
+            account: token_account,
+            mint: mint.clone(),
+            authority: pda,
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, &[pda_seeds]);
+        token::freeze_account(cpi_ctx)?;
+    }
+    nft_info.soulbound = soulbound;
+
+    record_minted_nft(
+        universal_nft_state,
+        nft_info,
+        owner_index,
+        supply_index,
+        mint_index,
+        MintedNft {
+            token_id,
+            name,
+            symbol,
+            uri,
+            owner: to,
+            mint: mint.key(),
+            semi_fungible: supply.is_some(),
+        },
+    )
+}
+
+// Mint the next token id to the minter of a sale path, charging the protocol mint fee
+fn mint_sale_nft(
+    sale: &mut SaleMint,
+    bumps: &SaleMintBumps,
+    name: &str,
+    symbol: &str,
+    base_uri: &str,
+) -> Result<()> {
+    let token_id = sale.universal_nft_state.next_token_id;
+    sale.universal_nft_state.check_supply(1)?;
+
+    collect_mint_fee(
+        sale.minter.to_account_info(),
+        &mut sale.treasury,
+        sale.system_program.to_account_info(),
+        &mut sale.universal_nft_state,
+        token_id,
+        sale.config.mint_fee_lamports,
+    )?;
+
+    let uri = format!("{}{}", base_uri, token_id);
+    require_gte!(MAX_URI_LEN, uri.len(), UniversalNFTError::UriTooLong);
+    mint_new_nft(
+        NewNftAccounts {
+            payer: sale.minter.to_account_info(),
+            pda: sale.pda.to_account_info(),
+            pda_bump: bumps.pda,
+            mint: sale.mint.to_account_info(),
+            token_account: sale.token_account.to_account_info(),
+            metadata: sale.metadata.to_account_info(),
+            update_authority: sale.update_authority.to_account_info(),
+            update_authority_is_signer: false,
+            token_program: sale.token_program.to_account_info(),
+            metadata_program: sale.metadata_program.to_account_info(),
+            system_program: sale.system_program.to_account_info(),
+            rent: sale.rent.to_account_info(),
+        },
+        name,
+        symbol,
+        &uri,
+        None,
+        1,
+    )?;
+
+    let owner = sale.minter.key();
+    let mint = sale.mint.key();
+    record_minted_nft(
+        &mut sale.universal_nft_state,
+        &mut sale.nft_info,
+        &mut sale.owner_index,
+        &mut sale.supply_index,
+        &mut sale.mint_index,
+        MintedNft {
+            token_id,
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri,
+            owner,
+            mint,
+            semi_fungible: false,
+        },
+    )
+}
+
+// Verify a sorted-pair keccak Merkle proof for `leaf` against `root`
+fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        anchor_lang::solana_program::keccak::hashv(&[&left, &right]).to_bytes()
+    });
+    computed == *root
+}
+
+// Inbound Mint: create the NFT's records and mint it to the receiver, or into escrow
+fn receive_mint(ctx: Context<OnCall>, sender: [u8; 20], transfer_data: CrossChainNFTTransfer) -> Result<()> {
+    record_bridge_volume(&mut ctx.accounts.universal_nft_state, &mut ctx.accounts.config, false)?;
+    require!(transfer_data.amount > 0, UniversalNFTError::InvalidAmount);
+    require_gte!(MAX_NAME_LEN, transfer_data.name.len(), UniversalNFTError::NameTooLong);
+    require_gte!(MAX_SYMBOL_LEN, transfer_data.symbol.len(), UniversalNFTError::SymbolTooLong);
+    require_gte!(MAX_URI_LEN, transfer_data.uri.len(), UniversalNFTError::UriTooLong);
+    require!(
+        transfer_data.source_chain.len() <= 8 && transfer_data.origin_contract.len() <= MAX_RECEIVER_LEN,
+        UniversalNFTError::DecodingError
+    );
+
+    // Foreign NFTs get a local id derived from their origin, so two foreign NFTs can never
+    // claim the same one; Solana-native NFTs returning home keep their original id
+    let (token_id, universal_id) = if transfer_data.origin_contract.is_empty() {
+        require!(
+            transfer_data.token_id & FOREIGN_TOKEN_ID_FLAG == 0,
+            UniversalNFTError::InvalidTokenId
+        );
+        (transfer_data.token_id, None)
+    } else {
+        let token_id = derive_foreign_token_id(
+            &transfer_data.source_chain,
+            &transfer_data.origin_contract,
+            &transfer_data.origin_token_id,
+        );
+        (token_id, Some(transfer_data.origin_token_id))
+    };
+
+    // NFTInfo shares the token id keyed namespace used by mint and burn
+    let token_id_bytes = token_id.to_le_bytes();
+    let mint_key = ctx.accounts.mint_account.key();
+    let (nft_info_key, nft_info_bump) =
+        Pubkey::find_program_address(&[b"nft_info", token_id_bytes.as_ref()], ctx.program_id);
+    let (mint_index_key, mint_index_bump) =
+        Pubkey::find_program_address(&[b"mint_index", mint_key.as_ref()], ctx.program_id);
+    require_keys_eq!(ctx.accounts.nft_info.key(), nft_info_key, UniversalNFTError::InvalidIndexAccount);
+    require_keys_eq!(ctx.accounts.mint_index.key(), mint_index_key, UniversalNFTError::InvalidIndexAccount);
+
+    let pda_info = ctx.accounts.pda.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+    let source_chain = source_chain_id(&transfer_data.source_chain).unwrap_or_default();
+
+    // A token Solana already knows reuses its records: a burned token returning home, or more
+    // units of a semi-fungible still live here. Either must come back with the metadata it left
+    // with, so a compromised counterpart cannot rewrite it on re-entry.
+    let (enters_supply, semi_fungible) = if !ctx.accounts.nft_info.data_is_empty() {
+        let mut nft_info = NFTInfo::try_deserialize(&mut &ctx.accounts.nft_info.try_borrow_data()?[..])?;
+        require_keys_eq!(nft_info.mint, mint_key, UniversalNFTError::InvalidIndexAccount);
+        require!(
+            nft_info.is_burned || nft_info.semi_fungible,
+            UniversalNFTError::SemiFungibleUnsupported
+        );
+        require!(
+            nft_info.semi_fungible || transfer_data.amount == 1,
+            UniversalNFTError::InvalidAmount
+        );
+        if nft_info.metadata_hash != [0; 32] {
+            require!(
+                metadata_hash(&transfer_data.name, &transfer_data.symbol, &transfer_data.uri)
+                    == nft_info.metadata_hash,
+                UniversalNFTError::MetadataHashMismatch
+            );
+        }
+        let returning = nft_info.is_burned;
+        if returning {
+            ctx.accounts.universal_nft_state.check_supply(1)?;
+            ctx.accounts.universal_nft_state.increase_supply(1)?;
+            if !nft_info.semi_fungible {
+                nft_info.owner = transfer_data.receiver;
+            }
+            nft_info.is_burned = false;
+            nft_info.burned_slot = 0;
+            nft_info.cross_chain_data = None;
+        }
+        nft_info.record_provenance(ProvenanceAction::BridgeIn, source_chain, transfer_data.receiver)?;
+        nft_info.try_serialize(&mut &mut ctx.accounts.nft_info.try_borrow_mut_data()?[..])?;
+        (returning, nft_info.semi_fungible)
+    } else {
+        ctx.accounts.universal_nft_state.check_supply(1)?;
+        ctx.accounts.universal_nft_state.increase_supply(1)?;
+        create_pda_funded_by_program(
+            &pda_info,
+            &ctx.accounts.nft_info,
+            &system_program_info,
+            8 + NFTInfo::INIT_SPACE,
+            ctx.program_id,
+            &[b"nft_info", token_id_bytes.as_ref(), &[nft_info_bump]],
+        )?;
+        let mut nft_info = NFTInfo {
+            token_id: token_id,
+            name: transfer_data.name.clone(),
+            symbol: transfer_data.symbol.clone(),
+            uri: transfer_data.uri.clone(),
+            owner: transfer_data.receiver,
+            mint: mint_key,
+            universal_id,
+            version: NFT_INFO_VERSION,
+            semi_fungible: transfer_data.amount > 1,
+            ..Default::default()
+        };
+        nft_info.record_provenance(ProvenanceAction::BridgeIn, source_chain, transfer_data.receiver)?;
+        nft_info.try_serialize(&mut &mut ctx.accounts.nft_info.try_borrow_mut_data()?[..])?;
+
+        create_pda_funded_by_program(
+            &pda_info,
+            &ctx.accounts.mint_index,
+            &system_program_info,
+            8 + MintIndex::INIT_SPACE,
+            ctx.program_id,
+            &[b"mint_index", mint_key.as_ref(), &[mint_index_bump]],
+        )?;
+        MintIndex { mint: mint_key, token_id: token_id }
+            .try_serialize(&mut &mut ctx.accounts.mint_index.try_borrow_mut_data()?[..])?;
+        (true, nft_info.semi_fungible)
+    };
+
+    // Mint straight to the receiver when their wallet and ATA are supplied; otherwise
+    // the NFT is held in escrow by the program PDA until the receiver claims it
+    let destination = match (
+        ctx.accounts.receiver.as_ref(),
+        ctx.accounts.receiver_ata.as_ref(),
+        ctx.accounts.associated_token_program.as_ref(),
+    ) {
+        (Some(receiver), Some(receiver_ata), Some(associated_token_program)) => {
+            require_keys_eq!(receiver.key(), transfer_data.receiver, UniversalNFTError::InvalidReceiver);
+            require_keys_eq!(
+                receiver_ata.key(),
+                get_associated_token_address(&transfer_data.receiver, &mint_key),
+                UniversalNFTError::InvalidReceiver
+            );
+            if receiver_ata.data_is_empty() {
+                create_ata_funded_by_program(
+                    &pda_info,
+                    ctx.bumps.pda,
+                    receiver_ata,
+                    receiver,
+                    &ctx.accounts.mint_account.to_account_info(),
+                    &system_program_info,
+                    &ctx.accounts.token_program.to_account_info(),
+                    associated_token_program,
+                )?;
+            }
+            receiver_ata.to_account_info()
+        }
+        _ => {
+            let (Some(pending_claim), Some(pda_ata)) =
+                (ctx.accounts.pending_claim.as_ref(), ctx.accounts.pda_ata.as_ref())
+            else {
+                return err!(UniversalNFTError::InvalidReceiver);
+            };
+            let (pending_claim_key, pending_claim_bump) = Pubkey::find_program_address(
+                &[b"pending_claim", token_id_bytes.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(pending_claim.key(), pending_claim_key, UniversalNFTError::InvalidIndexAccount);
+            // Further units for a receiver who has not claimed yet join the same escrow
+            let claim = if pending_claim.data_is_empty() {
+                create_pda_funded_by_program(
+                    &pda_info,
+                    pending_claim,
+                    &system_program_info,
+                    8 + PendingClaim::INIT_SPACE,
+                    ctx.program_id,
+                    &[b"pending_claim", token_id_bytes.as_ref(), &[pending_claim_bump]],
+                )?;
+                PendingClaim {
+                    token_id: token_id,
+                    receiver: transfer_data.receiver,
+                    mint: mint_key,
+                    amount: transfer_data.amount,
+                }
+            } else {
+                let mut claim = PendingClaim::try_deserialize(&mut &pending_claim.try_borrow_data()?[..])?;
+                require_keys_eq!(claim.receiver, transfer_data.receiver, UniversalNFTError::InvalidReceiver);
+                claim.amount = claim.amount.checked_add(transfer_data.amount).ok_or(UniversalNFTError::CounterOverflow)?;
+                claim
+            };
+            claim.try_serialize(&mut &mut pending_claim.try_borrow_mut_data()?[..])?;
+            pda_ata.to_account_info()
+        }
+    };
+
+    require_keys_eq!(
+        ctx.accounts.owner_index.owner,
+        transfer_data.receiver,
+        UniversalNFTError::OwnerIndexMismatch
+    );
+    // Semi-fungibles have many holders and stay out of owner indexes
+    if enters_supply {
+        if !semi_fungible {
+            ctx.accounts.owner_index.insert(token_id)?;
+        }
+        ctx.accounts.supply_index.insert(token_id)?;
+    }
+
+    if let Some(chain_stats) = ctx.accounts.chain_stats.as_mut() {
+        require!(
+            source_chain_id(&transfer_data.source_chain) == Some(chain_stats.chain_id),
+            UniversalNFTError::ChainStatsMismatch
+        );
+        chain_stats.inbound_count = chain_stats.inbound_count.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+    }
+
+    // Mint the NFT on Solana
+    if enters_supply {
+        require_nft_supply(&ctx.accounts.mint_account, 0)?;
+    }
+    let mint_accounts = MintTo {
+        mint: ctx.accounts.mint_account.to_account_info(),
+        to: destination,
+        authority: ctx.accounts.pda.to_account_info(),
+    };
+
+    let seeds = &[b"connected".as_ref(), &[ctx.bumps.pda]];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        mint_accounts,
+        signer_seeds,
+    );
+
+    mint_to(cpi_ctx, transfer_data.amount)?;
+
+    let seq = ctx.accounts.universal_nft_state.next_event_seq()?;
+    let clock = Clock::get()?;
+    let transfer_id = inbound_transfer_id(&sender, &transfer_data)?;
+    emit_cpi!(CrossChainTransferReceived {
+        seq,
+        token_id: token_id,
+        transfer_id,
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sender,
+        receiver: transfer_data.receiver,
+        name: transfer_data.name,
+        symbol: transfer_data.symbol,
+        uri: transfer_data.uri,
+        extra_data: transfer_data.extra_data,
+    });
+
+    Ok(())
+}
+
+// Decode a MessageType-tagged Borsh envelope and dispatch it to the handler for its type
+fn receive_envelope(ctx: Context<OnCall>, sender: [u8; 20], data: &[u8]) -> Result<()> {
+    let envelope = decode_inbound_envelope(data)?;
+    dispatch_envelope(ctx, sender, envelope)
+}
+
+// Decode a Borsh envelope, mapping codec failures to program errors
+fn decode_inbound_envelope(data: &[u8]) -> Result<Envelope> {
+    decode_envelope(data).map_err(|error| {
+        match error {
+            EnvelopeError::UnsupportedMessageType => UniversalNFTError::UnsupportedMessageType,
+            EnvelopeError::Malformed => UniversalNFTError::DecodingError,
+        }
+        .into()
+    })
+}
+
+// Route a decoded envelope to its handler
+fn dispatch_envelope(ctx: Context<OnCall>, sender: [u8; 20], envelope: Envelope) -> Result<()> {
+    match envelope {
+        Envelope::Mint(transfer_data) => receive_mint(ctx, sender, transfer_data),
+        Envelope::Burn(burn) => receive_burn(ctx, burn),
+        Envelope::MetadataUpdate(update) => receive_metadata_update(ctx, update),
+        Envelope::CompactMint(compact) => {
+            let uri = resolve_registered_uri(ctx.accounts.uri_registry.as_ref(), &compact.uri_hash)?;
+            receive_mint(ctx, sender, compact.with_uri(uri))
+        }
+    }
+}
+
+// Look up the URI a compact message hashes in the registry entry supplied by the caller
+fn resolve_registered_uri(uri_registry: Option<&Account<UriRegistry>>, hash: &[u8; 32]) -> Result<String> {
+    let uri_registry = uri_registry.ok_or(UniversalNFTError::UriNotRegistered)?;
+    let (expected, _) = Pubkey::find_program_address(&[b"uri_registry", hash.as_ref()], &crate::ID);
+    require_keys_eq!(uri_registry.key(), expected, UniversalNFTError::UriNotRegistered);
+    require!(uri_hash(&uri_registry.uri) == *hash, UniversalNFTError::UriNotRegistered);
+    Ok(uri_registry.uri.clone())
+}
+
+// Pass on a deposit the Gateway credited to the program PDA with an inbound call: to the
+// receiver named by the message, or to the treasury when configured or when there is none.
+// SPL deposits move out of the PDA's associated token account, where the Gateway credits them;
+// without token accounts the deposit is SOL. Only the call's `amount` ever leaves, never the
+// balance the PDA already held.
+fn forward_inbound_deposit(
+    accounts: &mut OnCall,
+    pda_bump: u8,
+    amount: u64,
+    receiver: Option<Pubkey>,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let recipient = match receiver.filter(|_| !accounts.config.forward_deposits_to_treasury) {
+        Some(receiver) => accounts
+            .receiver
+            .as_ref()
+            .filter(|account| account.key() == receiver)
+            .ok_or(UniversalNFTError::InvalidDepositRecipient)?
+            .to_account_info(),
+        None => accounts
+            .treasury
+            .as_ref()
+            .ok_or(UniversalNFTError::InvalidDepositRecipient)?
+            .to_account_info(),
+    };
+
+    match (
+        accounts.deposit_token_account.as_ref(),
+        accounts.recipient_token_account.as_ref(),
+    ) {
+        (Some(from), Some(to)) => {
+            // NFTs escrowed for their receivers are never a deposit
+            require!(
+                from.key() == get_associated_token_address(&accounts.pda.key(), &from.mint)
+                    && from.mint != accounts.mint_account.key()
+                    && from.amount >= amount,
+                UniversalNFTError::InvalidDepositRecipient
+            );
+            require!(
+                to.mint == from.mint && to.owner == recipient.key(),
+                UniversalNFTError::InvalidDepositRecipient
+            );
+            let pda_seeds: &[&[u8]] = &[b"connected", &[pda_bump]];
+            let cpi_accounts = token::Transfer {
+                from: from.to_account_info(),
+                to: to.to_account_info(),
+                authority: accounts.pda.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[pda_seeds],
+            );
+            token::transfer(cpi_ctx, amount)
+        }
+        (None, None) => {
+            debit_program_funds(&accounts.pda.to_account_info(), amount)?;
+            recipient.add_lamports(amount)?;
+            if let Some(treasury) = accounts.treasury.as_mut().filter(|treasury| treasury.key() == recipient.key()) {
+                treasury.total_collected =
+                    treasury.total_collected.checked_add(amount).ok_or(UniversalNFTError::FeeOverflow)?;
+            }
+            Ok(())
+        }
+        _ => err!(UniversalNFTError::InvalidDepositRecipient),
+    }
+}
+
+// Inbound Burn: retire a wrapped NFT still held in escrow by the program PDA
+fn receive_burn(ctx: Context<OnCall>, burn: CrossChainBurn) -> Result<()> {
+    let token_id_bytes = burn.token_id.to_le_bytes();
+    let (nft_info_key, _) =
+        Pubkey::find_program_address(&[b"nft_info", token_id_bytes.as_ref()], ctx.program_id);
+    let (pending_claim_key, _) =
+        Pubkey::find_program_address(&[b"pending_claim", token_id_bytes.as_ref()], ctx.program_id);
+    require_keys_eq!(ctx.accounts.nft_info.key(), nft_info_key, UniversalNFTError::InvalidIndexAccount);
+    let (Some(pending_claim), Some(pda_ata)) =
+        (ctx.accounts.pending_claim.as_ref(), ctx.accounts.pda_ata.as_ref())
+    else {
+        return err!(UniversalNFTError::TokenNotEscrowed);
+    };
+    require_keys_eq!(pending_claim.key(), pending_claim_key, UniversalNFTError::TokenNotEscrowed);
+    require!(
+        pending_claim.owner == ctx.program_id && !pending_claim.data_is_empty(),
+        UniversalNFTError::TokenNotEscrowed
+    );
+
+    let mut nft_info = NFTInfo::try_deserialize(&mut &ctx.accounts.nft_info.try_borrow_data()?[..])?;
+    require!(!nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+    require_keys_eq!(nft_info.mint, ctx.accounts.mint_account.key(), UniversalNFTError::InvalidTokenId);
+
+    let pda_seeds: &[&[u8]] = &[b"connected", &[ctx.bumps.pda]];
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint_account.to_account_info(),
+        from: pda_ata.to_account_info(),
+        authority: ctx.accounts.pda.to_account_info(),
+    };
+    burn(
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, &[pda_seeds]),
+        1,
+    )?;
+    close_program_account(pending_claim, &ctx.accounts.pda.to_account_info())?;
+
+    nft_info.is_burned = true;
+    nft_info.burned_slot = Clock::get()?.slot;
+    nft_info.try_serialize(&mut &mut ctx.accounts.nft_info.try_borrow_mut_data()?[..])?;
+    require_keys_eq!(
+        ctx.accounts.owner_index.owner,
+        nft_info.owner,
+        UniversalNFTError::OwnerIndexMismatch
+    );
+    ctx.accounts.owner_index.remove(burn.token_id)?;
+    ctx.accounts.supply_index.remove(burn.token_id)?;
+    ctx.accounts.universal_nft_state.decrease_supply()?;
+
+    emit!(NFTBurned {
+        seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+        token_id: burn.token_id,
+        owner: nft_info.owner,
+        destination_chain: String::new(),
+        destination_receiver: String::new(),
+        uri: nft_info.uri,
+    });
+    Ok(())
+}
+
+// Inbound MetadataUpdate: sync the name and URI of an NFT, growing its account from the PDA if needed
+fn receive_metadata_update(ctx: Context<OnCall>, update: CrossChainMetadataUpdate) -> Result<()> {
+    require_gte!(MAX_NAME_LEN, update.name.len(), UniversalNFTError::NameTooLong);
+    require!(
+        update.uri.len() <= ctx.accounts.config.max_uri_len as usize,
+        UniversalNFTError::UriTooLong
+    );
+    let (nft_info_key, _) = Pubkey::find_program_address(
+        &[b"nft_info", update.token_id.to_le_bytes().as_ref()],
+        ctx.program_id,
+    );
+    let nft_info_account = ctx.accounts.nft_info.to_account_info();
+    require_keys_eq!(nft_info_account.key(), nft_info_key, UniversalNFTError::InvalidIndexAccount);
+
+    let mut nft_info = NFTInfo::try_deserialize(&mut &nft_info_account.try_borrow_data()?[..])?;
+    require!(!nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+    nft_info.name = update.name;
+    nft_info.uri = update.uri;
+
+    let space = NFTInfo::space_for_uri(nft_info.uri.len());
+    if space > nft_info_account.data_len() {
+        let lamports = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(nft_info_account.lamports());
+        if lamports > 0 {
+            debit_program_funds(&ctx.accounts.pda.to_account_info(), lamports)?;
+            nft_info_account.add_lamports(lamports)?;
+        }
+        nft_info_account.resize(space)?;
+    }
+    nft_info.try_serialize(&mut &mut nft_info_account.try_borrow_mut_data()?[..])?;
+
+    emit!(NFTMetadataUpdated {
+        seq: ctx.accounts.universal_nft_state.next_event_seq()?,
+        token_id: update.token_id,
+        uri: nft_info.uri,
+    });
+    Ok(())
+}
+
+// Decode an account still in its baseline layout `L`, under the discriminator of its current type `T`
+fn decode_baseline<T: anchor_lang::Discriminator, L: AnchorDeserialize>(data: &[u8]) -> Result<L> {
+    let mut body = data
+        .strip_prefix(T::DISCRIMINATOR)
+        .ok_or(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch)?;
+    L::deserialize(&mut body).map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+}
+
+// Write a migrated account in the current layout, first growing it to `space` with rent from `payer`
+fn store_migrated_account<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    space: usize,
+    value: &T,
+) -> Result<()> {
+    if account.data_len() < space {
+        let lamports = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+        if lamports > 0 {
+            let transfer_accounts = system_program::Transfer { from: payer.clone(), to: account.clone() };
+            system_program::transfer(CpiContext::new(system_program_info.clone(), transfer_accounts), lamports)?;
+        }
+        account.resize(space)?;
+    }
+    value.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])
+}
+
+// Transfer a revert message refers to, as carried by the CrossChainNFTTransfer built for it:
+// the transfer id in `extra_data` and the destination chain in `source_chain`. Zeroed when the
+// message does not decode, which matches no record.
+fn revert_target(data: &[u8]) -> RevertTarget {
+    CrossChainNFTTransfer::deserialize(&mut &data[..])
+        .ok()
+        .and_then(|transfer| {
+            Some(RevertTarget {
+                transfer_id: transfer.extra_data.as_slice().try_into().ok()?,
+                token_id: transfer.token_id,
+                destination_chain_id: source_chain_id(&transfer.source_chain)?,
+            })
+        })
+        .unwrap_or_default()
+}
+
+// Inbound calls and reverts are accepted only as a direct CPI from the configured Gateway: the
+// current top-level instruction must be the Gateway's, with this program its first invocation
+fn require_gateway_caller(instruction_sysvar: &AccountInfo, gateway_program: &Pubkey) -> Result<()> {
+    require_eq!(
+        get_stack_height(),
+        TRANSACTION_LEVEL_STACK_HEIGHT + 1,
+        UniversalNFTError::InvalidCaller
+    );
+    let current_ix = instructions::get_instruction_relative(0, instruction_sysvar)?;
+    require_keys_eq!(current_ix.program_id, *gateway_program, UniversalNFTError::InvalidCaller);
+    Ok(())
+}
+
+// Close a program-owned account opened in a handler, returning its rent to `destination`
+fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> Result<()> {
+    let lamports = account.lamports();
+    account.sub_lamports(lamports)?;
+    destination.add_lamports(lamports)?;
+    account.assign(&system_program::ID);
+    account.resize(0)?;
+    Ok(())
+}
+
+// Take rent for a new account from a program-owned funder, which must stay rent-exempt itself
+fn debit_program_funds(funder: &AccountInfo, lamports: u64) -> Result<()> {
+    let reserve = Rent::get()?.minimum_balance(funder.data_len());
+    require!(
+        funder.lamports().saturating_sub(reserve) >= lamports,
+        UniversalNFTError::PdaUnderfunded
+    );
+    funder.sub_lamports(lamports)?;
+    Ok(())
+}
+
+// Create an associated token account paying rent from the program PDA: the address is
+// prefunded so the associated token program never has to debit the (data-carrying) payer
+#[allow(clippy::too_many_arguments)]
+fn create_ata_funded_by_program<'info>(
+    pda: &AccountInfo<'info>,
+    pda_bump: u8,
+    ata: &AccountInfo<'info>,
+    wallet: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    token_program_info: &AccountInfo<'info>,
+    associated_token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = Rent::get()?
+        .minimum_balance(TokenAccount::LEN)
+        .saturating_sub(ata.lamports());
+    if lamports > 0 {
+        debit_program_funds(pda, lamports)?;
+        ata.add_lamports(lamports)?;
+    }
+    let pda_seeds: &[&[u8]] = &[b"connected", &[pda_bump]];
+    associated_token::create_idempotent(CpiContext::new_with_signer(
+        associated_token_program.clone(),
+        associated_token::Create {
+            payer: pda.clone(),
+            associated_token: ata.clone(),
+            authority: wallet.clone(),
+            mint: mint.clone(),
+            system_program: system_program_info.clone(),
+            token_program: token_program_info.clone(),
+        },
+        &[pda_seeds],
+    ))
+}
+
+// Create a program-owned PDA paying rent from a program-owned account, which cannot
+// act as the source of a system create_account
+fn create_pda_funded_by_program<'info>(
+    funder: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(target.lamports());
+    if lamports > 0 {
+        debit_program_funds(funder, lamports)?;
+        target.add_lamports(lamports)?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program_info.clone(),
+            system_program::Allocate { account_to_allocate: target.clone() },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program_info.clone(),
+            system_program::Assign { account_to_assign: target.clone() },
+            &[seeds],
+        ),
+        owner,
+    )
+}
+
+// Close the signer's emptied NFT token account after bridging out, refunding rent to the signer
+fn close_source_token_account(accounts: &TransferCrossChain) -> Result<()> {
+    // Only the owner, or the close authority they set, can close their token account, and only
+    // once it is empty; operators, and holders keeping semi-fungible units, leave it in place
+    let close_authority = accounts.token_account.close_authority.unwrap_or(accounts.token_account.owner);
+    if close_authority != accounts.signer.key()
+        || token::accessor::amount(&accounts.token_account.to_account_info())? > 0
+    {
+        return Ok(());
+    }
+
+    let cpi_accounts = token::CloseAccount {
+        account: accounts.token_account.to_account_info(),
+        destination: accounts.signer.to_account_info(),
+        authority: accounts.signer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts);
+    token::close_account(cpi_ctx)
+}
+
+// Gateway whitelist entry of an SPL mint, as derived by the Gateway program
+fn gateway_whitelist_address(gateway_program: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"whitelist", mint.as_ref()], gateway_program).0
+}
+
+// Forward the message through the Gateway, depositing `amount` lamports for destination gas.
+// Without gas the message-only `call` is used, so no tokens move through the Gateway.
+fn send_gateway_message(
+    accounts: &TransferCrossChain,
+    amount: u64,
+    message: Vec<u8>,
+    revert_options: Option<RevertOptions>,
+) -> Result<()> {
+    if amount == 0 {
+        // The Gateway PDA rides along as a remaining account for gateways that record calls
+        let gateway_cpi_ctx = CpiContext::new(
+            accounts.gateway_program.to_account_info(),
+            gateway::cpi::accounts::Call {
+                signer: accounts.signer.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(vec![accounts.gateway_pda.to_account_info()]);
+        return gateway::cpi::call(
+            gateway_cpi_ctx,
+            accounts.config.universal_contract,
+            message,
+            revert_options,
+        );
+    }
+
+    let gateway_cpi_accounts = gateway::cpi::accounts::Deposit {
+        signer: accounts.signer.to_account_info(),
+        pda: accounts.gateway_pda.to_account_info(),
+        system_program: accounts.system_program.to_account_info(),
+    };
+
+    let gateway_cpi_ctx = CpiContext::new(
+        accounts.gateway_program.to_account_info(),
+        gateway_cpi_accounts,
+    );
+
+    gateway::cpi::deposit_and_call(
+        gateway_cpi_ctx,
+        amount,
+        accounts.config.universal_contract,
+        message,
+        revert_options,
+    )
+}
+
+// Send a queued transfer's stored Gateway call, with the cranker depositing the gas escrowed in
+// the pending transfer after it is moved over to the cranker
+fn dispatch_pending_transfer<'info>(
+    pending_transfer: &mut PendingTransfer,
+    pending_info: &AccountInfo<'info>,
+    cranker: &AccountInfo<'info>,
+    gateway_pda: &AccountInfo<'info>,
+    gateway_program: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    universal_contract: [u8; 20],
+) -> Result<()> {
+    let gas_amount = pending_transfer.gas_escrow;
+    pending_info.sub_lamports(gas_amount)?;
+    cranker.add_lamports(gas_amount)?;
+    pending_transfer.gas_escrow = 0;
+
+    let revert_options = RevertOptions {
+        revert_address: pending_transfer.revert_address,
+        call_on_revert: pending_transfer.call_on_revert,
+        abort_address: pending_transfer.abort_address,
+        revert_message: pending_transfer.revert_message.clone(),
+        on_revert_gas_limit: pending_transfer.on_revert_gas_limit,
+    };
+    let gateway_cpi_accounts = gateway::cpi::accounts::Deposit {
+        signer: cranker.clone(),
+        pda: gateway_pda.clone(),
+        system_program: system_program_info.clone(),
+    };
+    let gateway_cpi_ctx = CpiContext::new(gateway_program.clone(), gateway_cpi_accounts);
+    gateway::cpi::deposit_and_call(
+        gateway_cpi_ctx,
+        gas_amount,
+        universal_contract,
+        pending_transfer.message.clone(),
+        Some(revert_options),
+    )?;
+
+    pending_transfer.dispatched = true;
+    pending_transfer.updated_slot = Clock::get()?.slot;
+    Ok(())
+}
+
+// Accounts of the Wormhole core bridge's post_message instruction
+struct WormholeAccounts<'info> {
+    program: AccountInfo<'info>,
+    bridge: AccountInfo<'info>,
+    message: AccountInfo<'info>,
+    emitter: AccountInfo<'info>,
+    emitter_bump: u8,
+    sequence: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    fee_collector: AccountInfo<'info>,
+    clock: AccountInfo<'info>,
+    rent: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+// Post `payload` through the Wormhole core bridge from the program's emitter PDA, first
+// paying the bridge's message fee from the payer
+fn post_wormhole_message(accounts: WormholeAccounts, nonce: u32, payload: Vec<u8>) -> Result<()> {
+    // BridgeData: guardian_set_index u32, last_lamports u64, guardian_set_expiration_time u32, fee u64
+    let fee = {
+        let data = accounts.bridge.try_borrow_data()?;
+        let fee_bytes = data.get(16..24).ok_or(UniversalNFTError::InvalidWormholeProgram)?;
+        u64::from_le_bytes(fee_bytes.try_into().unwrap())
+    };
+    if fee > 0 {
+        let fee_accounts = system_program::Transfer {
+            from: accounts.payer.clone(),
+            to: accounts.fee_collector.clone(),
+        };
+        system_program::transfer(CpiContext::new(accounts.system_program.clone(), fee_accounts), fee)?;
+    }
+
+    // Instruction 1 is PostMessage { nonce, payload, consistency_level }, 1 = finalized
+    let mut data = vec![1u8];
+    (nonce, payload, 1u8)
+        .serialize(&mut data)
+        .map_err(|_| UniversalNFTError::SerializationError)?;
+    let instruction = anchor_lang::solana_program::instruction::Instruction {
+        program_id: accounts.program.key(),
+        accounts: vec![
+            AccountMeta::new(accounts.bridge.key(), false),
+            AccountMeta::new(accounts.message.key(), true),
+            AccountMeta::new_readonly(accounts.emitter.key(), true),
+            AccountMeta::new(accounts.sequence.key(), false),
+            AccountMeta::new(accounts.payer.key(), true),
+            AccountMeta::new(accounts.fee_collector.key(), false),
+            AccountMeta::new_readonly(accounts.clock.key(), false),
+            AccountMeta::new_readonly(accounts.rent.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data,
+    };
+    let emitter_seeds: &[&[u8]] = &[b"emitter", &[accounts.emitter_bump]];
+    anchor_lang::solana_program::program::invoke_signed(
+        &instruction,
+        &[
+            accounts.bridge,
+            accounts.message,
+            accounts.emitter,
+            accounts.sequence,
+            accounts.payer,
+            accounts.fee_collector,
+            accounts.clock,
+            accounts.rent,
+            accounts.system_program,
+            accounts.program,
+        ],
+        &[emitter_seeds],
+    )?;
+    Ok(())
+}
+
+// Invoke a LayerZero endpoint instruction with the caller-supplied endpoint accounts, signing
+// as the program's OApp PDA wherever it appears among them
+fn invoke_layerzero_endpoint<'info>(
+    endpoint: &AccountInfo<'info>,
+    oapp_bump: u8,
+    endpoint_accounts: &[AccountInfo<'info>],
+    instruction_name: &str,
+    params: impl AnchorSerialize,
+) -> Result<()> {
+    let oapp_seeds: &[&[u8]] = &[b"lz_oapp", &[oapp_bump]];
+    let oapp_key = Pubkey::create_program_address(oapp_seeds, &crate::ID)
+        .map_err(|_| UniversalNFTError::InvalidLayerZeroEndpoint)?;
+
+    // Anchor instruction data: sighash of `global:<name>` followed by the Borsh params
+    let preimage = format!("global:{}", instruction_name);
+    let mut data = anchor_lang::solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8].to_vec();
+    params.serialize(&mut data).map_err(|_| UniversalNFTError::SerializationError)?;
+
+    let instruction = anchor_lang::solana_program::instruction::Instruction {
+        program_id: endpoint.key(),
+        accounts: endpoint_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer || account.key() == oapp_key,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    };
+    let mut account_infos = endpoint_accounts.to_vec();
+    account_infos.push(endpoint.clone());
+    anchor_lang::solana_program::program::invoke_signed(&instruction, &account_infos, &[oapp_seeds])?;
+    Ok(())
+}
+
+// Verify that the instruction preceding this one is an Ed25519 program instruction
+// checking `signer`'s signature over exactly `message`
+fn verify_ed25519_permit(instruction_sysvar: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current_index = instructions::load_current_index_checked(instruction_sysvar)?;
+    require!(current_index > 0, UniversalNFTError::InvalidPermitSignature);
+    let ed25519_ix = instructions::load_instruction_at_checked(
+        (current_index - 1) as usize,
+        instruction_sysvar,
+    )?;
+    require!(
+        ed25519_ix.program_id == ed25519_program::ID && ed25519_ix.accounts.is_empty(),
+        UniversalNFTError::InvalidPermitSignature
+    );
+
+    // Layout: [num_signatures: u8, padding: u8, Ed25519SignatureOffsets (7 x u16)]
+    let data = &ed25519_ix.data;
+    require!(data.len() >= 16 && data[0] == 1, UniversalNFTError::InvalidPermitSignature);
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let signature_ix_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_ix_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix_index = read_u16(14);
+
+    // All referenced data must live inside the Ed25519 instruction itself
+    require!(
+        signature_ix_index == u16::MAX && public_key_ix_index == u16::MAX && message_ix_index == u16::MAX,
+        UniversalNFTError::InvalidPermitSignature
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(UniversalNFTError::InvalidPermitSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(UniversalNFTError::InvalidPermitSignature)?;
+    require!(
+        public_key == signer.as_ref() && signed_message == message,
+        UniversalNFTError::InvalidPermitSignature
+    );
+
+    Ok(())
+}
+
+/// Who authorizes burning the NFT for an outbound transfer
+enum OutboundAuthority<'info> {
+    /// The transaction signer, as owner or approved operator
+    Signer,
+    /// The owner via an off-chain permit, or a collection migration the owner delegated to,
+    /// burning through the program PDA delegate
+    Permit {
+        owner: Pubkey,
+        pda: AccountInfo<'info>,
+        pda_bump: u8,
+    },
+}
+
+/// Prepared outbound message and revert options for a Gateway CPI
+struct OutboundTransfer {
+    serialized_message: Vec<u8>,
+    revert_options: RevertOptions,
+    seq: u64,
+    transfer_id: [u8; 32],
+    /// The caller's receiver, handed back for the transfer event
+    recipient_address: Vec<u8>,
+}
+
+/// Outbound transfer named by a revert message, keying the records `on_revert` updates
+#[derive(Default)]
+struct RevertTarget {
+    transfer_id: [u8; 32],
+    token_id: u64,
+    destination_chain_id: u64,
+}
+
+// Shared validation, fee collection and burn logic for outbound cross-chain transfers
+#[allow(clippy::too_many_arguments)]
+fn prepare_outbound_transfer<'info>(
+    accounts: &mut TransferCrossChain<'info>,
+    authority: OutboundAuthority<'info>,
+    token_id: u64,
+    recipient_address: Vec<u8>,
+    destination_chain_id: u64,
+    metadata_uri: String,
+    on_revert_gas_limit: Option<u64>,
+    revert_params: Option<RevertParams>,
+    recipient_checksum: Option<String>,
+    extra_data: Vec<u8>,
+    final_destination: Option<FinalDestination>,
+    amount: u64,
+    transport: Transport,
+) -> Result<OutboundTransfer> {
+    require_gte!(MAX_URI_LEN, metadata_uri.len(), UniversalNFTError::UriTooLong);
+    require_gte!(MAX_EXTRA_DATA_LEN, extra_data.len(), UniversalNFTError::ExtraDataTooLong);
+    require!(!accounts.config.paused, UniversalNFTError::BridgePaused);
+    record_bridge_volume(&mut accounts.universal_nft_state, &mut accounts.config, true)?;
+
+    // Destination chain must be registered and enabled before anything is burned
+    require!(
+        destination_chain_id != accounts.config.self_chain_id,
+        UniversalNFTError::SelfChainDestination
+    );
+    let chain_config = load_enabled_chain_config(&accounts.chain_config)?;
+    require!(chain_config.transport == transport, UniversalNFTError::TransportMismatch);
+
+    // Validate the receiver against the destination chain's address format
+    chain_config.validate_receiver(&recipient_address)?;
+
+    // The universal contract validates the onward hop against its own registry; only its shape
+    // is checked here
+    let (final_destination_chain, final_receiver) = match final_destination {
+        Some(FinalDestination { chain_id, receiver }) => {
+            require!(
+                chain_id != destination_chain_id
+                    && !receiver.is_empty()
+                    && receiver.len() <= MAX_RECEIVER_LEN,
+                UniversalNFTError::InvalidFinalDestination
+            );
+            (Some(chain_id), receiver)
+        }
+        None => (None, Vec::new()),
+    };
+
+    // Verify the EIP-55 checksummed form when the client supplies one
+    if let Some(checksummed) = recipient_checksum {
+        require!(
+            chain_config.receiver_format == ReceiverFormat::Evm,
+            UniversalNFTError::InvalidReceiver
+        );
+        verify_eip55_checksum(&recipient_address, &checksummed)?;
+    }
+
+    // Resolve the on_revert gas limit against the configured bounds
+    let config = &accounts.config;
+    let on_revert_gas_limit = on_revert_gas_limit.unwrap_or(config.default_on_revert_gas_limit);
+    require!(
+        on_revert_gas_limit <= config.max_on_revert_gas_limit,
+        UniversalNFTError::RevertGasLimitTooHigh
+    );
+
+    // Resolve caller-supplied revert options, defaulting to a revert into the signer
+    let revert_params = revert_params.unwrap_or_default();
+    let revert_address = revert_params.revert_address.unwrap_or(accounts.signer.key());
+    let call_on_revert = revert_params.call_on_revert.unwrap_or(true);
+    
+    // A CPI must come from an allowlisted program; anything else must be this program's own
+    // top-level instruction, signed by the user
+    let current_ix = instructions::get_instruction_relative(0, &accounts.instruction_sysvar)?;
+    if get_stack_height() > TRANSACTION_LEVEL_STACK_HEIGHT {
+        require!(
+            accounts.config.allows_caller(&current_ix.program_id),
+            UniversalNFTError::InvalidCaller
+        );
+    } else {
+        require_keys_eq!(current_ix.program_id, crate::ID, UniversalNFTError::InvalidCaller);
+    }
+    debug_msg!(accounts.config, "Outbound transfer of token {} via {}", token_id, current_ix.program_id);
+
+    let nft_info = &mut accounts.nft_info;
+
+    // A 1/1 NFT moves whole; a semi-fungible may move any part of the holder's units
+    if nft_info.semi_fungible {
+        require!(amount > 0, UniversalNFTError::InvalidAmount);
+    } else {
+        require!(amount == 1, UniversalNFTError::InvalidAmount);
+    }
+    
+    // Ownership is derived from the token account holding the NFT; an approved
+    // operator (or the program PDA, for permits) that is also the token account's
+    // delegate may act for the owner
+    let token_account = &accounts.token_account;
+    let authorized = match &authority {
+        OutboundAuthority::Signer => {
+            let signer_key = accounts.signer.key();
+            token_account.owner == signer_key
+                || (nft_info.operator == Some(signer_key)
+                    && token_account.delegate == COption::Some(signer_key)
+                    && token_account.delegated_amount >= amount)
+        }
+        OutboundAuthority::Permit { owner, pda, .. } => {
+            token_account.owner == *owner
+                && token_account.delegate == COption::Some(pda.key())
+                && token_account.delegated_amount >= amount
+        }
+    };
+    require!(authorized, UniversalNFTError::NotOwner);
+    require!(token_account.amount >= amount, UniversalNFTError::NotOwner);
+    // Units of a semi-fungible have many holders; only a 1/1 NFT tracks its owner
+    let holder = token_account.owner;
+    if !nft_info.semi_fungible {
+        nft_info.owner = holder;
+    }
+
+    // Operator approvals are single-use
+    nft_info.operator = None;
+
+    // Enforce the per-wallet outbound limit for the current epoch
+    let max_transfers = accounts.config.max_transfers_per_epoch;
+    let rate_limit = &mut accounts.rate_limit;
+    let clock = Clock::get()?;
+    if rate_limit.wallet != token_account.owner || rate_limit.epoch != clock.epoch {
+        rate_limit.wallet = token_account.owner;
+        rate_limit.epoch = clock.epoch;
+        rate_limit.count = 0;
+    }
+    require!(
+        max_transfers == 0 || rate_limit.count < max_transfers,
+        UniversalNFTError::RateLimitExceeded
+    );
+    rate_limit.count = rate_limit.count.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+    
+    // Ensure NFT is not already burned, and is still a 1/1 token; a semi-fungible leaves
+    // Solana once its last units do
+    require!(!nft_info.is_burned, UniversalNFTError::AlreadyBurned);
+    if nft_info.semi_fungible {
+        require!(
+            accounts.mint.decimals == 0 && accounts.mint.supply >= amount,
+            UniversalNFTError::InvalidNftSupply
+        );
+    } else {
+        require_nft_supply(&accounts.mint, 1)?;
+    }
+    let leaves_solana = accounts.mint.supply == amount;
+
+    // Collection NFTs bridge only under their collection's bridge mode, into the
+    // collection's counterpart contract on the destination chain
+    let mut target_collection = Vec::new();
+    if let Some(collection_key) = nft_info.collection {
+        let collection = accounts
+            .collection
+            .as_ref()
+            .ok_or(UniversalNFTError::CollectionMismatch)?;
+        require_keys_eq!(collection.key(), collection_key, UniversalNFTError::CollectionMismatch);
+        require!(collection.bridge_mode.allows_outbound(), UniversalNFTError::CollectionBridgeDisabled);
+
+        // Enforce the collection's outbound cap for the current epoch, when it has set one
+        let collection_config = accounts
+            .collection_config
+            .as_ref()
+            .ok_or(UniversalNFTError::InvalidCollectionConfig)?;
+        let (collection_config_key, _) =
+            Pubkey::find_program_address(&[b"collection_config", collection_key.as_ref()], &crate::ID);
+        require_keys_eq!(collection_config.key(), collection_config_key, UniversalNFTError::InvalidCollectionConfig);
+        if !collection_config.data_is_empty() {
+            let mut outbound_cap = CollectionConfig::try_deserialize(&mut &collection_config.data.borrow()[..])?;
+            outbound_cap.record_outbound(clock.epoch)?;
+            outbound_cap.try_serialize(&mut &mut collection_config.try_borrow_mut_data()?[..])?;
+        }
+
+        let collection_address = accounts
+            .collection_address
+            .as_ref()
+            .ok_or(UniversalNFTError::CollectionNotMapped)?;
+        require!(
+            collection_address.collection == collection_key
+                && collection_address.chain_id == destination_chain_id,
+            UniversalNFTError::CollectionNotMapped
+        );
+        target_collection = collection_address.address.clone();
+    }
+
+    // A Solana-native NFT is assigned its universal id the first time it leaves Solana
+    let universal_id = match nft_info.universal_id {
+        Some(universal_id) => universal_id,
+        None => {
+            let universal_id = compute_universal_id(&nft_info.mint, accounts.config.self_chain_id, clock.slot);
+            nft_info.universal_id = Some(universal_id);
+            universal_id
+        }
+    };
+
+    // On-chain traits travel with the NFT
+    let attributes = accounts
+        .attributes
+        .as_ref()
+        .map(|attributes| attributes.entries.clone())
+        .unwrap_or_default();
+
+    // Collect the protocol bridge fee into the treasury
+    let bridge_fee = accounts.config.bridge_fee_lamports;
+    if bridge_fee > 0 {
+        let fee_accounts = system_program::Transfer {
+            from: accounts.signer.to_account_info(),
+            to: accounts.treasury.to_account_info(),
+        };
+        let fee_ctx = CpiContext::new(accounts.system_program.to_account_info(), fee_accounts);
+        system_program::transfer(fee_ctx, bridge_fee)?;
+        let treasury = &mut accounts.treasury;
+        treasury.total_collected = treasury.total_collected.checked_add(bridge_fee).ok_or(UniversalNFTError::FeeOverflow)?;
+    }
+
+    let chain_stats = &mut accounts.chain_stats;
+    chain_stats.chain_id = destination_chain_id;
+    chain_stats.outbound_count = chain_stats.outbound_count.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+    chain_stats.total_fees = chain_stats.total_fees.checked_add(bridge_fee).ok_or(UniversalNFTError::FeeOverflow)?;
+    
+    // Serialize the message for ZetaChain once; the receiver is moved back out afterwards
+    let (serialized_message, recipient_address) = if chain_config.compact_messages {
+        let message_data = CompactCrossChainMessage {
+            message_type: MessageType::CompactMint,
+            token_id,
+            recipient_address,
+            uri_hash: uri_hash(&metadata_uri),
+            target_collection,
+            attributes,
+            universal_id,
+            extra_data,
+            final_destination_chain,
+            final_receiver,
+            amount,
+        };
+        let serialized_message = message_data.try_to_vec().map_err(|_| UniversalNFTError::SerializationError)?;
+        (serialized_message, message_data.recipient_address)
+    } else {
+        let message_data = CrossChainMessage {
+            message_type: MessageType::Mint,
+            token_id,
+            recipient_address,
+            metadata_uri,
+            target_collection,
+            attributes,
+            universal_id,
+            extra_data,
+            final_destination_chain,
+            final_receiver,
+            amount,
+        };
+        let serialized_message = message_data.try_to_vec().map_err(|_| UniversalNFTError::SerializationError)?;
+        (serialized_message, message_data.recipient_address)
+    };
+
+    // Burn the NFT on source chain first
+    let token_account = &accounts.token_account;
+    let mint_account = &accounts.mint;
+    
+    if nft_info.soulbound {
+        thaw_soulbound(
+            &accounts.universal_nft_state,
+            nft_info,
+            accounts.collection.as_deref(),
+            accounts.collection_authority.as_ref(),
+            accounts.pda.as_ref(),
+            token_account.to_account_info(),
+            mint_account.to_account_info(),
+            accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    // Burn token using token program
+    let cpi_program = accounts.token_program.to_account_info();
+    match authority {
+        OutboundAuthority::Signer => {
+            let cpi_accounts = token::Burn {
+                mint: mint_account.to_account_info(),
+                from: token_account.to_account_info(),
+                authority: accounts.signer.to_account_info(),
+            };
+            token::burn(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+        }
+        OutboundAuthority::Permit { pda, pda_bump, .. } => {
+            let cpi_accounts = token::Burn {
+                mint: mint_account.to_account_info(),
+                from: token_account.to_account_info(),
+                authority: pda,
+            };
+            let seeds = &[b"connected".as_ref(), &[pda_bump]];
+            let signer_seeds = &[&seeds[..]];
+            token::burn(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), amount)?;
+        }
+    }
+
+    // Update NFT state to indicate cross-chain transfer
+    nft_info.record_provenance(ProvenanceAction::BridgeOut, destination_chain_id, holder)?;
+    nft_info.metadata_hash = metadata_hash(&nft_info.name, &nft_info.symbol, &nft_info.uri);
+    if leaves_solana {
+        nft_info.is_burned = true;
+        nft_info.burned_slot = clock.slot;
+        if !nft_info.semi_fungible {
+            accounts.owner_index.remove(token_id)?;
+        }
+        accounts.supply_index.remove(token_id)?;
+        accounts.universal_nft_state.decrease_supply()?;
+    }
+    let seq = accounts.universal_nft_state.next_event_seq()?;
+    let transfer_id = outbound_transfer_id(token_id, destination_chain_id, nft_info.transfer_nonce);
+    nft_info.transfer_nonce = nft_info.transfer_nonce.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+
+    // Record the transfer as in flight until it is confirmed or reverted
+    let pending_transfer = &mut accounts.pending_transfer;
+    pending_transfer.token_id = token_id;
+    pending_transfer.owner = holder;
+    pending_transfer.destination_chain_id = destination_chain_id;
+    pending_transfer.transfer_id = transfer_id;
+    pending_transfer.status = TransferStatus::Initiated;
+    pending_transfer.initiated_slot = clock.slot;
+    pending_transfer.updated_slot = clock.slot;
+
+    // Issue the owner's receipt for the in-flight NFT
+    let receipt = &mut accounts.receipt;
+    receipt.owner = holder;
+    receipt.token_id = token_id;
+    receipt.transfer_id = transfer_id;
+    receipt.destination_chain_id = destination_chain_id;
+    receipt.initiated_slot = clock.slot;
+
+    let transfer_record = &mut accounts.transfer_record;
+    transfer_record.transfer_id = transfer_id;
+    transfer_record.token_id = token_id;
+    transfer_record.owner = holder;
+    transfer_record.destination_chain_id = destination_chain_id;
+    transfer_record.status = TransferStatus::Initiated;
+    transfer_record.initiated_slot = clock.slot;
+    transfer_record.updated_slot = clock.slot;
+    nft_info.cross_chain_data = Some(CrossChainData {
+        destination_chain_id,
+        recipient_address: recipient_address.clone(),
+        transfer_timestamp: clock.unix_timestamp,
+        transfer_id,
+    });
+    
+    // The revert message carries everything on_revert needs to restore the NFT, and the
+    // transfer id locating its records
+    let revert_message = CrossChainNFTTransfer {
+        token_id,
+        name: nft_info.name.clone(),
+        symbol: nft_info.symbol.clone(),
+        uri: nft_info.uri.clone(),
+        receiver: holder,
+        source_chain: destination_chain_id.to_le_bytes().to_vec(),
+        origin_contract: Vec::new(),
+        origin_token_id: nft_info.universal_id.unwrap_or_default(),
+        extra_data: transfer_id.to_vec(),
+        amount,
+    }
+    .try_to_vec()
+    .map_err(|_| UniversalNFTError::SerializationError)?;
+    require!(
+        revert_message.len() <= MAX_REVERT_MESSAGE_LEN,
+        UniversalNFTError::RevertMessageTooLong
+    );
+
+    // Keep everything needed to re-send the Gateway call until it has been dispatched
+    require_gte!(
+        MAX_OUTBOUND_MESSAGE_LEN,
+        serialized_message.len(),
+        UniversalNFTError::MessageTooLarge
+    );
+    let pending_transfer = &mut accounts.pending_transfer;
+    pending_transfer.message = serialized_message.clone();
+    pending_transfer.revert_address = revert_address;
+    pending_transfer.call_on_revert = call_on_revert;
+    pending_transfer.abort_address = accounts.config.abort_address;
+    pending_transfer.revert_message = revert_message.clone();
+    pending_transfer.on_revert_gas_limit = on_revert_gas_limit;
+    pending_transfer.dispatched = false;
+    pending_transfer.gas_escrow = 0;
+    pending_transfer.amount = amount;
+
+    // Create revert options for cross-chain call
+    let revert_options = RevertOptions {
+        revert_address,
+        call_on_revert,
+        abort_address: accounts.config.abort_address,
+        revert_message,
+        on_revert_gas_limit,
+    };
+
+    Ok(OutboundTransfer {
+        serialized_message,
+        revert_options,
+        seq,
+        transfer_id,
+        recipient_address,
+    })
+}
+
+// Cross-chain message types and data structures
+
+/// Messaging layer that carries transfers to a destination chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Transport {
+    /// ZetaChain Gateway
+    Gateway,
+    /// Wormhole core bridge, for chains ZetaChain does not connect
+    Wormhole,
+    /// LayerZero endpoint
+    LayerZero,
+}
+
+/// Payload of an outbound Wormhole message
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct WormholeEnvelope {
+    /// Wormhole chain id of the destination
+    pub target_chain: u16,
+    /// Universal NFT contract on the destination chain
+    pub target_address: [u8; 32],
+    /// Borsh-encoded CrossChainMessage
+    pub message: Vec<u8>,
+}
+
+/// Receiver address encodings supported by ZetaChain-connected chains
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ReceiverFormat {
+    Evm,
+    Bitcoin,
+    Ton,
+    Sui,
+    Solana,
+    Aptos,
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct CrossChainData {
+    pub destination_chain_id: u64,
+    #[max_len(MAX_RECEIVER_LEN)]
+    pub recipient_address: Vec<u8>,
+    pub transfer_timestamp: i64,
+    pub transfer_id: [u8; 32],
+}
+
+/// Cost breakdown returned by `quote_transfer`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransferQuote {
+    pub destination_chain_id: u64,
+    pub protocol_fee: u64,
+    pub gas_deposit: u64,
+    pub rent: u64,
+    pub total: u64,
+}
+
+/// Message signed off-chain by the NFT owner to authorize `transfer_cross_chain_with_permit`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PermitMessage {
+    pub program_id: Pubkey,
+    pub token_id: u64,
+    pub destination_chain_id: u64,
+    pub recipient_address: Vec<u8>,
+    pub nonce: u64,
+    pub expiry: i64,
+}
+
+// ZetaChain Gateway integration structs
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GatewayCallInstruction {
+    pub receiver: [u8; 20],
+    pub message: Vec<u8>,
+    pub revert_options: Option<RevertOptions>,
+}
+
+// Account contexts
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + UniversalNFTState::INIT_SPACE,
+        seeds = [b"universal_nft_state"],
+        bump
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(init, payer = payer, space = 8 + Pda::INIT_SPACE, seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OutboundQueue::INIT_SPACE,
+        seeds = [b"outbound_queue"],
+        bump
+    )]
+    pub outbound_queue: Account<'info, OutboundQueue>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxSupply<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCollection<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Collection::INIT_SPACE,
+        seeds = [b"collection", universal_nft_state.next_collection_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, Collection>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCollection<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        mut,
+        seeds = [b"collection", collection.collection_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub collection: Account<'info, Collection>,
+}
+
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct SetCollectionAddress<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ UniversalNFTError::Unauthorized)]
+    pub collection: Account<'info, Collection>,
+
+    /// CHECK: Chain registry entry, loaded in-program so unregistered chains map to UnsupportedChain
+    #[account(
+        seeds = [b"chain_config", chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CollectionChainAddress::INIT_SPACE,
+        seeds = [b"collection_address", collection.key().as_ref(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub collection_address: Account<'info, CollectionChainAddress>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCollectionOutboundCap<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ UniversalNFTError::Unauthorized)]
+    pub collection: Account<'info, Collection>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CollectionConfig::INIT_SPACE,
+        seeds = [b"collection_config", collection.key().as_ref()],
+        bump
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(uri: String)]
+pub struct RegisterUri<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UriRegistry::INIT_SPACE,
+        seeds = [b"uri_registry", uri_hash(&uri).as_ref()],
+        bump
+    )]
+    pub uri_registry: Account<'info, UriRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(destination_chain_id: u64)]
+pub struct QuoteTransfer<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Chain registry entry, loaded in-program so unregistered chains map to UnsupportedChain
+    #[account(
+        seeds = [b"chain_config", destination_chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(chain_id: u64, params: ChainConfigParams)]
+pub struct SetChainConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ChainConfig::INIT_SPACE,
+        seeds = [b"chain_config", chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ChainStats::INIT_SPACE,
+        seeds = [b"chain_stats", chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_stats: Account<'info, ChainStats>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Caip2Mapping::INIT_SPACE,
+        seeds = [b"caip2", caip2_seed(&params.caip2).as_ref()],
+        bump
+    )]
+    pub caip2_mapping: Account<'info, Caip2Mapping>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(caip2: String)]
+pub struct ResolveCaip2<'info> {
+    #[account(seeds = [b"caip2", caip2_seed(&caip2).as_ref()], bump)]
+    pub caip2_mapping: Account<'info, Caip2Mapping>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundPda<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(mut, seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueRescue<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RescueRequest::INIT_SPACE,
+        seeds = [b"rescue_request"],
+        bump
+    )]
+    pub rescue_request: Account<'info, RescueRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Rescue<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    /// Executed request, closed back to the authority
+    #[account(mut, close = authority, seeds = [b"rescue_request"], bump)]
+    pub rescue_request: Account<'info, RescueRequest>,
+
+    #[account(mut, seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    /// CHECK: Recipient of rescued lamports, checked against `destination` in-program
+    #[account(mut)]
+    pub destination: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: MintIndex address of the rescued mint, which must not exist
+    pub mint_index: Option<UncheckedAccount<'info>>,
+
+    /// Program PDA's token account holding the stranded tokens
+    #[account(mut, token::authority = pda)]
+    pub pda_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Destination's token account receiving rescued tokens
+    #[account(mut)]
+    pub destination_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Vec<u8>)]
+pub struct BlockAddress<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BlocklistEntry::INIT_SPACE,
+        seeds = [b"blocked", blocklist_seed(&address).as_ref()],
+        bump
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Vec<u8>)]
+pub struct UnblockAddress<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(mut, close = authority, seeds = [b"blocked", blocklist_seed(&address).as_ref()], bump)]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+}
+
+#[derive(Accounts)]
+pub struct RenounceAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey, page: u32)]
+pub struct InitOwnerIndex<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OwnerIndex::INIT_SPACE,
+        seeds = [b"owner_index", owner.as_ref(), page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub owner_index: Account<'info, OwnerIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey, page: u32)]
+pub struct ViewOwnerTokens<'info> {
+    #[account(
+        seeds = [b"owner_index", owner.as_ref(), page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub owner_index: Account<'info, OwnerIndex>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct InitSupplyIndex<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + SupplyIndex::INIT_SPACE,
+        seeds = [b"supply_index", page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub supply_index: Account<'info, SupplyIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct ViewSupplyPage<'info> {
+    #[account(seeds = [b"supply_index", page.to_le_bytes().as_ref()], bump)]
+    pub supply_index: Account<'info, SupplyIndex>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct ViewNFT<'info> {
+    #[account(seeds = [b"nft_info", token_id.to_le_bytes().as_ref()], bump)]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    /// Rental record for the token, if one exists
+    #[account(seeds = [b"rental", token_id.to_le_bytes().as_ref()], bump)]
+    pub rental: Option<Account<'info, Rental>>,
+}
+
+#[derive(Accounts)]
+#[instruction(transfer_id: [u8; 32])]
+pub struct ViewTransfer<'info> {
+    #[account(seeds = [b"transfer_record", transfer_id.as_ref()], bump)]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct SyncNFTInfoCompact<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"nft_info", token_id.to_le_bytes().as_ref()], bump)]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + std::mem::size_of::<NFTInfoCompact>(),
+        seeds = [b"nft_info_compact", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info_compact: AccountLoader<'info, NFTInfoCompact>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64, name: String, symbol: String, uri: String, to: Pubkey)]
+pub struct MintNFT<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    /// Program PDA holding mint authority over every NFT mint
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = pda,
+        mint::freeze_authority = pda,
+        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Only used as the owner of the recipient token account
+    #[account(address = to @ UniversalNFTError::NotOwner)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + NFTInfo::INIT_SPACE,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Box<Account<'info, NFTInfo>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Any page of the recipient's token index with free capacity
+    #[account(
+        mut,
+        constraint = owner_index.owner == to @ UniversalNFTError::OwnerIndexMismatch
+    )]
+    pub owner_index: Box<Account<'info, OwnerIndex>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MintIndex::INIT_SPACE,
+        seeds = [b"mint_index", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_index: Box<Account<'info, MintIndex>>,
+
+    /// Global supply index page receiving this token
+    #[account(mut)]
+    pub supply_index: Box<Account<'info, SupplyIndex>>,
+
+    /// Collection the NFT is minted into, if any
+    #[account(mut)]
+    pub collection: Option<Box<Account<'info, Collection>>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Accounts of `mint_nft_auto`: those of `mint_nft`, derived from the next unused token id
+#[derive(Accounts)]
+#[instruction(name: String, symbol: String, uri: String, to: Pubkey)]
+pub struct MintNFTAuto<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    /// Program PDA holding mint authority over every NFT mint
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = pda,
+        mint::freeze_authority = pda,
+        seeds = [b"nft_mint", universal_nft_state.next_token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Only used as the owner of the recipient token account
+    #[account(address = to @ UniversalNFTError::NotOwner)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + NFTInfo::INIT_SPACE,
+        seeds = [b"nft_info", universal_nft_state.next_token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Box<Account<'info, NFTInfo>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Any page of the recipient's token index with free capacity
+    #[account(
+        mut,
+        constraint = owner_index.owner == to @ UniversalNFTError::OwnerIndexMismatch
+    )]
+    pub owner_index: Box<Account<'info, OwnerIndex>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MintIndex::INIT_SPACE,
+        seeds = [b"mint_index", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_index: Box<Account<'info, MintIndex>>,
+
+    /// Global supply index page receiving this token
+    #[account(mut)]
+    pub supply_index: Box<Account<'info, SupplyIndex>>,
+
+    /// Collection the NFT is minted into, if any
+    #[account(mut)]
+    pub collection: Option<Box<Account<'info, Collection>>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlist<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Allowlist::INIT_SPACE,
+        seeds = [b"allowlist"],
+        bump
+    )]
+    pub allowlist: Account<'info, Allowlist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts shared by every sale mint path; the minter pays and receives the next token id
+#[derive(Accounts)]
+pub struct SaleMint<'info> {
+    #[account(mut)]
+    pub minter: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Program PDA holding mint authority over every NFT mint
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(
+        init,
+        payer = minter,
+        mint::decimals = 0,
+        mint::authority = pda,
+        mint::freeze_authority = pda,
+        seeds = [b"nft_mint", universal_nft_state.next_token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = minter,
+        associated_token::mint = mint,
+        associated_token::authority = minter
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = minter,
+        space = 8 + NFTInfo::INIT_SPACE,
+        seeds = [b"nft_info", universal_nft_state.next_token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    /// CHECK: Metaplex metadata account, created by the metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Collection authority recorded as the metadata update authority
+    #[account(address = universal_nft_state.authority @ UniversalNFTError::Unauthorized)]
+    pub update_authority: UncheckedAccount<'info>,
+
+    /// Any page of the minter's token index with free capacity
+    #[account(
+        mut,
+        constraint = owner_index.owner == minter.key() @ UniversalNFTError::OwnerIndexMismatch
+    )]
+    pub owner_index: Account<'info, OwnerIndex>,
+
+    /// Global supply index page receiving this token
+    #[account(mut)]
+    pub supply_index: Account<'info, SupplyIndex>,
+
+    #[account(
+        init,
+        payer = minter,
+        space = 8 + MintIndex::INIT_SPACE,
+        seeds = [b"mint_index", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_index: Account<'info, MintIndex>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MintAllowlisted<'info> {
+    pub sale: SaleMint<'info>,
+
+    #[account(seeds = [b"allowlist"], bump)]
+    pub allowlist: Account<'info, Allowlist>,
+
+    #[account(
+        init_if_needed,
+        payer = sale.minter,
+        space = 8 + AllowlistClaim::INIT_SPACE,
+        seeds = [b"allowlist_claim", sale.minter.key().as_ref()],
+        bump
+    )]
+    pub claim_counter: Account<'info, AllowlistClaim>,
+}
+
+#[derive(Accounts)]
+pub struct SetPublicSale<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PublicSale::INIT_SPACE,
+        seeds = [b"public_sale"],
+        bump
+    )]
+    pub public_sale: Account<'info, PublicSale>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PublicMint<'info> {
+    pub sale: SaleMint<'info>,
+
+    #[account(seeds = [b"public_sale"], bump)]
+    pub public_sale: Account<'info, PublicSale>,
+
+    #[account(
+        init_if_needed,
+        payer = sale.minter,
+        space = 8 + SaleMintCounter::INIT_SPACE,
+        seeds = [b"public_mint", sale.minter.key().as_ref()],
+        bump
+    )]
+    pub mint_counter: Account<'info, SaleMintCounter>,
+
+    /// CHECK: Receives the creator share of the sale price
+    #[account(mut, address = public_sale.creator @ UniversalNFTError::InvalidSalePayment)]
+    pub creator: UncheckedAccount<'info>,
+
+    /// Sale payment accounts, required when the sale is priced in an SPL token
+    #[account(
+        mut,
+        token::mint = public_sale.payment_mint.unwrap_or_default(),
+        token::authority = sale.minter
+    )]
+    pub minter_payment_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = public_sale.payment_mint.unwrap_or_default(),
+        token::authority = sale.treasury
+    )]
+    pub treasury_payment_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = public_sale.payment_mint.unwrap_or_default(),
+        token::authority = creator
+    )]
+    pub creator_payment_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintPhases<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + MintPhases::INIT_SPACE,
+        seeds = [b"mint_phases"],
+        bump
+    )]
+    pub mint_phases: Account<'info, MintPhases>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(phase_index: u8)]
+pub struct MintInPhase<'info> {
+    pub sale: SaleMint<'info>,
+
+    #[account(seeds = [b"mint_phases"], bump)]
+    pub mint_phases: Account<'info, MintPhases>,
+
+    /// Per-phase mint counter for the minter
+    #[account(
+        init_if_needed,
+        payer = sale.minter,
+        space = 8 + SaleMintCounter::INIT_SPACE,
+        seeds = [b"phase_mint", &[phase_index], sale.minter.key().as_ref()],
+        bump
+    )]
+    pub mint_counter: Account<'info, SaleMintCounter>,
+}
+
+#[derive(Accounts)]
+pub struct Airdrop<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Program PDA holding mint authority over every NFT mint
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    /// Global supply index page receiving the airdropped tokens
+    #[account(mut)]
+    pub supply_index: Account<'info, SupplyIndex>,
+
+    /// Collection the tokens are airdropped into, if any
+    #[account(mut)]
+    pub collection: Option<Box<Account<'info, Collection>>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(transfer_id: [u8; 32])]
+pub struct RetryDispatch<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_transfer", transfer_id.as_ref()],
+        bump,
+        constraint = pending_transfer.status == TransferStatus::Initiated @ UniversalNFTError::TransferNotPending,
+        constraint = !pending_transfer.dispatched @ UniversalNFTError::AlreadyDispatched,
+        constraint = pending_transfer.gas_escrow > 0 @ UniversalNFTError::TransferNotQueued
+    )]
+    pub pending_transfer: Account<'info, PendingTransfer>,
+
+    /// CHECK: Gateway PDA account, derived from the configured gateway program
+    #[account(mut, seeds = [b"meta"], bump, seeds::program = config.gateway_program)]
+    pub gateway_pda: AccountInfo<'info>,
+
+    /// CHECK: Gateway program, must match the configured gateway
+    #[account(executable, address = config.gateway_program @ UniversalNFTError::GatewayMismatch)]
+    pub gateway_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(transfer_id: [u8; 32])]
+pub struct ConfirmTransfer<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"pending_transfer", transfer_id.as_ref()],
+        bump,
+        has_one = owner,
+        constraint = pending_transfer.status == TransferStatus::Initiated @ UniversalNFTError::TransferNotPending
+    )]
+    pub pending_transfer: Account<'info, PendingTransfer>,
+
+    /// Owner's receipt, retired with the confirmation
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"receipt", owner.key().as_ref(), transfer_id.as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    #[account(
+        mut,
+        seeds = [b"transfer_record", transfer_id.as_ref()],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Original owner receiving the tracking account's rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(transfer_id: [u8; 32])]
+pub struct ReclaimExpired<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"pending_transfer", transfer_id.as_ref()],
+        bump,
+        has_one = owner @ UniversalNFTError::NotOwner
+    )]
+    pub pending_transfer: Account<'info, PendingTransfer>,
+
+    /// Owner's receipt, redeemed to restore the NFT
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"receipt", owner.key().as_ref(), transfer_id.as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    #[account(
+        mut,
+        seeds = [b"transfer_record", transfer_id.as_ref()],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_info", pending_transfer.token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    #[account(mut, address = nft_info.mint @ UniversalNFTError::InvalidTokenId)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// Any page of the owner's token index with free capacity
+    #[account(
+        mut,
+        constraint = owner_index.owner == owner.key() @ UniversalNFTError::OwnerIndexMismatch
+    )]
+    pub owner_index: Account<'info, OwnerIndex>,
+
+    /// Global supply index page receiving the restored token
+    #[account(mut)]
+    pub supply_index: Account<'info, SupplyIndex>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct CloseBurned<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = nft_info.owner == owner.key() @ UniversalNFTError::NotOwner,
+        constraint = nft_info.is_burned @ UniversalNFTError::NotBurned,
+        constraint = nft_info.cross_chain_data.is_none() @ UniversalNFTError::NftBridgedOut
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct SweepBurned<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = owner @ UniversalNFTError::NotOwner,
+        constraint = nft_info.is_burned @ UniversalNFTError::NotBurned,
+        constraint = nft_info.cross_chain_data.is_none() @ UniversalNFTError::NftBridgedOut
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    /// CHECK: Original owner receiving the remaining rent, validated against nft_info
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64, uri: String)]
+pub struct UpdateMetadata<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump,
+        realloc = NFTInfo::space_for_uri(uri.len()),
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    /// Metaplex metadata of the NFT's mint, updated to the new URI
+    #[account(
+        mut,
+        seeds = [b"metadata", metadata_program.key().as_ref(), nft_info.mint.as_ref()],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64, destination_chain_id: u64)]
+pub struct SendMetadataUpdate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    /// Collection of the NFT, required when it belongs to one
+    pub collection: Option<Account<'info, Collection>>,
+
+    /// CHECK: Chain registry entry, loaded in-program so unregistered chains map to UnsupportedChain
+    #[account(
+        seeds = [b"chain_config", destination_chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: UncheckedAccount<'info>,
+
+    /// CHECK: Gateway PDA account, derived from the configured gateway program
+    #[account(mut, seeds = [b"meta"], bump, seeds::program = config.gateway_program)]
+    pub gateway_pda: AccountInfo<'info>,
+
+    /// CHECK: Gateway program, must match the configured gateway
+    #[account(executable, address = config.gateway_program @ UniversalNFTError::GatewayMismatch)]
+    pub gateway_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64, new_owner: Pubkey)]
+pub struct TransferLocal<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = signer
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Only used as the authority of the destination token account
+    #[account(address = new_owner)]
+    pub new_owner_account: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = new_owner_account
+    )]
+    pub destination_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    /// Page of the signer's token index containing this token
+    #[account(
+        mut,
+        constraint = from_owner_index.owner == signer.key() @ UniversalNFTError::OwnerIndexMismatch
+    )]
+    pub from_owner_index: Account<'info, OwnerIndex>,
+
+    /// Any page of the new owner's token index with free capacity
+    #[account(
+        mut,
+        constraint = to_owner_index.owner == new_owner @ UniversalNFTError::OwnerIndexMismatch
+    )]
+    pub to_owner_index: Account<'info, OwnerIndex>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64, operator: Pubkey)]
+pub struct ManageOperator<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Only used as the token delegate
+    #[account(address = operator)]
+    pub operator_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct RevokeOperator<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct StakeNFT<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    /// Program PDA, delegate and freeze authority for staked NFTs
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(
+        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + StakeInfo::INIT_SPACE,
+        seeds = [b"stake_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct UnstakeNFT<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    /// Program PDA, delegate and freeze authority for staked NFTs
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(
+        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_info", token_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = stake_info.owner == owner.key() @ UniversalNFTError::NotOwner
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64, renter: Pubkey)]
+pub struct RentOut<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Only used as the token delegate
+    #[account(address = renter)]
+    pub renter_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Rental::INIT_SPACE,
+        seeds = [b"rental", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub rental: Account<'info, Rental>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct EndRental<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Renter co-signature, required to end a rental before expiry
+    pub renter: Option<Signer<'info>>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"rental", token_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = rental.owner == owner.key() @ UniversalNFTError::NotOwner
+    )]
+    pub rental: Account<'info, Rental>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct SetAttribute<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    /// Collection of the NFT, required when it belongs to one
+    pub collection: Option<Account<'info, Collection>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + Attributes::INIT_SPACE,
+        seeds = [b"attributes", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub attributes: Account<'info, Attributes>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct ClearAttribute<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Account<'info, NFTInfo>,
+
+    /// Collection of the NFT, required when it belongs to one
+    pub collection: Option<Account<'info, Collection>>,
+
+    #[account(
+        mut,
+        seeds = [b"attributes", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub attributes: Account<'info, Attributes>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(token_id: u64, recipient_address: Vec<u8>, destination_chain_id: u64)]
+pub struct TransferCrossChain<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_info: Box<Account<'info, NFTInfo>>,
+
+    /// Token account holding the NFT, owned by the signer or delegated to an approved operator
+    #[account(mut, token::mint = mint)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+    
+    #[account(mut, address = nft_info.mint @ UniversalNFTError::InvalidTokenId)]
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// Page of the token owner's index containing this token
+    #[account(
+        mut,
+        constraint = owner_index.owner == token_account.owner @ UniversalNFTError::OwnerIndexMismatch
+    )]
+    pub owner_index: Box<Account<'info, OwnerIndex>>,
+
+    /// Global supply index page containing this token
+    #[account(mut)]
+    pub supply_index: Box<Account<'info, SupplyIndex>>,
+    
+    /// Instructions sysvar for caller verification
+    /// CHECK: Instructions sysvar account
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: Chain registry entry, loaded in-program so unregistered chains map to UnsupportedChain
+    #[account(
+        seeds = [b"chain_config", destination_chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + ChainStats::INIT_SPACE,
+        seeds = [b"chain_stats", destination_chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_stats: Box<Account<'info, ChainStats>>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + PendingTransfer::INIT_SPACE,
+        seeds = [
+            b"pending_transfer",
+            outbound_transfer_id(token_id, destination_chain_id, nft_info.transfer_nonce).as_ref()
+        ],
+        bump
+    )]
+    pub pending_transfer: Box<Account<'info, PendingTransfer>>,
+
+    /// Owner's receipt for the in-flight NFT, closed on confirmation or when it is reclaimed
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Receipt::INIT_SPACE,
+        seeds = [
+            b"receipt",
+            token_account.owner.as_ref(),
+            outbound_transfer_id(token_id, destination_chain_id, nft_info.transfer_nonce).as_ref()
+        ],
+        bump
+    )]
+    pub receipt: Box<Account<'info, Receipt>>,
+
+    /// Status record of this transfer, kept after it settles
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + TransferRecord::INIT_SPACE,
+        seeds = [
+            b"transfer_record",
+            outbound_transfer_id(token_id, destination_chain_id, nft_info.transfer_nonce).as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Box<Account<'info, TransferRecord>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + WalletRateLimit::INIT_SPACE,
+        seeds = [b"rate_limit", token_account.owner.as_ref()],
+        bump
+    )]
+    pub rate_limit: Account<'info, WalletRateLimit>,
+
+    /// CHECK: Blocklist entry of the token owner, which must not exist
+    #[account(
+        seeds = [b"blocked", blocklist_seed(token_account.owner.as_ref()).as_ref()],
+        bump,
+        constraint = owner_blocklist_entry.data_is_empty() @ UniversalNFTError::AddressBlocked
+    )]
+    pub owner_blocklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Blocklist entry of the destination receiver, which must not exist
+    #[account(
+        seeds = [b"blocked", blocklist_seed(&recipient_address).as_ref()],
+        bump,
+        constraint = recipient_blocklist_entry.data_is_empty() @ UniversalNFTError::AddressBlocked
+    )]
+    pub recipient_blocklist_entry: UncheckedAccount<'info>,
+
+    /// Collection authority co-signature, required to bridge a soulbound NFT
+    pub collection_authority: Option<Signer<'info>>,
+
+    /// CHECK: Program PDA that thaws soulbound token accounts, verified in-program
+    pub pda: Option<UncheckedAccount<'info>>,
+
+    /// Collection of the NFT, required when it belongs to one
+    pub collection: Option<Box<Account<'info, Collection>>>,
+
+    /// Collection's counterpart contract on the destination chain, required with `collection`
+    pub collection_address: Option<Box<Account<'info, CollectionChainAddress>>>,
+
+    /// CHECK: Collection's outbound cap PDA, required with `collection`; loaded in-program since it
+    /// exists only once the collection sets a cap
+    #[account(mut)]
+    pub collection_config: Option<UncheckedAccount<'info>>,
+
+    /// On-chain attributes of the NFT, carried in the outbound message when present
+    #[account(seeds = [b"attributes", token_id.to_le_bytes().as_ref()], bump)]
+    pub attributes: Option<Box<Account<'info, Attributes>>>,
+    
+    // Gateway accounts for cross-chain transfer
+    /// CHECK: Gateway PDA account, derived from the configured gateway program
+    #[account(mut, seeds = [b"meta"], bump, seeds::program = config.gateway_program)]
+    pub gateway_pda: AccountInfo<'info>,
+    
+    /// CHECK: Gateway program, must match the configured gateway
+    #[account(executable, address = config.gateway_program @ UniversalNFTError::GatewayMismatch)]
+    pub gateway_program: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferCrossChainWithSplGas<'info> {
+    pub transfer: TransferCrossChain<'info>,
+
+    pub gas_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = gas_mint,
+        token::authority = transfer.signer
+    )]
+    pub gas_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Gateway whitelist entry for the gas token, derived from the configured gateway program
+    #[account(
+        owner = transfer.config.gateway_program @ UniversalNFTError::InvalidGatewayAccount,
+        address = gateway_whitelist_address(&transfer.config.gateway_program, &gas_mint.key())
+            @ UniversalNFTError::InvalidGatewayWhitelist
+    )]
+    pub gas_whitelist_entry: AccountInfo<'info>,
+
+    /// Gateway PDA's associated token account for the gas token
+    #[account(
+        mut,
+        address = get_associated_token_address(&transfer.gateway_pda.key(), &gas_mint.key())
+            @ UniversalNFTError::InvalidGatewayTokenAccount
+    )]
+    pub gas_gateway_token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct TransferCrossChainWithPermit<'info> {
+    pub transfer: TransferCrossChain<'info>,
+
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(
+        init_if_needed,
+        payer = transfer.signer,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [b"permit_nonce", transfer.token_account.owner.as_ref()],
+        bump
+    )]
+    pub permit_nonce: Account<'info, PermitNonce>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OptIntoMigration<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub collection: Account<'info, Collection>,
+
+    #[account(mut, token::authority = holder)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = 8 + MigrationReceiver::INIT_SPACE,
+        seeds = [b"migration_receiver", collection.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub migration_receiver: Account<'info, MigrationReceiver>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64, recipient_address: Vec<u8>)]
+pub struct MigrateCollection<'info> {
+    pub transfer: TransferCrossChain<'info>,
+
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    /// Collection being migrated, whose authority signs the transfer
+    #[account(
+        constraint = collection.authority == transfer.signer.key() @ UniversalNFTError::Unauthorized
+    )]
+    pub collection: Box<Account<'info, Collection>>,
+
+    /// Receiver the holder registered with `opt_into_migration`
+    #[account(
+        seeds = [
+            b"migration_receiver",
+            collection.key().as_ref(),
+            transfer.token_account.owner.as_ref()
+        ],
+        bump,
+        constraint = migration_receiver.receiver == recipient_address @ UniversalNFTError::MigrationReceiverMismatch
+    )]
+    pub migration_receiver: Account<'info, MigrationReceiver>,
+
+    /// CHECK: Holder of the migrated token, refunded the rent of its emptied token account
+    #[account(mut, address = transfer.token_account.owner @ UniversalNFTError::NotOwner)]
+    pub holder: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = transfer.signer,
+        space = 8 + Migration::INIT_SPACE,
+        seeds = [b"migration", collection.key().as_ref()],
+        bump
+    )]
+    pub migration: Account<'info, Migration>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferCrossChainQueued<'info> {
+    pub transfer: TransferCrossChain<'info>,
+
+    #[account(mut, seeds = [b"outbound_queue"], bump)]
+    pub outbound_queue: Account<'info, OutboundQueue>,
+
+    #[account(
+        init,
+        payer = transfer.signer,
+        space = 8 + QueueEntry::INIT_SPACE,
+        seeds = [b"queue_entry", outbound_queue.tail.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub queue_entry: Account<'info, QueueEntry>,
+}
+
+#[derive(Accounts)]
+pub struct TransferCrossChainWormhole<'info> {
+    pub transfer: TransferCrossChain<'info>,
+
+    /// Fresh keypair receiving the posted Wormhole message
+    #[account(mut)]
+    pub wormhole_message: Signer<'info>,
+
+    /// CHECK: Program emitter PDA identifying this program to Wormhole
+    #[account(seeds = [b"emitter"], bump)]
+    pub wormhole_emitter: UncheckedAccount<'info>,
+
+    /// CHECK: Wormhole bridge config, derived from the configured Wormhole program
+    #[account(mut, seeds = [b"Bridge"], bump, seeds::program = transfer.config.wormhole_program)]
+    pub wormhole_bridge: UncheckedAccount<'info>,
+
+    /// CHECK: Wormhole fee collector, derived from the configured Wormhole program
+    #[account(mut, seeds = [b"fee_collector"], bump, seeds::program = transfer.config.wormhole_program)]
+    pub wormhole_fee_collector: UncheckedAccount<'info>,
+
+    /// CHECK: Wormhole sequence tracker for the program emitter
+    #[account(
+        mut,
+        seeds = [b"Sequence", wormhole_emitter.key().as_ref()],
+        bump,
+        seeds::program = transfer.config.wormhole_program
+    )]
+    pub wormhole_sequence: UncheckedAccount<'info>,
+
+    /// CHECK: Wormhole core bridge program, must match the configured one
+    #[account(executable, address = transfer.config.wormhole_program @ UniversalNFTError::InvalidWormholeProgram)]
+    pub wormhole_program: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct TransferCrossChainLayerZero<'info> {
+    pub transfer: TransferCrossChain<'info>,
+
+    /// CHECK: OApp PDA registered with the LayerZero endpoint; signs the send
+    #[account(seeds = [b"lz_oapp"], bump)]
+    pub lz_oapp: UncheckedAccount<'info>,
+
+    /// CHECK: LayerZero endpoint program, must match the configured endpoint
+    #[account(executable, address = transfer.config.layerzero_endpoint @ UniversalNFTError::InvalidLayerZeroEndpoint)]
+    pub layerzero_endpoint: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterLayerZeroOApp<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: OApp PDA being registered
+    #[account(seeds = [b"lz_oapp"], bump)]
+    pub lz_oapp: UncheckedAccount<'info>,
+
+    /// CHECK: LayerZero endpoint program, must match the configured endpoint
+    #[account(executable, address = config.layerzero_endpoint @ UniversalNFTError::InvalidLayerZeroEndpoint)]
+    pub layerzero_endpoint: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Possibly legacy layout, decoded and authority-checked in the handler
+    #[account(mut, owner = crate::ID, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct MigrateNFTInfo<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Possibly legacy layout, decoded in the handler
+    #[account(mut, owner = crate::ID, seeds = [b"nft_info", token_id.to_le_bytes().as_ref()], bump)]
+    pub nft_info: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncLookupTable<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"universal_nft_state"],
+        bump,
+        has_one = authority @ UniversalNFTError::Unauthorized
+    )]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// Lookup table authority
+    #[account(seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    /// CHECK: Table being created or extended, matched against `Config.lookup_table` in the handler
+    #[account(mut)]
+    pub lookup_table: UncheckedAccount<'info>,
+
+    /// CHECK: Address lookup table program
+    #[account(address = address_lookup_table::program::ID)]
+    pub address_lookup_table_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DispatchNext<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"outbound_queue"],
+        bump,
+        constraint = outbound_queue.head < outbound_queue.tail @ UniversalNFTError::QueueEmpty
+    )]
+    pub outbound_queue: Account<'info, OutboundQueue>,
+
+    #[account(
+        mut,
+        close = cranker,
+        seeds = [b"queue_entry", outbound_queue.head.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub queue_entry: Account<'info, QueueEntry>,
+
+    /// CHECK: PendingTransfer for the queued token; may have been closed by a reclaim
+    #[account(
+        mut,
+        seeds = [b"pending_transfer", queue_entry.transfer_id.as_ref()],
+        bump
     )]
+    pub pending_transfer: UncheckedAccount<'info>,
+
+    /// CHECK: Gateway PDA account, derived from the configured gateway program
+    #[account(mut, seeds = [b"meta"], bump, seeds::program = config.gateway_program)]
+    pub gateway_pda: AccountInfo<'info>,
+
+    /// CHECK: Gateway program, must match the configured gateway
+    #[account(executable, address = config.gateway_program @ UniversalNFTError::GatewayMismatch)]
+    pub gateway_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OnCall<'info> {
+    #[account(mut, seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
     pub universal_nft_state: Account<'info, UniversalNFTState>,
 
-    #[account(init, payer = signer, space = size_of::<Pda>() + 32, seeds = [b"connected"], bump)]
-    pub pda: Account<'info, Pda>,
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// Escrow token account of the program PDA, used when the receiver's accounts are not supplied
+    #[account(mut, token::mint = mint_account, token::authority = pda)]
+    pub pda_ata: Option<Account<'info, TokenAccount>>,
+
+    pub mint_account: Account<'info, Mint>,
+
+    /// CHECK: NFTInfo PDA keyed by the incoming token id; created in the handler
+    #[account(mut)]
+    pub nft_info: UncheckedAccount<'info>,
+
+    /// CHECK: MintIndex PDA keyed by mint_account; created in the handler
+    #[account(mut)]
+    pub mint_index: UncheckedAccount<'info>,
+
+    /// CHECK: PendingClaim PDA keyed by the incoming token id; created in the handler
+    #[account(mut)]
+    pub pending_claim: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Receiver wallet named in the inbound message, verified in the handler; credited
+    /// with the call's deposit unless deposits go to the treasury
+    #[account(mut)]
+    pub receiver: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Receiver's associated token account, created in the handler with rent paid by the program PDA
+    #[account(mut)]
+    pub receiver_ata: Option<UncheckedAccount<'info>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// Any page of the receiver's token index with free capacity
+    #[account(mut)]
+    pub owner_index: Account<'info, OwnerIndex>,
+
+    /// Statistics for the source chain, when the caller supplies it
+    #[account(mut)]
+    pub chain_stats: Option<Account<'info, ChainStats>>,
+
+    /// Global supply index page receiving this token
+    #[account(mut)]
+    pub supply_index: Account<'info, SupplyIndex>,
+
+    /// Registry entry resolving the URI of a compact Mint message
+    pub uri_registry: Option<Account<'info, UriRegistry>>,
+
+    /// Treasury, credited with the call's deposit when configured or when no receiver is named
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// Program PDA's associated token account holding an SPL deposit, when the call carried one
+    #[account(mut, token::authority = pda)]
+    pub deposit_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Receiver's or treasury's token account for an SPL deposit
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Test contract
+    pub gateway_pda: UncheckedAccount<'info>,
+
+    /// Instructions sysvar, to verify the Gateway is the caller
+    /// CHECK: Instructions sysvar account
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
 
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(token_id: u64)]
-pub struct MintNFT<'info> {
+#[instruction(params: LzReceiveParams)]
+pub struct LzReceive<'info> {
+    pub inbound: OnCall<'info>,
+
+    /// CHECK: OApp PDA registered with the LayerZero endpoint; signs the clear
+    #[account(seeds = [b"lz_oapp"], bump)]
+    pub lz_oapp: UncheckedAccount<'info>,
+
+    /// Registry entry of the source chain, which must route through LayerZero from `params.sender`
+    #[account(
+        constraint = chain_config.enabled
+            && chain_config.transport == Transport::LayerZero
+            && chain_config.transport_chain_id == params.src_eid
+            && chain_config.peer == params.sender @ UniversalNFTError::InvalidCaller
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    /// CHECK: LayerZero endpoint program, must match the configured endpoint
+    #[account(executable, address = inbound.config.layerzero_endpoint @ UniversalNFTError::InvalidLayerZeroEndpoint)]
+    pub layerzero_endpoint: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount: u64, sender: Pubkey, data: Vec<u8>)]
+pub struct OnRevert<'info> {
+    #[account(mut, seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// Instructions sysvar, to verify the Gateway is the caller
+    /// CHECK: Instructions sysvar account
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+
+    /// Statistics for the destination chain of the reverted transfer, when supplied
     #[account(
         mut,
-        seeds = [b"universal_nft_state"],
+        seeds = [b"chain_stats", revert_target(&data).destination_chain_id.to_le_bytes().as_ref()],
         bump
     )]
-    pub universal_nft_state: Account<'info, UniversalNFTState>,
+    pub chain_stats: Option<Account<'info, ChainStats>>,
 
+    /// In-flight record of the reverted transfer, when supplied
     #[account(
-        init,
-        payer = signer,
-        mint::decimals = 0,
-        mint::authority = signer,
-        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
+        mut,
+        seeds = [b"pending_transfer", revert_target(&data).transfer_id.as_ref()],
+        bump
+    )]
+    pub pending_transfer: Option<Account<'info, PendingTransfer>>,
+
+    /// Status record of the reverted transfer, when supplied
+    #[account(mut, seeds = [b"transfer_record", revert_target(&data).transfer_id.as_ref()], bump)]
+    pub transfer_record: Option<Account<'info, TransferRecord>>,
+
+    /// NFTInfo of the reverted token, when supplied, to record the revert in its provenance
+    #[account(
+        mut,
+        seeds = [b"nft_info", revert_target(&data).token_id.to_le_bytes().as_ref()],
         bump
     )]
+    pub nft_info: Option<Account<'info, NFTInfo>>,
+
+    /// CHECK: Original sender receiving the reverted deposit, checked against `sender` in-program
+    #[account(mut)]
+    pub owner: Option<UncheckedAccount<'info>>,
+
+    /// Program PDA's token account the Gateway returned reverted SPL tokens to
+    #[account(mut, token::authority = pda)]
+    pub pda_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Sender's token account receiving reverted SPL tokens
+    #[account(mut)]
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u64)]
+pub struct ClaimNFT<'info> {
+    #[account(mut)]
+    pub receiver: Signer<'info>,
+
+    #[account(mut, seeds = [b"connected"], bump)]
+    pub pda: Account<'info, Pda>,
+
+    #[account(mut, seeds = [b"universal_nft_state"], bump)]
+    pub universal_nft_state: Account<'info, UniversalNFTState>,
+
+    /// Escrow record, closed back to the program PDA that funded it
+    #[account(
+        mut,
+        close = pda,
+        seeds = [b"pending_claim", token_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pending_claim.receiver == receiver.key() @ UniversalNFTError::NotOwner
+    )]
+    pub pending_claim: Account<'info, PendingClaim>,
+
+    /// CHECK: Blocklist entry of the receiver, which must not exist
+    #[account(
+        seeds = [b"blocked", blocklist_seed(receiver.key().as_ref()).as_ref()],
+        bump,
+        constraint = receiver_blocklist_entry.data_is_empty() @ UniversalNFTError::AddressBlocked
+    )]
+    pub receiver_blocklist_entry: UncheckedAccount<'info>,
+
+    #[account(address = pending_claim.mint @ UniversalNFTError::InvalidTokenId)]
     pub mint: Account<'info, Mint>,
 
     #[account(
-        init,
-        payer = signer,
+        mut,
         associated_token::mint = mint,
-        associated_token::authority = signer
+        associated_token::authority = pda
     )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub pda_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = receiver,
+        associated_token::mint = mint,
+        associated_token::authority = receiver
+    )]
+    pub receiver_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// Account data structures
+
+#[account]
+#[derive(InitSpace)]
+pub struct UniversalNFTState {
+    pub authority: Pubkey,
+    pub total_supply: u64,
+    pub next_token_id: u64,
+    pub event_seq: u64,
+    /// Cap on live supply; once set it can only be lowered
+    pub max_supply: Option<u64>,
+    /// Start slot of the circuit breaker's current volume window
+    pub window_start_slot: u64,
+    pub window_outbound: u64,
+    pub window_inbound: u64,
+    pub next_collection_id: u64,
+    /// Schema version, see `UNIVERSAL_NFT_STATE_VERSION`
+    pub version: u8,
+}
+
+impl UniversalNFTState {
+    /// Ensure `count` more tokens fit under the max supply
+    pub fn check_supply(&self, count: u64) -> Result<()> {
+        if let Some(max_supply) = self.max_supply {
+            require!(
+                self.total_supply.saturating_add(count) <= max_supply,
+                UniversalNFTError::MaxSupplyReached
+            );
+        }
+        Ok(())
+    }
+
+    /// Count `count` tokens entering the live supply
+    pub fn increase_supply(&mut self, count: u64) -> Result<()> {
+        self.total_supply = self.total_supply.checked_add(count).ok_or(UniversalNFTError::SupplyOverflow)?;
+        Ok(())
+    }
+
+    /// Count one token leaving the live supply
+    pub fn decrease_supply(&mut self) -> Result<()> {
+        self.total_supply = self.total_supply.checked_sub(1).ok_or(UniversalNFTError::SupplyUnderflow)?;
+        Ok(())
+    }
+
+    /// Ensure `token_id` is the next local token id
+    pub fn check_next_token_id(&self, token_id: u64) -> Result<()> {
+        require!(token_id >= self.next_token_id, UniversalNFTError::TokenIdTaken);
+        require!(token_id == self.next_token_id, UniversalNFTError::TokenIdNotSequential);
+        Ok(())
+    }
+
+    /// Allocate `count` local token ids starting at `first`, which must be `next_token_id`
+    ///
+    /// Local ids are strictly sequential from 1 and never reused, even after a burn, so every
+    /// id below `next_token_id` has been minted and clients can enumerate them. They stay below
+    /// `FOREIGN_TOKEN_ID_FLAG`, the space of ids derived for NFTs arriving from other chains,
+    /// whose uniqueness rests on their NFTInfo PDA alone.
+    pub fn allocate_token_ids(&mut self, first: u64, count: u64) -> Result<()> {
+        self.check_next_token_id(first)?;
+        let end = first
+            .checked_add(count)
+            .filter(|end| *end <= FOREIGN_TOKEN_ID_FLAG)
+            .ok_or(UniversalNFTError::TokenIdOverflow)?;
+        self.next_token_id = end;
+        Ok(())
+    }
+
+    /// Reserve the next event sequence number
+    pub fn next_event_seq(&mut self) -> Result<u64> {
+        self.event_seq = self.event_seq.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+        Ok(self.event_seq)
+    }
+}
+
+#[account]
+#[derive(InitSpace, Default)]
+pub struct NFTInfo {
+    pub token_id: u64,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
+    pub symbol: String,
+    #[max_len(MAX_URI_LEN)]
+    pub uri: String,
+    /// Holder of a 1/1 NFT; a semi-fungible's units have many holders, so it keeps its minter
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub is_burned: bool,
+    /// Latest outbound transfer, kept until the NFT returns or is reclaimed; a burned NFT without
+    /// it was destroyed rather than bridged out
+    pub cross_chain_data: Option<CrossChainData>,
+    /// Slot at which the NFT was burned, used to age out residual accounts
+    pub burned_slot: u64,
+    /// Operator approved to bridge the NFT on the owner's behalf, cleared on use
+    pub operator: Option<Pubkey>,
+    /// Frozen in its holder's wallet; only the collection authority may bridge or burn it
+    pub soulbound: bool,
+    /// Collection the NFT was minted into, if any
+    pub collection: Option<Pubkey>,
+    /// Cross-chain identity, assigned when a Solana-native NFT first bridges out
+    pub universal_id: Option<[u8; 32]>,
+    /// Ring buffer of the latest ownership and bridge events
+    #[max_len(PROVENANCE_LEN)]
+    pub provenance: Vec<ProvenanceEntry>,
+    /// Index of the next provenance slot to overwrite once the buffer is full
+    pub provenance_head: u8,
+    /// Schema version, see `NFT_INFO_VERSION`
+    pub version: u8,
+    /// Token id with many interchangeable units (ERC-1155 style) rather than a 1/1 NFT
+    pub semi_fungible: bool,
+    /// Keeps `reserved` in whole words
+    pub _padding: [u8; 7],
+    /// `keccak256(name || symbol || uri)` as the token last left Solana; zero until then
+    pub metadata_hash: [u8; 32],
+    /// Number of outbound transfers of the token, mixed into each transfer id
+    pub transfer_nonce: u64,
+    /// Zeroed bytes held for future fields, added in version 2
+    pub reserved: [u64; 2],
+}
+
+/// Directions in which a collection's NFTs may bridge
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BridgeMode {
+    Bidirectional,
+    OutboundOnly,
+    InboundOnly,
+    Disabled,
+}
+
+impl BridgeMode {
+    pub fn allows_outbound(&self) -> bool {
+        matches!(self, BridgeMode::Bidirectional | BridgeMode::OutboundOnly)
+    }
+
+    pub fn allows_inbound(&self) -> bool {
+        matches!(self, BridgeMode::Bidirectional | BridgeMode::InboundOnly)
+    }
+}
+
+/// Secondary-sale royalty written into each NFT's metadata
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct RoyaltyConfig {
+    pub recipient: Pubkey,
+    pub basis_points: u16,
+}
+
+/// Independent NFT project sharing this deployment
+#[account]
+#[derive(InitSpace)]
+pub struct Collection {
+    pub collection_id: u64,
+    pub authority: Pubkey,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
+    pub symbol: String,
+    /// Tokens minted into the collection
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+    pub bridge_mode: BridgeMode,
+    pub royalty: Option<RoyaltyConfig>,
+}
+
+impl Collection {
+    /// Ensure one more token fits under the collection's max supply
+    pub fn check_supply(&self) -> Result<()> {
+        if let Some(max_supply) = self.max_supply {
+            require!(self.supply < max_supply, UniversalNFTError::MaxSupplyReached);
+        }
+        Ok(())
+    }
+}
+
+/// Bounded on-chain trait list of a token
+#[account]
+#[derive(InitSpace)]
+pub struct Attributes {
+    pub token_id: u64,
+    #[max_len(MAX_ATTRIBUTES)]
+    pub entries: Vec<Attribute>,
+}
+
+/// Counterpart contract of a collection on one connected chain
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionChainAddress {
+    pub collection: Pubkey,
+    pub chain_id: u64,
+    /// Contract address encoded per the chain's receiver format
+    #[max_len(MAX_RECEIVER_LEN)]
+    pub address: Vec<u8>,
+}
+
+/// Outbound volume cap of a collection and its counter for the current epoch
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionConfig {
+    pub collection: Pubkey,
+    /// Maximum outbound transfers per epoch across the collection; zero disables the cap
+    pub max_outbound_per_epoch: u32,
+    pub epoch: u64,
+    pub epoch_outbound: u32,
+}
+
+impl CollectionConfig {
+    /// Count one outbound transfer in `epoch`, failing once the cap is reached
+    pub fn record_outbound(&mut self, epoch: u64) -> Result<()> {
+        if self.epoch != epoch {
+            self.epoch = epoch;
+            self.epoch_outbound = 0;
+        }
+        require!(
+            self.max_outbound_per_epoch == 0 || self.epoch_outbound < self.max_outbound_per_epoch,
+            UniversalNFTError::CollectionOutboundCapReached
+        );
+        self.epoch_outbound = self.epoch_outbound.checked_add(1).ok_or(UniversalNFTError::CounterOverflow)?;
+        Ok(())
+    }
+}
+
+/// Progress of a collection's migration to another chain
+#[account]
+#[derive(InitSpace)]
+pub struct Migration {
+    pub collection: Pubkey,
+    pub destination_chain_id: u64,
+    /// Items bridged so far
+    pub migrated: u64,
+    /// Highest token id migrated; the next item must have a larger id
+    pub last_token_id: u64,
+    pub started_slot: u64,
+    pub updated_slot: u64,
+}
+
+/// Receiver on the migration's destination chain registered by a holder of the collection
+#[account]
+#[derive(InitSpace)]
+pub struct MigrationReceiver {
+    pub collection: Pubkey,
+    pub holder: Pubkey,
+    #[max_len(MAX_RECEIVER_LEN)]
+    pub receiver: Vec<u8>,
+}
+
+/// Metadata URI stored under `[b"uri_registry", keccak256(uri)]` for compact messages
+#[account]
+#[derive(InitSpace)]
+pub struct UriRegistry {
+    #[max_len(MAX_URI_LEN)]
+    pub uri: String,
+}
+
+/// Inbound NFT held by the program PDA until its receiver claims it
+#[account]
+#[derive(InitSpace)]
+pub struct PendingClaim {
+    pub token_id: u64,
+    pub receiver: Pubkey,
+    pub mint: Pubkey,
+    /// Units held in escrow
+    pub amount: u64,
+}
+
+/// In-place staking record for an NFT
+#[account]
+#[derive(InitSpace)]
+pub struct StakeInfo {
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub is_staked: bool,
+    /// Unix timestamp at which the current stake began
+    pub staked_at: i64,
+    /// Accumulated duration of completed stakes
+    pub total_staked_seconds: u64,
+}
+
+/// Time-boxed usage right granted by the owner to a renter
+#[account]
+#[derive(InitSpace)]
+pub struct Rental {
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub renter: Pubkey,
+    /// Unix timestamp at which the usage right lapses
+    pub expiry: i64,
+    /// Whether the renter was also approved as the token delegate
+    pub delegated: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ProvenanceAction {
+    Mint,
+    Transfer,
+    BridgeOut,
+    BridgeIn,
+    Revert,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct ProvenanceEntry {
+    pub action: ProvenanceAction,
+    /// Counterpart chain of a bridge event; zero for events on this chain
+    pub chain_id: u64,
+    /// Owner on this chain after the event, or the sender of a bridge-out
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+impl NFTInfo {
+    /// Append a provenance entry, overwriting the oldest once the buffer is full
+    pub fn record_provenance(&mut self, action: ProvenanceAction, chain_id: u64, owner: Pubkey) -> Result<()> {
+        let entry = ProvenanceEntry {
+            action,
+            chain_id,
+            owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        if self.provenance.len() < PROVENANCE_LEN {
+            self.provenance.push(entry);
+        } else {
+            self.provenance[self.provenance_head as usize] = entry;
+        }
+        self.provenance_head = ((self.provenance_head as usize + 1) % PROVENANCE_LEN) as u8;
+        Ok(())
+    }
+
+    /// Account space needed to hold a URI of `uri_len` bytes, never below the initial allocation
+    pub fn space_for_uri(uri_len: usize) -> usize {
+        8 + NFTInfo::INIT_SPACE + uri_len.saturating_sub(MAX_URI_LEN)
+    }
+}
+
+/// UniversalNFTState as first deployed, before versioning
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyUniversalNFTState {
+    pub authority: Pubkey,
+    pub total_supply: u64,
+    pub next_token_id: u64,
+}
+
+impl From<LegacyUniversalNFTState> for UniversalNFTState {
+    fn from(legacy: LegacyUniversalNFTState) -> Self {
+        UniversalNFTState {
+            authority: legacy.authority,
+            total_supply: legacy.total_supply,
+            next_token_id: legacy.next_token_id,
+            event_seq: 0,
+            max_supply: None,
+            window_start_slot: 0,
+            window_outbound: 0,
+            window_inbound: 0,
+            next_collection_id: 0,
+            version: 0,
+        }
+    }
+}
+
+/// CrossChainData as first deployed, holding only EVM recipients
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyCrossChainData {
+    pub destination_chain_id: u64,
+    pub recipient_address: [u8; 20],
+    pub transfer_timestamp: i64,
+}
+
+/// NFTInfo as first deployed, before versioning
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyNFTInfo {
+    pub token_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub is_burned: bool,
+    pub cross_chain_data: Option<LegacyCrossChainData>,
+}
+
+impl From<LegacyNFTInfo> for NFTInfo {
+    fn from(legacy: LegacyNFTInfo) -> Self {
+        NFTInfo {
+            token_id: legacy.token_id,
+            name: legacy.name,
+            symbol: legacy.symbol,
+            uri: legacy.uri,
+            owner: legacy.owner,
+            mint: legacy.mint,
+            is_burned: legacy.is_burned,
+            cross_chain_data: legacy.cross_chain_data.map(|data| CrossChainData {
+                destination_chain_id: data.destination_chain_id,
+                recipient_address: data.recipient_address.to_vec(),
+                transfer_timestamp: data.transfer_timestamp,
+                transfer_id: [0; 32],
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// Fixed-size, zero-copy mirror of NFTInfo without provenance or cross-chain history
+///
+/// Strings are stored in fixed buffers with their lengths; absent keys are `Pubkey::default()`
+/// and an absent universal id is all zeroes. Kept current by `sync_nft_info_compact`.
+#[account(zero_copy)]
+pub struct NFTInfoCompact {
+    pub token_id: u64,
+    pub burned_slot: u64,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub operator: Pubkey,
+    pub collection: Pubkey,
+    pub universal_id: [u8; 32],
+    pub name: [u8; MAX_NAME_LEN],
+    pub symbol: [u8; MAX_SYMBOL_LEN],
+    pub uri: [u8; MAX_URI_LEN],
+    pub name_len: u8,
+    pub symbol_len: u8,
+    pub uri_len: u8,
+    /// `NFT_COMPACT_BURNED`, `NFT_COMPACT_SOULBOUND` and `NFT_COMPACT_SEMI_FUNGIBLE` bits
+    pub flags: u8,
+    pub _padding: [u8; 2],
+}
+
+pub const NFT_COMPACT_BURNED: u8 = 1 << 0;
+pub const NFT_COMPACT_SOULBOUND: u8 = 1 << 1;
+pub const NFT_COMPACT_SEMI_FUNGIBLE: u8 = 1 << 2;
+
+impl NFTInfoCompact {
+    /// Overwrite the mirror with the current state of `nft_info`
+    pub fn copy_from(&mut self, nft_info: &NFTInfo) -> Result<()> {
+        require_gte!(MAX_URI_LEN, nft_info.uri.len(), UniversalNFTError::UriTooLong);
+        self.token_id = nft_info.token_id;
+        self.burned_slot = nft_info.burned_slot;
+        self.owner = nft_info.owner;
+        self.mint = nft_info.mint;
+        self.operator = nft_info.operator.unwrap_or_default();
+        self.collection = nft_info.collection.unwrap_or_default();
+        self.universal_id = nft_info.universal_id.unwrap_or_default();
+        self.name_len = copy_padded(&mut self.name, &nft_info.name);
+        self.symbol_len = copy_padded(&mut self.symbol, &nft_info.symbol);
+        self.uri_len = copy_padded(&mut self.uri, &nft_info.uri);
+        self.flags = 0;
+        if nft_info.is_burned {
+            self.flags |= NFT_COMPACT_BURNED;
+        }
+        if nft_info.soulbound {
+            self.flags |= NFT_COMPACT_SOULBOUND;
+        }
+        if nft_info.semi_fungible {
+            self.flags |= NFT_COMPACT_SEMI_FUNGIBLE;
+        }
+        Ok(())
+    }
+
+    pub fn is_burned(&self) -> bool {
+        self.flags & NFT_COMPACT_BURNED != 0
+    }
+
+    pub fn is_soulbound(&self) -> bool {
+        self.flags & NFT_COMPACT_SOULBOUND != 0
+    }
+
+    pub fn is_semi_fungible(&self) -> bool {
+        self.flags & NFT_COMPACT_SEMI_FUNGIBLE != 0
+    }
+
+    pub fn operator(&self) -> Option<Pubkey> {
+        Some(self.operator).filter(|operator| *operator != Pubkey::default())
+    }
+
+    pub fn collection(&self) -> Option<Pubkey> {
+        Some(self.collection).filter(|collection| *collection != Pubkey::default())
+    }
+
+    pub fn name(&self) -> &str {
+        std::str::from_utf8(&self.name[..self.name_len as usize]).unwrap_or_default()
+    }
 
-    #[account(
-        init,
-        payer = signer,
-        space = 8 + size_of::<NFTInfo>(),
-        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub nft_info: Account<'info, NFTInfo>,
+    pub fn symbol(&self) -> &str {
+        std::str::from_utf8(&self.symbol[..self.symbol_len as usize]).unwrap_or_default()
+    }
 
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut)]
-    pub metadata: UncheckedAccount<'info>,
+    pub fn uri(&self) -> &str {
+        std::str::from_utf8(&self.uri[..self.uri_len as usize]).unwrap_or_default()
+    }
+}
 
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
-    pub metadata_program: Program<'info, Metadata>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub gateway_program: Pubkey,
+    pub default_on_revert_gas_limit: u64,
+    pub max_on_revert_gas_limit: u64,
+    /// ZetaChain address that receives funds if a cross-chain call aborts
+    pub abort_address: [u8; 20],
+    /// Protocol fee in lamports charged on each cross-chain transfer
+    pub bridge_fee_lamports: u64,
+    /// Fee in lamports charged on each mint
+    pub mint_fee_lamports: u64,
+    /// Universal NFT contract on ZetaChain that receives outbound messages
+    pub universal_contract: [u8; 20],
+    /// Maximum URI length accepted by `update_metadata`, at most `MAX_URI_LEN`
+    pub max_uri_len: u32,
+    /// Minimum slots after burning before `sweep_burned` may close an NFTInfo
+    pub sweep_min_age_slots: u64,
+    /// Share of swept rent paid to the cranker, in basis points
+    pub sweep_reward_bps: u16,
+    /// Slots after which an unconfirmed outbound transfer may be reclaimed by its owner
+    pub transfer_timeout_slots: u64,
+    /// Maximum outbound transfers per wallet per epoch; zero disables the limit
+    pub max_transfers_per_epoch: u32,
+    /// Halts inbound and outbound bridging; set manually or by the circuit breaker
+    pub paused: bool,
+    /// Length in slots of the circuit breaker's volume window
+    pub circuit_breaker_window_slots: u64,
+    /// Combined transfers per window that trip the circuit breaker; zero disables it
+    pub circuit_breaker_threshold: u64,
+    /// ZetaChain chain id of this Solana deployment, which can never be a destination
+    pub self_chain_id: u64,
+    /// Wormhole core bridge program; `Pubkey::default()` until Wormhole is configured
+    pub wormhole_program: Pubkey,
+    /// LayerZero endpoint program; `Pubkey::default()` until LayerZero is configured
+    pub layerzero_endpoint: Pubkey,
+    /// Emit verbose `msg!` diagnostics; off, the program logs nothing beyond its events
+    pub debug_logs: bool,
+    /// Address lookup table of the program's static accounts, kept by `sync_lookup_table`
+    pub lookup_table: Pubkey,
+    /// Forward deposits arriving with inbound calls to the treasury instead of the NFT receiver
+    pub forward_deposits_to_treasury: bool,
+    /// Programs allowed to start outbound transfers through CPI; unused slots are `Pubkey::default()`
+    pub allowed_callers: [Pubkey; MAX_ALLOWED_CALLERS],
 }
 
-#[derive(Accounts)]
-#[instruction(token_id: u64)]
-pub struct BurnNFT<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
+impl Config {
+    pub fn allows_caller(&self, program: &Pubkey) -> bool {
+        *program != Pubkey::default() && self.allowed_callers.contains(program)
+    }
+}
 
-    #[account(mut, seeds = [b"universal_nft_state"], bump)]
-    pub universal_nft_state: Account<'info, UniversalNFTState>,
+/// Chain registry entry for a destination chain
+#[account]
+#[derive(InitSpace)]
+pub struct ChainConfig {
+    pub chain_id: u64,
+    /// CAIP-2 identifier for the chain, e.g. `eip155:1`
+    #[max_len(MAX_CAIP2_LEN)]
+    pub caip2: String,
+    pub enabled: bool,
+    /// Lamports required to cover destination gas for a transfer
+    pub gas_deposit_lamports: u64,
+    /// Encoding expected for receivers on this chain
+    pub receiver_format: ReceiverFormat,
+    /// SPL mints accepted for destination gas, `Pubkey::default()` marks an empty slot
+    pub gas_token_mints: [Pubkey; MAX_GAS_TOKENS],
+    /// Messaging layer used to reach this chain
+    pub transport: Transport,
+    /// The chain's id within its transport, e.g. the Wormhole chain id or LayerZero endpoint id
+    pub transport_chain_id: u32,
+    /// Universal NFT contract on this chain addressed through the transport
+    pub peer: [u8; 32],
+    /// Send `keccak256(uri)` instead of the URI; the chain's contract resolves it from its registry
+    pub compact_messages: bool,
+}
 
-    #[account(
-        mut,
-        seeds = [b"nft_mint", token_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub mint: Account<'info, Mint>,
+impl ChainConfig {
+    pub fn accepts_gas_token(&self, mint: &Pubkey) -> bool {
+        *mint != Pubkey::default() && self.gas_token_mints.contains(mint)
+    }
 
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = signer
-    )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub fn validate_receiver(&self, receiver: &[u8]) -> Result<()> {
+        require_gte!(MAX_RECEIVER_LEN, receiver.len(), UniversalNFTError::InvalidReceiverLength);
+        let length_valid = match self.receiver_format {
+            ReceiverFormat::Evm => receiver.len() == 20,
+            ReceiverFormat::Solana | ReceiverFormat::Sui | ReceiverFormat::Aptos => receiver.len() == 32,
+            // Raw form (4-byte workchain + 32-byte hash) or 48-char user-friendly form
+            ReceiverFormat::Ton => receiver.len() == 36 || receiver.len() == 48,
+            ReceiverFormat::Bitcoin => receiver.len() >= 26,
+        };
+        require!(length_valid, UniversalNFTError::InvalidReceiverLength);
+        require!(receiver.iter().any(|b| *b != 0), UniversalNFTError::ZeroReceiver);
+        let valid = match self.receiver_format {
+            // The user-friendly form is text
+            ReceiverFormat::Ton => receiver.len() == 36 || receiver.is_ascii(),
+            // Base58 or bech32 address string
+            ReceiverFormat::Bitcoin => receiver.iter().all(|b| b.is_ascii_alphanumeric()),
+            _ => true,
+        };
+        require!(valid, UniversalNFTError::InvalidReceiver);
+        Ok(())
+    }
+}
 
-    #[account(
-        mut,
-        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub nft_info: Account<'info, NFTInfo>,
+/// Maps a CAIP-2 chain identifier to its ZetaChain chain id
+#[account]
+#[derive(InitSpace)]
+pub struct Caip2Mapping {
+    #[max_len(MAX_CAIP2_LEN)]
+    pub caip2: String,
+    pub chain_id: u64,
+}
 
-    pub token_program: Program<'info, Token>,
+/// One page of the token ids held by an owner
+#[account]
+#[derive(InitSpace)]
+pub struct OwnerIndex {
+    pub owner: Pubkey,
+    pub page: u32,
+    #[max_len(OWNER_INDEX_PAGE_SIZE)]
+    pub token_ids: Vec<u64>,
 }
 
-#[derive(Accounts)]
-#[instruction(token_id: u64)]
-pub struct TransferCrossChain<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
+impl OwnerIndex {
+    pub fn insert(&mut self, token_id: u64) -> Result<()> {
+        require!(self.token_ids.len() < OWNER_INDEX_PAGE_SIZE, UniversalNFTError::OwnerIndexFull);
+        self.token_ids.push(token_id);
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        seeds = [b"nft_info", token_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub nft_info: Account<'info, NFTInfo>,
+    pub fn remove(&mut self, token_id: u64) -> Result<()> {
+        let position = self
+            .token_ids
+            .iter()
+            .position(|id| *id == token_id)
+            .ok_or(UniversalNFTError::TokenNotIndexed)?;
+        self.token_ids.swap_remove(position);
+        Ok(())
+    }
+}
 
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = signer
-    )]
-    pub token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    
-    /// Instructions sysvar for caller verification
-    /// CHECK: Instructions sysvar account
-    #[account(address = instructions::ID)]
-    pub instruction_sysvar: AccountInfo<'info>,
-    
-    // Gateway accounts for cross-chain transfer
-    /// CHECK: Gateway PDA account
-    pub gateway_pda: AccountInfo<'info>,
-    
-    /// CHECK: Whitelist entry for the token
-    pub whitelist_entry: AccountInfo<'info>,
-    
-    /// CHECK: Gateway token account  
-    pub gateway_token_account: AccountInfo<'info>,
-    
-    /// CHECK: Gateway program
-    pub gateway_program: AccountInfo<'info>,
-    
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+/// One page of all live token ids, kept in mint order
+#[account]
+#[derive(InitSpace)]
+pub struct SupplyIndex {
+    pub page: u32,
+    #[max_len(SUPPLY_INDEX_PAGE_SIZE)]
+    pub token_ids: Vec<u64>,
 }
 
-#[derive(Accounts)]
-pub struct OnCall<'info> {
-    #[account(mut, seeds = [b"connected"], bump)]
-    pub pda: Account<'info, Pda>,
+impl SupplyIndex {
+    pub fn insert(&mut self, token_id: u64) -> Result<()> {
+        require!(self.token_ids.len() < SUPPLY_INDEX_PAGE_SIZE, UniversalNFTError::SupplyIndexFull);
+        self.token_ids.push(token_id);
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub pda_ata: Account<'info, TokenAccount>,
+    pub fn remove(&mut self, token_id: u64) -> Result<()> {
+        let position = self
+            .token_ids
+            .iter()
+            .position(|id| *id == token_id)
+            .ok_or(UniversalNFTError::TokenNotIndexed)?;
+        // Preserve ordering so pages stay sorted by mint order
+        self.token_ids.remove(position);
+        Ok(())
+    }
+}
 
-    pub mint_account: Account<'info, Mint>,
+/// Lifecycle of an outbound cross-chain transfer; only `TransferRecord` reaches the last two,
+/// as confirmed and reclaimed pending transfers are closed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TransferStatus {
+    Initiated,
+    Reverted,
+    Confirmed,
+    /// Reclaimed after timing out without a revert
+    Expired,
+}
 
-    #[account(
-        init,
-        payer = pda,
-        space = 8 + size_of::<NFTInfo>(),
-        seeds = [b"nft_info", mint_account.key().as_ref()],
-        bump
-    )]
-    pub nft_info: Account<'info, NFTInfo>,
+/// Lasting status of one outbound transfer, keyed by its transfer id
+#[account]
+#[derive(InitSpace)]
+pub struct TransferRecord {
+    pub transfer_id: [u8; 32],
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub destination_chain_id: u64,
+    pub status: TransferStatus,
+    pub initiated_slot: u64,
+    pub updated_slot: u64,
+}
 
-    /// CHECK: Test contract
-    pub gateway_pda: UncheckedAccount<'info>,
+/// On-chain record of an outbound transfer that has not been finalized, keyed by its transfer id
+#[account]
+#[derive(InitSpace)]
+pub struct PendingTransfer {
+    pub token_id: u64,
+    pub owner: Pubkey,
+    pub destination_chain_id: u64,
+    pub transfer_id: [u8; 32],
+    pub status: TransferStatus,
+    pub initiated_slot: u64,
+    pub updated_slot: u64,
+    /// Serialized Gateway message, kept so the call can be retried
+    #[max_len(MAX_OUTBOUND_MESSAGE_LEN)]
+    pub message: Vec<u8>,
+    pub revert_address: Pubkey,
+    pub call_on_revert: bool,
+    pub abort_address: [u8; 20],
+    #[max_len(MAX_REVERT_MESSAGE_LEN)]
+    pub revert_message: Vec<u8>,
+    pub on_revert_gas_limit: u64,
+    /// Whether the Gateway call has gone out
+    pub dispatched: bool,
+    /// Lamports escrowed for the destination gas deposit of a queued transfer
+    pub gas_escrow: u64,
+    /// Units burned for the transfer, restored if it is reclaimed
+    pub amount: u64,
+}
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+/// Receipt held by an owner while their NFT is in flight to another chain, one per transfer
+#[account]
+#[derive(InitSpace)]
+pub struct Receipt {
+    pub owner: Pubkey,
+    pub token_id: u64,
+    pub transfer_id: [u8; 32],
+    pub destination_chain_id: u64,
+    pub initiated_slot: u64,
 }
 
-#[derive(Accounts)]
-pub struct OnRevert<'info> {
-    #[account(mut, seeds = [b"connected"], bump)]
-    pub pda: Account<'info, Pda>,
+/// Outbound transfer counter for one wallet in the current epoch
+#[account]
+#[derive(InitSpace)]
+pub struct WalletRateLimit {
+    pub wallet: Pubkey,
+    pub epoch: u64,
+    pub count: u32,
+}
 
-    #[account(mut)]
-    pub signer: Signer<'info>,
+/// FIFO of queued outbound transfers; entries live in `QueueEntry` PDAs keyed by position
+#[account]
+#[derive(InitSpace)]
+pub struct OutboundQueue {
+    pub head: u64,
+    pub tail: u64,
+}
 
-    pub system_program: Program<'info, System>,
+#[account]
+#[derive(InitSpace)]
+pub struct QueueEntry {
+    pub position: u64,
+    pub token_id: u64,
+    /// Transfer id of the queued transfer, locating its PendingTransfer
+    pub transfer_id: [u8; 32],
 }
 
-// Account data structures
+/// Per-chain bridge counters for monitoring without an indexer
+#[account]
+#[derive(InitSpace)]
+pub struct ChainStats {
+    pub chain_id: u64,
+    pub outbound_count: u64,
+    pub inbound_count: u64,
+    pub reverted_count: u64,
+    pub total_fees: u64,
+}
 
+/// Merkle allowlist for presale mints
 #[account]
-pub struct UniversalNFTState {
-    pub authority: Pubkey,
-    pub total_supply: u64,
-    pub next_token_id: u64,
+#[derive(InitSpace)]
+pub struct Allowlist {
+    pub merkle_root: [u8; 32],
+    pub max_claims_per_address: u32,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
+    pub symbol: String,
+    #[max_len(MAX_BASE_URI_LEN)]
+    pub base_uri: String,
 }
 
+/// Number of allowlist mints claimed by an address
 #[account]
-pub struct NFTInfo {
-    pub token_id: u64,
+#[derive(InitSpace)]
+pub struct AllowlistClaim {
+    pub claimer: Pubkey,
+    pub claimed: u32,
+}
+
+/// Paid public sale configuration
+#[account]
+#[derive(InitSpace)]
+pub struct PublicSale {
+    /// Price per mint, in lamports or in base units of `payment_mint`
+    pub price: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    /// Mints allowed per wallet; zero means unlimited
+    pub max_per_wallet: u32,
+    pub creator: Pubkey,
+    /// Share of the price paid to the creator, in basis points; the rest goes to the treasury
+    pub creator_share_bps: u16,
+    #[max_len(MAX_NAME_LEN)]
     pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
     pub symbol: String,
-    pub uri: String,
-    pub owner: Pubkey,
+    #[max_len(MAX_BASE_URI_LEN)]
+    pub base_uri: String,
+}
+
+/// Number of sale mints made by a wallet
+#[account]
+#[derive(InitSpace)]
+pub struct SaleMintCounter {
+    pub wallet: Pubkey,
+    pub minted: u32,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum MintPhaseKind {
+    Allowlist,
+    Public,
+}
+
+/// One window of the mint schedule
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct MintPhase {
+    pub kind: MintPhaseKind,
+    /// Allowlist root; ignored for public phases
+    pub merkle_root: [u8; 32],
+    /// Price per mint in lamports, paid to the treasury
+    pub price: u64,
+    /// Mints allowed per wallet in this phase; zero means unlimited
+    pub max_per_wallet: u32,
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+/// Ordered launch schedule enforced by `mint_in_phase`
+#[account]
+#[derive(InitSpace)]
+pub struct MintPhases {
+    #[max_len(MAX_MINT_PHASES)]
+    pub phases: Vec<MintPhase>,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
+    pub symbol: String,
+    #[max_len(MAX_BASE_URI_LEN)]
+    pub base_uri: String,
+}
+
+impl MintPhases {
+    /// Index of the phase whose window contains `now`
+    pub fn active_phase(&self, now: i64) -> Option<usize> {
+        self.phases
+            .iter()
+            .position(|phase| now >= phase.start_ts && now < phase.end_ts)
+    }
+}
+
+/// Resolves a mint to its universal token id
+#[account]
+#[derive(InitSpace)]
+pub struct MintIndex {
     pub mint: Pubkey,
-    pub is_burned: bool,
-    pub cross_chain_data: Option<CrossChainData>,
+    pub token_id: u64,
+}
+
+/// Replay protection for ed25519 transfer permits, one per NFT owner
+#[account]
+#[derive(InitSpace)]
+pub struct PermitNonce {
+    pub owner: Pubkey,
+    pub nonce: u64,
 }
 
 #[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
 pub struct Pda {
     pub last_sender: [u8; 20],
+    #[max_len(32)]
     pub last_message: String,
 }
 
-// Cross-chain data structures
+/// Rescue of stranded assets queued by the authority, executable after its timelock
+#[account]
+#[derive(InitSpace)]
+pub struct RescueRequest {
+    /// Mint of the SPL token to rescue; None for lamports
+    pub asset: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub executable_slot: u64,
+}
+
+/// Blocked address; bridging from or to it is rejected while the entry exists
+#[account]
+#[derive(InitSpace)]
+pub struct BlocklistEntry {
+    #[max_len(MAX_RECEIVER_LEN)]
+    pub address: Vec<u8>,
+    pub blocked_slot: u64,
+}
+
+/// Return data of `view_nft`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct NftView {
+    pub info: NFTInfo,
+    /// Renter during an active rental, otherwise the owner
+    pub current_user: Pubkey,
+}
+
+// Instruction parameters
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct CrossChainNFTTransfer {
-    pub token_id: u64,
+pub struct CollectionParams {
     pub name: String,
     pub symbol: String,
-    pub uri: String,
-    pub receiver: Pubkey,
-    pub source_chain: Vec<u8>,
+    pub max_supply: Option<u64>,
+    pub bridge_mode: BridgeMode,
+    pub royalty: Option<RoyaltyConfig>,
 }
 
-// Events
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdateCollectionParams {
+    pub authority: Option<Pubkey>,
+    pub max_supply: Option<u64>,
+    pub bridge_mode: Option<BridgeMode>,
+    pub royalty: Option<RoyaltyConfig>,
+}
 
-#[event]
-pub struct NFTMinted {
-    pub token_id: u64,
-    pub owner: Pubkey,
-    pub uri: String,
-    pub mint: Pubkey,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdateConfigParams {
+    pub gateway_program: Option<Pubkey>,
+    pub default_on_revert_gas_limit: Option<u64>,
+    pub max_on_revert_gas_limit: Option<u64>,
+    pub abort_address: Option<[u8; 20]>,
+    pub bridge_fee_lamports: Option<u64>,
+    pub mint_fee_lamports: Option<u64>,
+    pub universal_contract: Option<[u8; 20]>,
+    pub max_uri_len: Option<u32>,
+    pub sweep_min_age_slots: Option<u64>,
+    pub sweep_reward_bps: Option<u16>,
+    pub transfer_timeout_slots: Option<u64>,
+    pub max_transfers_per_epoch: Option<u32>,
+    pub paused: Option<bool>,
+    pub circuit_breaker_window_slots: Option<u64>,
+    pub circuit_breaker_threshold: Option<u64>,
+    pub self_chain_id: Option<u64>,
+    pub wormhole_program: Option<Pubkey>,
+    pub layerzero_endpoint: Option<Pubkey>,
+    pub debug_logs: Option<bool>,
+    pub forward_deposits_to_treasury: Option<bool>,
+    /// Replaces the whole CPI caller allowlist
+    pub allowed_callers: Option<Vec<Pubkey>>,
 }
 
-#[event]
-pub struct NFTBurned {
-    pub token_id: u64,
-    pub owner: Pubkey,
-    pub destination_chain: String,
-    pub destination_receiver: String,
-    pub uri: String,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AllowlistParams {
+    pub merkle_root: [u8; 32],
+    pub max_claims_per_address: u32,
+    pub name: String,
+    pub symbol: String,
+    pub base_uri: String,
 }
 
-#[event]
-pub struct NFTReceived {
-    pub token_id: u64,
-    pub owner: Pubkey,
-    pub uri: String,
-    pub from_chain: String,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PublicSaleParams {
+    pub price: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub max_per_wallet: u32,
+    pub creator: Pubkey,
+    pub creator_share_bps: u16,
+    pub name: String,
+    pub symbol: String,
+    pub base_uri: String,
 }
 
-#[event]
-pub struct CrossChainTransferInitiated {
-    pub token_id: u64,
-    pub destination_chain: String,
-    pub destination_receiver: String,
-    pub gas_amount: u64,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintPhasesParams {
+    pub phases: Vec<MintPhase>,
+    pub name: String,
+    pub symbol: String,
+    pub base_uri: String,
 }
 
-// Events
-#[event]
-pub struct CrossChainTransferEvent {
-    pub token_id: u64,
-    pub from_chain: String,
-    pub to_chain: String,
-    pub sender: Pubkey,
-    pub receiver: [u8; 20],
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ChainConfigParams {
+    /// CAIP-2 identifier for the chain, e.g. `eip155:1` or `solana:mainnet`
+    pub caip2: String,
+    pub enabled: bool,
+    pub gas_deposit_lamports: u64,
+    pub receiver_format: ReceiverFormat,
+    pub gas_token_mints: Vec<Pubkey>,
+    pub transport: Transport,
+    pub transport_chain_id: u32,
+    pub peer: [u8; 32],
+    pub compact_messages: bool,
 }
 
-#[event]
-pub struct CrossChainTransferReceived {
-    pub token_id: u64,
-    pub sender: [u8; 20],
+/// Arguments of a LayerZero `lz_receive` delivery
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LzReceiveParams {
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub guid: [u8; 32],
+    pub message: Vec<u8>,
+    pub extra_data: Vec<u8>,
+}
+
+/// Arguments of the LayerZero endpoint's `send`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LzSendParams {
+    pub dst_eid: u32,
+    pub receiver: [u8; 32],
+    pub message: Vec<u8>,
+    pub options: Vec<u8>,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
+
+/// Arguments of the LayerZero endpoint's `clear`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LzClearParams {
     pub receiver: Pubkey,
-    pub name: String,
-    pub symbol: String,
-    pub uri: String,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub guid: [u8; 32],
+    pub message: Vec<u8>,
 }
 
-#[event]
-pub struct CrossChainTransferReverted {
-    pub token_id: u64,
-    pub original_sender: Pubkey,
-    pub reverted_amount: u64,
+/// Arguments of the LayerZero endpoint's `register_oapp`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LzRegisterOAppParams {
+    pub delegate: Pubkey,
+}
+
+/// Onward hop the universal contract forwards an NFT to after the destination chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FinalDestination {
+    pub chain_id: u64,
+    /// Receiver encoded per the final chain's format
+    pub receiver: Vec<u8>,
+}
+
+/// Caller-supplied overrides for the Gateway `RevertOptions`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct RevertParams {
+    pub revert_address: Option<Pubkey>,
+    pub call_on_revert: Option<bool>,
 }
 
 // Error codes
@@ -679,18 +8336,206 @@ pub enum UniversalNFTError {
     AlreadyBurned,
     #[msg("Invalid token ID")]
     InvalidTokenId,
-}
-
-#[error_code]
-pub enum ErrorCode {
+    #[msg("Insufficient fees available in the treasury")]
+    InsufficientFees,
+    #[msg("NFT name exceeds the maximum length")]
+    NameTooLong,
+    #[msg("NFT symbol exceeds the maximum length")]
+    SymbolTooLong,
+    #[msg("NFT URI exceeds the maximum length")]
+    UriTooLong,
+    #[msg("NFT has not been burned")]
+    NotBurned,
+    #[msg("Burned NFT is not old enough to be swept")]
+    SweepTooEarly,
+    #[msg("Basis points must not exceed 10000")]
+    InvalidBasisPoints,
+    #[msg("Transfer permit has expired")]
+    PermitExpired,
+    #[msg("Transfer permit nonce does not match")]
+    InvalidPermitNonce,
+    #[msg("Transfer permit signature is missing or invalid")]
+    InvalidPermitSignature,
+    #[msg("Airdrop recipients or accounts are invalid")]
+    InvalidAirdrop,
+    #[msg("Owner index page does not belong to the expected owner")]
+    OwnerIndexMismatch,
+    #[msg("Owner index page is full")]
+    OwnerIndexFull,
+    #[msg("Token is not present in the index page")]
+    TokenNotIndexed,
+    #[msg("Supply index page is full")]
+    SupplyIndexFull,
+    #[msg("Index account does not match the expected address")]
+    InvalidIndexAccount,
+    #[msg("Transfer is not in the expected pending state")]
+    TransferNotPending,
+    #[msg("Transfer has not been reverted and has not timed out yet")]
+    TransferNotExpired,
+    #[msg("Transfer has already been dispatched to the Gateway")]
+    AlreadyDispatched,
+    #[msg("Outbound queue is empty")]
+    QueueEmpty,
+    #[msg("Wallet has reached its outbound transfer limit for this epoch")]
+    RateLimitExceeded,
+    #[msg("Bridging is paused")]
+    BridgePaused,
+    #[msg("Max supply reached")]
+    MaxSupplyReached,
+    #[msg("Max supply can only be lowered once set")]
+    MaxSupplyLocked,
+    #[msg("Caller is not on the allowlist")]
+    NotAllowlisted,
+    #[msg("Address has reached its mint limit")]
+    ClaimLimitReached,
+    #[msg("Sale is not active")]
+    SaleNotActive,
+    #[msg("Sale payment accounts are missing or invalid")]
+    InvalidSalePayment,
+    #[msg("Soulbound NFTs can only be moved by the collection authority")]
+    Soulbound,
+    #[msg("NFT mint has no program freeze authority")]
+    NotFreezable,
+    #[msg("NFT is already staked")]
+    AlreadyStaked,
+    #[msg("NFT is not staked")]
+    NotStaked,
+    #[msg("Rental expiry must be in the future")]
+    InvalidRentalExpiry,
+    #[msg("Rental has not expired")]
+    RentalActive,
+    #[msg("Collection account does not match the NFT's collection")]
+    CollectionMismatch,
+    #[msg("Collection does not allow bridging in this direction")]
+    CollectionBridgeDisabled,
+    #[msg("Collection has no counterpart contract on the destination chain")]
+    CollectionNotMapped,
+    #[msg("Token is not held in escrow by the program")]
+    TokenNotEscrowed,
+    #[msg("Attribute key or value is invalid")]
+    InvalidAttribute,
+    #[msg("Token has reached its attribute limit")]
+    TooManyAttributes,
+    #[msg("Mint phases must be ordered, non-overlapping windows")]
+    InvalidMintPhases,
+    #[msg("Requested mint phase is not the active phase")]
+    PhaseNotActive,
+    #[msg("Mint does not hold the supply of a 1/1 NFT")]
+    InvalidNftSupply,
     #[msg("The data provided could not be converted to a valid UTF-8 string.")]
     InvalidDataFormat,
     #[msg("Failed to decode cross-chain transfer data")]
     DecodingError,
+    #[msg("Unknown or unsupported inbound message type")]
+    UnsupportedMessageType,
+    #[msg("Destination is this Solana chain; use transfer_local instead")]
+    SelfChainDestination,
     #[msg("Failed to serialize data")]
     SerializationError,
-    #[msg("Not the owner of the NFT")]
-    NotOwner,
     #[msg("Invalid caller - must be called by authorized program")]
     InvalidCaller,
+    #[msg("Gateway program does not match the configured gateway")]
+    GatewayMismatch,
+    #[msg("Gateway account is not owned by the expected program")]
+    InvalidGatewayAccount,
+    #[msg("on_revert gas limit exceeds the configured maximum")]
+    RevertGasLimitTooHigh,
+    #[msg("Revert message exceeds the maximum length")]
+    RevertMessageTooLong,
+    #[msg("Gas token is not accepted for the destination chain")]
+    UnsupportedGasToken,
+    #[msg("Gas amount must be greater than zero")]
+    InvalidGasAmount,
+    #[msg("Too many gas tokens for a single chain")]
+    TooManyGasTokens,
+    #[msg("Receiver address is invalid for the destination chain")]
+    InvalidReceiver,
+    #[msg("Destination chain is not registered or is disabled")]
+    UnsupportedChain,
+    #[msg("Receiver address must not be the zero address")]
+    ZeroReceiver,
+    #[msg("Receiver address does not match its EIP-55 checksum")]
+    InvalidChecksum,
+    #[msg("Chain stats account does not match the source chain")]
+    ChainStatsMismatch,
+    #[msg("Chain identifier is not a valid CAIP-2 string")]
+    InvalidCaip2,
+    #[msg("CAIP-2 identifier is already registered to another chain")]
+    Caip2InUse,
+    #[msg("Destination chain is not reached through this transport")]
+    TransportMismatch,
+    #[msg("Wormhole program does not match the configured program")]
+    InvalidWormholeProgram,
+    #[msg("LayerZero endpoint does not match the configured endpoint")]
+    InvalidLayerZeroEndpoint,
+    #[msg("Compact message URI hash has no matching registry entry")]
+    UriNotRegistered,
+    #[msg("Lookup table does not match the configured table")]
+    InvalidLookupTable,
+    #[msg("Program PDA cannot cover the rent; top it up with fund_pda")]
+    PdaUnderfunded,
+    #[msg("Accounts to refund the reverted deposit are missing or do not match the sender")]
+    InvalidRefundAccounts,
+    #[msg("Accounts to forward the inbound deposit are missing or do not match its recipient")]
+    InvalidDepositRecipient,
+    #[msg("Gateway whitelist entry does not match the configured gateway and mint")]
+    InvalidGatewayWhitelist,
+    #[msg("Gateway token account is not the Gateway PDA's account for the mint")]
+    InvalidGatewayTokenAccount,
+    #[msg("Too many programs in the CPI caller allowlist")]
+    TooManyAllowedCallers,
+    #[msg("Transfer extra data exceeds the maximum length")]
+    ExtraDataTooLong,
+    #[msg("Rescue does not match the queued request")]
+    RescueMismatch,
+    #[msg("Queued rescue is still timelocked")]
+    RescueTimelocked,
+    #[msg("NFTs of this program cannot be rescued")]
+    RescueEscrowedNft,
+    #[msg("Accounts for the rescued asset are missing or do not match its destination")]
+    InvalidRescueAccounts,
+    #[msg("Total supply would overflow")]
+    SupplyOverflow,
+    #[msg("Total supply would underflow")]
+    SupplyUnderflow,
+    #[msg("Token id space is exhausted")]
+    TokenIdOverflow,
+    #[msg("State counter would overflow")]
+    CounterOverflow,
+    #[msg("Fee total would overflow")]
+    FeeOverflow,
+    #[msg("Token id must be the next sequential id")]
+    TokenIdNotSequential,
+    #[msg("Address is on the blocklist")]
+    AddressBlocked,
+    #[msg("Blocked address must be between 1 and 90 bytes")]
+    InvalidBlockedAddress,
+    #[msg("Collection has reached its outbound transfer cap for this epoch")]
+    CollectionOutboundCapReached,
+    #[msg("Collection config account is missing or not the collection's")]
+    InvalidCollectionConfig,
+    #[msg("Unpause bridging and disable the circuit breaker before renouncing the authority")]
+    CircuitBreakerArmed,
+    #[msg("Final destination must be another chain with a receiver of 1 to 90 bytes")]
+    InvalidFinalDestination,
+    #[msg("Amount must be 1 for an NFT and non-zero for a semi-fungible token")]
+    InvalidAmount,
+    #[msg("Not supported for semi-fungible tokens")]
+    SemiFungibleUnsupported,
+    #[msg("Returning token's name, symbol or URI differs from when it left Solana")]
+    MetadataHashMismatch,
+    #[msg("Serialized outbound message exceeds the maximum length")]
+    MessageTooLarge,
+    #[msg("Receiver address has the wrong length for the destination chain")]
+    InvalidReceiverLength,
+    #[msg("Collection is already migrating to another chain")]
+    MigrationMismatch,
+    #[msg("Collection items must be migrated in ascending token id order")]
+    MigrationOutOfOrder,
+    #[msg("Migration receiver does not match the one the holder registered")]
+    MigrationReceiverMismatch,
+    #[msg("Transfer was not queued with a gas escrow")]
+    TransferNotQueued,
+    #[msg("NFT was bridged out and may still return or be reclaimed")]
+    NftBridgedOut,
 }
@@ -0,0 +1,1172 @@
+//! End-to-end round trips through the mock Gateway: mint, transfer out, revert, and inbound mint
+//!
+//! Runs against the built programs. Build them and fetch the Token Metadata program first:
+//!
+//! ```bash
+//! anchor build
+//! solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s \
+//!     programs/connected/tests/fixtures/mpl_token_metadata.so
+//! cargo test -p connected --test round_trip
+//! ```
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::*;
+use anchor_lang::{AccountDeserialize, AccountSerialize, Discriminator, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use anchor_spl::token::TokenAccount;
+use connected::{
+    ChainConfigParams, CrossChainMessage, CrossChainNFTTransfer, CrossChainTransferEvent,
+    CrossChainTransferReceived, CrossChainTransferReverted, FinalDestination, LegacyCrossChainData,
+    LegacyNFTInfo, LegacyUniversalNFTState, NFTInfo, PendingTransfer, ProvenanceAction, Receipt,
+    ReceiverFormat, RevertRefunded, TransferRecord, Transport, TransferStatus, UniversalNFTState,
+    UpdateConfigParams,
+};
+use solana_banks_interface::BanksTransactionResultWithMetadata;
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use spl_associated_token_account::get_associated_token_address;
+
+const DESTINATION_CHAIN_ID: u64 = 1;
+const EVM_RECIPIENT: [u8; 20] = [0x11; 20];
+const GAS_AMOUNT: u64 = 2_000_000;
+const GAS_CHANGE: u64 = 500_000;
+const TOKEN_ID: u64 = 1;
+const URI: &str = "https://example.com/1.json";
+const EXTRA_DATA: &[u8] = b"quest:42";
+const FINAL_CHAIN_ID: u64 = 8453;
+const FINAL_RECIPIENT: [u8; 20] = [0x22; 20];
+// Budget for a transfer_cross_chain of a maximum-length URI; raise only with a reason
+const TRANSFER_CROSS_CHAIN_CU_BUDGET: u64 = 120_000;
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &connected::ID).0
+}
+
+fn blocklist_entry(address: &[u8]) -> Pubkey {
+    pda(&[b"blocked", &solana_sdk::hash::hash(address).to_bytes()])
+}
+
+// Id of the `nonce`th outbound transfer of TOKEN_ID
+fn transfer_id(nonce: u64) -> [u8; 32] {
+    connected::outbound_transfer_id(TOKEN_ID, DESTINATION_CHAIN_ID, nonce)
+}
+
+fn transfer_record(nonce: u64) -> Pubkey {
+    pda(&[b"transfer_record", &transfer_id(nonce)])
+}
+
+fn pending_transfer(nonce: u64) -> Pubkey {
+    pda(&[b"pending_transfer", &transfer_id(nonce)])
+}
+
+fn receipt(owner: &Pubkey, nonce: u64) -> Pubkey {
+    pda(&[b"receipt", owner.as_ref(), &transfer_id(nonce)])
+}
+
+fn connected_ix(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: connected::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> BanksTransactionResultWithMetadata {
+    let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+    all_instructions.extend_from_slice(instructions);
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &all_instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    if let Err(err) = &result.result {
+        let logs = result.metadata.as_ref().map(|metadata| metadata.log_messages.clone());
+        panic!("transaction failed: {err:?}\n{logs:#?}");
+    }
+    result
+}
+
+// Send a transaction that must fail, returning the custom error code of its failing instruction
+async fn send_expecting_error(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> u32 {
+    let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+    all_instructions.extend_from_slice(instructions);
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &all_instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    match context.banks_client.process_transaction(transaction).await {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => code,
+        other => panic!("expected a custom program error, got {other:?}"),
+    }
+}
+
+async fn fetch<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap_or_else(|| panic!("account {address} does not exist"));
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+// Overwrite a program account's data in place, to put state at its limits
+async fn store<T: AccountSerialize>(context: &mut ProgramTestContext, address: Pubkey, value: &T) {
+    let mut account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    account.data[..data.len()].copy_from_slice(&data);
+    context.set_account(&address, &account.into());
+}
+
+// Compute units consumed by the top-level invocation of the connected program
+fn connected_compute_units(result: &BanksTransactionResultWithMetadata) -> u64 {
+    let prefix = format!("Program {} consumed ", connected::ID);
+    // Self-invoked event CPIs log first; the outermost invocation finishes last
+    let line = result
+        .metadata
+        .iter()
+        .flat_map(|metadata| &metadata.log_messages)
+        .filter_map(|line| line.strip_prefix(&prefix))
+        .last()
+        .expect("connected program did not run");
+    line.split_whitespace().next().unwrap().parse().unwrap()
+}
+
+// Decode the `emit_cpi!` events of type `E` from a transaction's inner instructions
+fn events<E: AnchorDeserialize + Discriminator>(result: &BanksTransactionResultWithMetadata) -> Vec<E> {
+    result
+        .metadata
+        .iter()
+        .flat_map(|metadata| metadata.inner_instructions.iter().flatten().flatten())
+        .filter_map(|inner| {
+            let data = inner.instruction.data.strip_prefix(EVENT_IX_TAG_LE)?;
+            let data = data.strip_prefix(E::DISCRIMINATOR)?;
+            E::try_from_slice(data).ok()
+        })
+        .collect()
+}
+
+struct Harness {
+    context: ProgramTestContext,
+    user: Keypair,
+    gateway_meta: Pubkey,
+    event_authority: Pubkey,
+}
+
+async fn setup() -> Harness {
+    let mut program_test = ProgramTest::new("connected", connected::ID, None);
+    program_test.add_program("mock_gateway", mock_gateway::ID, None);
+    program_test.add_program("mpl_token_metadata", mpl_token_metadata::ID, None);
+    let mut context = program_test.start_with_context().await;
+
+    let user = Keypair::new();
+    let payer = context.payer.pubkey();
+    let gateway_meta = Pubkey::find_program_address(&[b"meta"], &mock_gateway::ID).0;
+    let event_authority = pda(&[b"__event_authority"]);
+    let state = pda(&[b"universal_nft_state"]);
+    let config = pda(&[b"config"]);
+
+    let initialize = connected_ix(
+        connected::accounts::Initialize {
+            payer,
+            authority: payer,
+            universal_nft_state: state,
+            pda: pda(&[b"connected"]),
+            config,
+            treasury: pda(&[b"treasury"]),
+            outbound_queue: pda(&[b"outbound_queue"]),
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::Initialize {},
+    );
+    let use_mock_gateway = connected_ix(
+        connected::accounts::UpdateConfig {
+            authority: payer,
+            universal_nft_state: state,
+            config,
+        },
+        connected::instruction::UpdateConfig {
+            params: UpdateConfigParams {
+                gateway_program: Some(mock_gateway::ID),
+                ..Default::default()
+            },
+        },
+    );
+    let caip2 = "eip155:1".to_string();
+    let set_chain_config = connected_ix(
+        connected::accounts::SetChainConfig {
+            payer,
+            authority: payer,
+            universal_nft_state: state,
+            config,
+            chain_config: pda(&[b"chain_config", &DESTINATION_CHAIN_ID.to_le_bytes()]),
+            chain_stats: pda(&[b"chain_stats", &DESTINATION_CHAIN_ID.to_le_bytes()]),
+            caip2_mapping: pda(&[b"caip2", &solana_sdk::hash::hash(caip2.as_bytes()).to_bytes()]),
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::SetChainConfig {
+            chain_id: DESTINATION_CHAIN_ID,
+            params: ChainConfigParams {
+                caip2,
+                enabled: true,
+                gas_deposit_lamports: GAS_AMOUNT,
+                receiver_format: ReceiverFormat::Evm,
+                gas_token_mints: vec![],
+                transport: Transport::Gateway,
+                transport_chain_id: DESTINATION_CHAIN_ID as u32,
+                peer: [0; 32],
+                compact_messages: false,
+            },
+        },
+    );
+    let init_owner_index = connected_ix(
+        connected::accounts::InitOwnerIndex {
+            payer,
+            owner_index: pda(&[b"owner_index", user.pubkey().as_ref(), &0u32.to_le_bytes()]),
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::InitOwnerIndex { owner: user.pubkey(), page: 0 },
+    );
+    let init_supply_index = connected_ix(
+        connected::accounts::InitSupplyIndex {
+            payer,
+            supply_index: pda(&[b"supply_index", &0u32.to_le_bytes()]),
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::InitSupplyIndex { page: 0 },
+    );
+    let init_gateway = Instruction {
+        program_id: mock_gateway::ID,
+        accounts: mock_gateway::accounts::Initialize {
+            signer: payer,
+            pda: gateway_meta,
+            system_program: solana_sdk::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: mock_gateway::instruction::Initialize {}.data(),
+    };
+    // Inbound mints create accounts with rent paid by the program PDA
+    let fund_user = system_instruction::transfer(&payer, &user.pubkey(), 1_000_000_000);
+    let fund_pda = connected_ix(
+        connected::accounts::FundPda {
+            funder: payer,
+            universal_nft_state: state,
+            pda: pda(&[b"connected"]),
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::FundPda { amount: 1_000_000_000 },
+    );
+
+    send(
+        &mut context,
+        &[
+            initialize,
+            use_mock_gateway,
+            set_chain_config,
+            init_owner_index,
+            init_supply_index,
+            init_gateway,
+            fund_user,
+            fund_pda,
+        ],
+        &[],
+    )
+    .await;
+
+    Harness {
+        context,
+        user,
+        gateway_meta,
+        event_authority,
+    }
+}
+
+async fn mint_local(harness: &mut Harness, uri: &str) -> (Pubkey, Pubkey) {
+    let (mint_nft, mint, token_account) = mint_nft_ix(harness, TOKEN_ID, uri);
+    send(&mut harness.context, &[mint_nft], &[]).await;
+    (mint, token_account)
+}
+
+// Authority mint of `token_id` to the user, with its mint and the user's token account
+fn mint_nft_ix(harness: &Harness, token_id: u64, uri: &str) -> (Instruction, Pubkey, Pubkey) {
+    let payer = harness.context.payer.pubkey();
+    let user = harness.user.pubkey();
+    let mint = pda(&[b"nft_mint", &token_id.to_le_bytes()]);
+    let token_account = get_associated_token_address(&user, &mint);
+
+    let mint_nft = connected_ix(
+        connected::accounts::MintNFT {
+            payer,
+            authority: payer,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            pda: pda(&[b"connected"]),
+            config: pda(&[b"config"]),
+            treasury: pda(&[b"treasury"]),
+            mint,
+            recipient: user,
+            token_account,
+            nft_info: pda(&[b"nft_info", &token_id.to_le_bytes()]),
+            metadata: mpl_token_metadata::accounts::Metadata::find_pda(&mint).0,
+            owner_index: pda(&[b"owner_index", user.as_ref(), &0u32.to_le_bytes()]),
+            mint_index: pda(&[b"mint_index", mint.as_ref()]),
+            supply_index: pda(&[b"supply_index", &0u32.to_le_bytes()]),
+            collection: None,
+            token_program: spl_token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            metadata_program: mpl_token_metadata::ID,
+            system_program: solana_sdk::system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        connected::instruction::MintNft {
+            token_id,
+            name: "Universal".to_string(),
+            symbol: "UNFT".to_string(),
+            uri: uri.to_string(),
+            to: user,
+            soulbound: false,
+        },
+    );
+    (mint_nft, mint, token_account)
+}
+
+async fn transfer_out(
+    harness: &mut Harness,
+    mint: Pubkey,
+    token_account: Pubkey,
+    uri: &str,
+    gas_amount: u64,
+) -> BanksTransactionResultWithMetadata {
+    let transfer = transfer_out_ix(harness, mint, token_account, uri, gas_amount);
+    send(&mut harness.context, &[transfer], &[&harness.user]).await
+}
+
+fn transfer_out_ix(harness: &Harness, mint: Pubkey, token_account: Pubkey, uri: &str, gas_amount: u64) -> Instruction {
+    let user = harness.user.pubkey();
+    connected_ix(
+        connected::accounts::TransferCrossChain {
+            signer: user,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            nft_info: pda(&[b"nft_info", &TOKEN_ID.to_le_bytes()]),
+            token_account,
+            mint,
+            owner_index: pda(&[b"owner_index", user.as_ref(), &0u32.to_le_bytes()]),
+            supply_index: pda(&[b"supply_index", &0u32.to_le_bytes()]),
+            instruction_sysvar: solana_sdk::sysvar::instructions::ID,
+            config: pda(&[b"config"]),
+            treasury: pda(&[b"treasury"]),
+            chain_config: pda(&[b"chain_config", &DESTINATION_CHAIN_ID.to_le_bytes()]),
+            chain_stats: pda(&[b"chain_stats", &DESTINATION_CHAIN_ID.to_le_bytes()]),
+            pending_transfer: pending_transfer(0),
+            receipt: receipt(&user, 0),
+            transfer_record: transfer_record(0),
+            rate_limit: pda(&[b"rate_limit", user.as_ref()]),
+            owner_blocklist_entry: blocklist_entry(user.as_ref()),
+            recipient_blocklist_entry: blocklist_entry(&EVM_RECIPIENT),
+            collection_authority: None,
+            pda: None,
+            collection: None,
+            collection_address: None,
+            collection_config: None,
+            attributes: None,
+            gateway_pda: harness.gateway_meta,
+            gateway_program: mock_gateway::ID,
+            token_program: spl_token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: solana_sdk::system_program::ID,
+            event_authority: harness.event_authority,
+            program: connected::ID,
+        },
+        connected::instruction::TransferCrossChain {
+            token_id: TOKEN_ID,
+            recipient_address: EVM_RECIPIENT.to_vec(),
+            destination_chain_id: DESTINATION_CHAIN_ID,
+            metadata_uri: uri.to_string(),
+            on_revert_gas_limit: None,
+            revert_params: None,
+            gas_amount: Some(gas_amount),
+            recipient_checksum: None,
+            extra_data: Some(EXTRA_DATA.to_vec()),
+            final_destination: Some(FinalDestination {
+                chain_id: FINAL_CHAIN_ID,
+                receiver: FINAL_RECIPIENT.to_vec(),
+            }),
+            amount: None,
+        },
+    )
+}
+
+#[tokio::test]
+async fn transfer_out_records_gateway_call_and_burns() {
+    let mut harness = setup().await;
+    let (mint, token_account) = mint_local(&mut harness, URI).await;
+    let result = transfer_out(&mut harness, mint, token_account, URI, GAS_AMOUNT).await;
+
+    let nft_info: NFTInfo = fetch(&mut harness.context, pda(&[b"nft_info", &TOKEN_ID.to_le_bytes()])).await;
+    assert!(nft_info.is_burned);
+    assert!(nft_info.universal_id.is_some());
+    assert_eq!(nft_info.provenance.last().unwrap().action, ProvenanceAction::BridgeOut);
+
+    let pending_transfer: PendingTransfer = fetch(&mut harness.context, pending_transfer(0)).await;
+    assert_eq!(pending_transfer.status, TransferStatus::Initiated);
+    assert!(pending_transfer.dispatched);
+    assert_eq!(pending_transfer.destination_chain_id, DESTINATION_CHAIN_ID);
+    assert_eq!(pending_transfer.owner, harness.user.pubkey());
+
+    // The owner holds a receipt for the in-flight NFT
+    let receipt: Receipt = fetch(&mut harness.context, receipt(&harness.user.pubkey(), 0)).await;
+    assert_eq!(receipt.owner, harness.user.pubkey());
+    assert_eq!(receipt.token_id, TOKEN_ID);
+    assert_eq!(receipt.transfer_id, pending_transfer.transfer_id);
+
+    // The transfer's status record is keyed by the token's first transfer id
+    let record: TransferRecord = fetch(&mut harness.context, transfer_record(0)).await;
+    assert_eq!(record.transfer_id, pending_transfer.transfer_id);
+    assert_eq!(record.status, TransferStatus::Initiated);
+    assert_eq!(nft_info.transfer_nonce, 1);
+
+    // The Gateway saw the SOL gas deposit and the serialized transfer message
+    let meta: mock_gateway::Meta = fetch(&mut harness.context, harness.gateway_meta).await;
+    assert_eq!(meta.deposit_count, 1);
+    let deposit = meta.last_deposit.unwrap();
+    assert_eq!(deposit.signer, harness.user.pubkey());
+    assert_eq!(deposit.amount, GAS_AMOUNT);
+    assert_eq!(deposit.mint, None);
+    let message = CrossChainMessage::try_from_slice(&deposit.message).unwrap();
+    assert_eq!(message.token_id, TOKEN_ID);
+    assert_eq!(message.recipient_address, EVM_RECIPIENT.to_vec());
+    assert_eq!(Some(message.universal_id), nft_info.universal_id);
+    assert_eq!(message.extra_data, EXTRA_DATA);
+    assert_eq!(message.final_destination_chain, Some(FINAL_CHAIN_ID));
+    assert_eq!(message.final_receiver, FINAL_RECIPIENT.to_vec());
+
+    // The source token account is closed once the NFT is burned
+    let token_account = harness.context.banks_client.get_account(token_account).await.unwrap();
+    assert!(token_account.is_none());
+
+    let sent: Vec<CrossChainTransferEvent> = events(&result);
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].token_id, TOKEN_ID);
+    assert_eq!(sent[0].transfer_id, pending_transfer.transfer_id);
+    assert_eq!(sent[0].receiver, EVM_RECIPIENT.to_vec());
+    assert_eq!(sent[0].final_destination_chain, Some(FINAL_CHAIN_ID));
+    assert_eq!(sent[0].final_receiver, FINAL_RECIPIENT.to_vec());
+}
+
+#[tokio::test]
+async fn transfer_out_without_gas_sends_message_only_call() {
+    let mut harness = setup().await;
+    let (mint, token_account) = mint_local(&mut harness, URI).await;
+    transfer_out(&mut harness, mint, token_account, URI, 0).await;
+
+    // No tokens move through the Gateway; the NFT travels in the message alone
+    let meta: mock_gateway::Meta = fetch(&mut harness.context, harness.gateway_meta).await;
+    assert_eq!(meta.deposit_count, 1);
+    let deposit = meta.last_deposit.unwrap();
+    assert_eq!(deposit.amount, 0);
+    assert_eq!(deposit.mint, None);
+    let message = CrossChainMessage::try_from_slice(&deposit.message).unwrap();
+    assert_eq!(message.token_id, TOKEN_ID);
+    assert_eq!(message.metadata_uri, URI);
+}
+
+#[tokio::test]
+async fn transfer_out_stays_within_compute_budget() {
+    let mut harness = setup().await;
+    let uri = format!("https://example.com/{}", "a".repeat(connected::MAX_URI_LEN - 20));
+    assert_eq!(uri.len(), connected::MAX_URI_LEN);
+    let (mint, token_account) = mint_local(&mut harness, &uri).await;
+    let result = transfer_out(&mut harness, mint, token_account, &uri, GAS_AMOUNT).await;
+
+    let consumed = connected_compute_units(&result);
+    assert!(
+        consumed <= TRANSFER_CROSS_CHAIN_CU_BUDGET,
+        "transfer_cross_chain used {consumed} CU, budget is {TRANSFER_CROSS_CHAIN_CU_BUDGET}"
+    );
+}
+
+#[tokio::test]
+async fn local_token_ids_are_strictly_sequential() {
+    let mut harness = setup().await;
+
+    // An explicit id past next_token_id would leave a gap in the local id range
+    let (skip_ahead, _, _) = mint_nft_ix(&harness, TOKEN_ID + 1, URI);
+    let code = send_expecting_error(&mut harness.context, &[skip_ahead], &[]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::TokenIdNotSequential));
+
+    // mint_nft_auto takes the same accounts, derived from the next id, and returns that id
+    let (mint_nft, _, _) = mint_nft_ix(&harness, TOKEN_ID, URI);
+    let mint_auto = Instruction {
+        data: connected::instruction::MintNftAuto {
+            name: "Universal".to_string(),
+            symbol: "UNFT".to_string(),
+            uri: URI.to_string(),
+            to: harness.user.pubkey(),
+            soulbound: false,
+        }
+        .data(),
+        ..mint_nft
+    };
+    let result = send(&mut harness.context, &[mint_auto], &[]).await;
+    let return_data = result.metadata.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, connected::ID);
+    // Return data comes back with trailing zero bytes trimmed
+    let mut token_id = [0u8; 8];
+    token_id[..return_data.data.len()].copy_from_slice(&return_data.data);
+    assert_eq!(u64::from_le_bytes(token_id), TOKEN_ID);
+
+    let state: UniversalNFTState = fetch(&mut harness.context, pda(&[b"universal_nft_state"])).await;
+    assert_eq!(state.next_token_id, TOKEN_ID + 1);
+    assert_eq!(state.total_supply, 1);
+}
+
+#[tokio::test]
+async fn mint_at_supply_limit_fails_with_overflow_error() {
+    let mut harness = setup().await;
+    let state_address = pda(&[b"universal_nft_state"]);
+    let mut state: UniversalNFTState = fetch(&mut harness.context, state_address).await;
+    state.total_supply = u64::MAX;
+    store(&mut harness.context, state_address, &state).await;
+
+    let (mint_nft, _, _) = mint_nft_ix(&harness, TOKEN_ID, URI);
+    let code = send_expecting_error(&mut harness.context, &[mint_nft], &[]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::SupplyOverflow));
+}
+
+#[tokio::test]
+async fn mint_by_non_authority_fails() {
+    let mut harness = setup().await;
+    let (mut mint_nft, _, _) = mint_nft_ix(&harness, TOKEN_ID, URI);
+    // Swap the authority signer for the user, who holds no authority over the program
+    mint_nft.accounts[1].pubkey = harness.user.pubkey();
+    let code = send_expecting_error(&mut harness.context, &[mint_nft], &[&harness.user]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::Unauthorized));
+}
+
+#[tokio::test]
+async fn mint_after_renouncing_authority_fails() {
+    let mut harness = setup().await;
+    let renounce = connected_ix(
+        connected::accounts::RenounceAuthority {
+            authority: harness.context.payer.pubkey(),
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            config: pda(&[b"config"]),
+        },
+        connected::instruction::RenounceAuthority {},
+    );
+    send(&mut harness.context, &[renounce], &[]).await;
+
+    let (mint_nft, _, _) = mint_nft_ix(&harness, TOKEN_ID, URI);
+    let code = send_expecting_error(&mut harness.context, &[mint_nft], &[]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::Unauthorized));
+}
+
+#[tokio::test]
+async fn transfer_out_at_zero_supply_fails_with_underflow_error() {
+    let mut harness = setup().await;
+    let (mint, token_account) = mint_local(&mut harness, URI).await;
+    let state_address = pda(&[b"universal_nft_state"]);
+    let mut state: UniversalNFTState = fetch(&mut harness.context, state_address).await;
+    state.total_supply = 0;
+    store(&mut harness.context, state_address, &state).await;
+
+    let transfer = transfer_out_ix(&harness, mint, token_account, URI, GAS_AMOUNT);
+    let code = send_expecting_error(&mut harness.context, &[transfer], &[&harness.user]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::SupplyUnderflow));
+}
+
+#[tokio::test]
+async fn transfer_to_blocked_recipient_fails() {
+    let mut harness = setup().await;
+    let (mint, token_account) = mint_local(&mut harness, URI).await;
+    let payer = harness.context.payer.pubkey();
+    let block = connected_ix(
+        connected::accounts::BlockAddress {
+            authority: payer,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            blocklist_entry: blocklist_entry(&EVM_RECIPIENT),
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::BlockAddress { address: EVM_RECIPIENT.to_vec() },
+    );
+    send(&mut harness.context, &[block], &[]).await;
+
+    let transfer = transfer_out_ix(&harness, mint, token_account, URI, GAS_AMOUNT);
+    let code = send_expecting_error(&mut harness.context, &[transfer], &[&harness.user]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::AddressBlocked));
+}
+
+// Accounts restoring the first transfer of TOKEN_ID and refunding its SOL gas to the user
+fn on_revert_accounts(harness: &Harness) -> connected::accounts::OnRevert {
+    connected::accounts::OnRevert {
+        pda: pda(&[b"connected"]),
+        universal_nft_state: pda(&[b"universal_nft_state"]),
+        signer: harness.context.payer.pubkey(),
+        config: pda(&[b"config"]),
+        instruction_sysvar: solana_sdk::sysvar::instructions::ID,
+        chain_stats: Some(pda(&[b"chain_stats", &DESTINATION_CHAIN_ID.to_le_bytes()])),
+        pending_transfer: Some(pending_transfer(0)),
+        transfer_record: Some(transfer_record(0)),
+        nft_info: Some(pda(&[b"nft_info", &TOKEN_ID.to_le_bytes()])),
+        owner: Some(harness.user.pubkey()),
+        pda_token_account: None,
+        owner_token_account: None,
+        token_program: None,
+        system_program: solana_sdk::system_program::ID,
+        event_authority: harness.event_authority,
+        program: connected::ID,
+    }
+}
+
+#[tokio::test]
+async fn revert_marks_transfer_reverted() {
+    let mut harness = setup().await;
+    let (mint, token_account) = mint_local(&mut harness, URI).await;
+    transfer_out(&mut harness, mint, token_account, URI, GAS_AMOUNT).await;
+
+    // The recorded revert message alone identifies the NFT to restore
+    let payer = harness.context.payer.pubkey();
+    let meta: mock_gateway::Meta = fetch(&mut harness.context, harness.gateway_meta).await;
+    let revert_message = meta.last_deposit.unwrap().revert_options.unwrap().revert_message;
+    let revert_data = CrossChainNFTTransfer::try_from_slice(&revert_message).unwrap();
+    assert_eq!(revert_data.token_id, TOKEN_ID);
+    assert_eq!(revert_data.uri, URI);
+    assert_eq!(revert_data.receiver, harness.user.pubkey());
+    assert_eq!(
+        revert_data.extra_data,
+        connected::outbound_transfer_id(TOKEN_ID, DESTINATION_CHAIN_ID, 0).to_vec()
+    );
+    let mut accounts = mock_gateway::accounts::Execute {
+        signer: payer,
+        pda: harness.gateway_meta,
+        destination: connected::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(on_revert_accounts(&harness).to_account_metas(None));
+    let user_balance = harness.context.banks_client.get_balance(harness.user.pubkey()).await.unwrap();
+    let execute_revert = Instruction {
+        program_id: mock_gateway::ID,
+        accounts,
+        data: mock_gateway::instruction::ExecuteRevert {
+            amount: GAS_AMOUNT,
+            sender: harness.user.pubkey(),
+            data: revert_message,
+        }
+        .data(),
+    };
+    let result = send(&mut harness.context, &[execute_revert], &[]).await;
+
+    let pending_transfer: PendingTransfer = fetch(&mut harness.context, pending_transfer(0)).await;
+    assert_eq!(pending_transfer.status, TransferStatus::Reverted);
+    let record: TransferRecord = fetch(&mut harness.context, transfer_record(0)).await;
+    assert_eq!(record.status, TransferStatus::Reverted);
+
+    let nft_info: NFTInfo = fetch(&mut harness.context, pda(&[b"nft_info", &TOKEN_ID.to_le_bytes()])).await;
+    let last = nft_info.provenance.last().unwrap();
+    assert_eq!(last.action, ProvenanceAction::Revert);
+    assert_eq!(last.chain_id, DESTINATION_CHAIN_ID);
+
+    let meta: mock_gateway::Meta = fetch(&mut harness.context, harness.gateway_meta).await;
+    assert_eq!(meta.executed_count, 1);
+
+    let reverted: Vec<CrossChainTransferReverted> = events(&result);
+    assert_eq!(reverted.len(), 1);
+    assert_eq!(reverted[0].token_id, TOKEN_ID);
+    assert_eq!(reverted[0].original_sender, harness.user.pubkey());
+    assert_eq!(reverted[0].transfer_id, pending_transfer.transfer_id);
+    assert_eq!(reverted[0].reverted_amount, GAS_AMOUNT);
+
+    // The reverted gas deposit is forwarded from the program PDA back to the sender
+    let refunded_balance = harness.context.banks_client.get_balance(harness.user.pubkey()).await.unwrap();
+    assert_eq!(refunded_balance, user_balance + GAS_AMOUNT);
+    let refunded: Vec<RevertRefunded> = events(&result);
+    assert_eq!(refunded.len(), 1);
+    assert_eq!(refunded[0].owner, harness.user.pubkey());
+    assert_eq!(refunded[0].mint, None);
+    assert_eq!(refunded[0].amount, GAS_AMOUNT);
+}
+
+#[tokio::test]
+async fn revert_not_from_gateway_is_rejected() {
+    let mut harness = setup().await;
+    let (mint, token_account) = mint_local(&mut harness, URI).await;
+    transfer_out(&mut harness, mint, token_account, URI, GAS_AMOUNT).await;
+
+    // Called directly, a forged revert would refund the program PDA's lamports to its sender
+    let meta: mock_gateway::Meta = fetch(&mut harness.context, harness.gateway_meta).await;
+    let revert_message = meta.last_deposit.unwrap().revert_options.unwrap().revert_message;
+    let forged_revert = connected_ix(
+        on_revert_accounts(&harness),
+        connected::instruction::OnRevert {
+            amount: GAS_AMOUNT,
+            sender: harness.user.pubkey(),
+            data: revert_message,
+        },
+    );
+    let code = send_expecting_error(&mut harness.context, &[forged_revert], &[]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::InvalidCaller));
+
+    let pending_transfer: PendingTransfer = fetch(&mut harness.context, pending_transfer(0)).await;
+    assert_eq!(pending_transfer.status, TransferStatus::Initiated);
+}
+
+// Accounts minting `token_id` on `mint` into the user's associated token account
+fn on_call_accounts(harness: &Harness, mint: Pubkey, token_id: u64) -> connected::accounts::OnCall {
+    let user = harness.user.pubkey();
+    connected::accounts::OnCall {
+        pda: pda(&[b"connected"]),
+        universal_nft_state: pda(&[b"universal_nft_state"]),
+        config: pda(&[b"config"]),
+        pda_ata: None,
+        mint_account: mint,
+        nft_info: pda(&[b"nft_info", &token_id.to_le_bytes()]),
+        mint_index: pda(&[b"mint_index", mint.as_ref()]),
+        pending_claim: None,
+        receiver: Some(user),
+        receiver_ata: Some(get_associated_token_address(&user, &mint)),
+        associated_token_program: Some(spl_associated_token_account::ID),
+        owner_index: pda(&[b"owner_index", user.as_ref(), &0u32.to_le_bytes()]),
+        chain_stats: None,
+        supply_index: pda(&[b"supply_index", &0u32.to_le_bytes()]),
+        uri_registry: None,
+        treasury: None,
+        deposit_token_account: None,
+        recipient_token_account: None,
+        gateway_pda: harness.gateway_meta,
+        instruction_sysvar: solana_sdk::sysvar::instructions::ID,
+        token_program: spl_token::ID,
+        system_program: solana_sdk::system_program::ID,
+        event_authority: harness.event_authority,
+        program: connected::ID,
+    }
+}
+
+#[tokio::test]
+async fn inbound_call_mints_foreign_nft_to_receiver() {
+    let mut harness = setup().await;
+    let payer = harness.context.payer.pubkey();
+    let user = harness.user.pubkey();
+    let program_pda = pda(&[b"connected"]);
+
+    // Inbound NFTs arrive on a fresh mint controlled by the program PDA
+    let mint = Keypair::new();
+    let rent = harness.context.banks_client.get_rent().await.unwrap();
+    let create_mint = system_instruction::create_account(
+        &payer,
+        &mint.pubkey(),
+        rent.minimum_balance(spl_token::state::Mint::LEN),
+        spl_token::state::Mint::LEN as u64,
+        &spl_token::ID,
+    );
+    let init_mint = spl_token::instruction::initialize_mint2(
+        &spl_token::ID,
+        &mint.pubkey(),
+        &program_pda,
+        Some(&program_pda),
+        0,
+    )
+    .unwrap();
+
+    let origin_contract = vec![0x22; 20];
+    let origin_token_id = [0x33; 32];
+    let source_chain = DESTINATION_CHAIN_ID.to_le_bytes().to_vec();
+    let token_id = connected::derive_foreign_token_id(&source_chain, &origin_contract, &origin_token_id);
+    let transfer = CrossChainNFTTransfer {
+        token_id: 0,
+        name: "Traveller".to_string(),
+        symbol: "TRVL".to_string(),
+        uri: "https://example.com/foreign.json".to_string(),
+        receiver: user,
+        source_chain,
+        origin_contract,
+        origin_token_id,
+        extra_data: EXTRA_DATA.to_vec(),
+        amount: 1,
+    };
+    // Mint envelope: MessageType::Mint tag followed by the transfer
+    let mut data = vec![0u8];
+    data.extend(transfer.try_to_vec().unwrap());
+
+    let receiver_ata = get_associated_token_address(&user, &mint.pubkey());
+    let mut accounts = mock_gateway::accounts::Execute {
+        signer: payer,
+        pda: harness.gateway_meta,
+        destination: connected::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(on_call_accounts(&harness, mint.pubkey(), token_id).to_account_metas(None));
+    // The call carries gas change, which the Gateway holds from an earlier deposit
+    let fund_gateway = system_instruction::transfer(&payer, &harness.gateway_meta, GAS_CHANGE);
+    let execute = Instruction {
+        program_id: mock_gateway::ID,
+        accounts,
+        data: mock_gateway::instruction::Execute {
+            amount: GAS_CHANGE,
+            sender: [0x44; 20],
+            data,
+        }
+        .data(),
+    };
+    let user_balance = harness.context.banks_client.get_balance(user).await.unwrap();
+    let result = send(
+        &mut harness.context,
+        &[create_mint, init_mint, fund_gateway, execute],
+        &[&mint],
+    )
+    .await;
+
+    let nft_info: NFTInfo = fetch(&mut harness.context, pda(&[b"nft_info", &token_id.to_le_bytes()])).await;
+    assert_eq!(nft_info.token_id, token_id);
+    assert_eq!(nft_info.owner, user);
+    assert_eq!(nft_info.mint, mint.pubkey());
+    assert_eq!(nft_info.universal_id, Some(origin_token_id));
+    assert_eq!(nft_info.provenance.last().unwrap().action, ProvenanceAction::BridgeIn);
+
+    let token_account: TokenAccount = fetch(&mut harness.context, receiver_ata).await;
+    assert_eq!(token_account.owner, user);
+    assert_eq!(token_account.amount, 1);
+
+    let received: Vec<CrossChainTransferReceived> = events(&result);
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].token_id, token_id);
+    assert_eq!(received[0].receiver, user);
+    assert_eq!(received[0].sender, [0x44; 20]);
+    assert_eq!(received[0].extra_data, EXTRA_DATA);
+    assert_eq!(
+        received[0].transfer_id,
+        connected::inbound_transfer_id(&[0x44; 20], &transfer).unwrap()
+    );
+
+    // The gas change is forwarded to the receiver rather than left on the program PDA
+    let received_balance = harness.context.banks_client.get_balance(user).await.unwrap();
+    assert_eq!(received_balance, user_balance + GAS_CHANGE);
+}
+
+#[tokio::test]
+async fn inbound_call_not_from_gateway_is_rejected() {
+    let mut harness = setup().await;
+    let user = harness.user.pubkey();
+    let (mint, _) = mint_local(&mut harness, URI).await;
+
+    // Called directly, a forged call would mint arbitrary NFTs with rent from the program PDA
+    let source_chain = DESTINATION_CHAIN_ID.to_le_bytes().to_vec();
+    let token_id = connected::derive_foreign_token_id(&source_chain, &[0x22; 20], &[0x33; 32]);
+    let transfer = CrossChainNFTTransfer {
+        token_id: 0,
+        name: "Forged".to_string(),
+        symbol: "FAKE".to_string(),
+        uri: "https://example.com/forged.json".to_string(),
+        receiver: user,
+        source_chain,
+        origin_contract: vec![0x22; 20],
+        origin_token_id: [0x33; 32],
+        extra_data: Vec::new(),
+        amount: 1,
+    };
+    let mut data = vec![0u8];
+    data.extend(transfer.try_to_vec().unwrap());
+    let forged_call = connected_ix(
+        on_call_accounts(&harness, mint, token_id),
+        connected::instruction::OnCall {
+            amount: 0,
+            sender: [0x44; 20],
+            data,
+        },
+    );
+    let code = send_expecting_error(&mut harness.context, &[forged_call], &[]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::InvalidCaller));
+
+    let nft_info = pda(&[b"nft_info", &token_id.to_le_bytes()]);
+    assert!(harness.context.banks_client.get_account(nft_info).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn confirmed_transfer_cannot_be_reclaimed() {
+    let mut harness = setup().await;
+    let (mint, token_account) = mint_local(&mut harness, URI).await;
+    transfer_out(&mut harness, mint, token_account, URI, GAS_AMOUNT).await;
+
+    // Delivered on the destination chain, then timed out locally
+    let payer = harness.context.payer.pubkey();
+    let user = harness.user.pubkey();
+    let confirm = connected_ix(
+        connected::accounts::ConfirmTransfer {
+            authority: payer,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            pending_transfer: pending_transfer(0),
+            receipt: receipt(&user, 0),
+            transfer_record: transfer_record(0),
+            owner: user,
+        },
+        connected::instruction::ConfirmTransfer { transfer_id: transfer_id(0) },
+    );
+    let no_timeout = connected_ix(
+        connected::accounts::UpdateConfig {
+            authority: payer,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            config: pda(&[b"config"]),
+        },
+        connected::instruction::UpdateConfig {
+            params: UpdateConfigParams {
+                transfer_timeout_slots: Some(0),
+                ..Default::default()
+            },
+        },
+    );
+    send(&mut harness.context, &[confirm, no_timeout], &[]).await;
+
+    let reclaim = connected_ix(
+        connected::accounts::ReclaimExpired {
+            owner: user,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            config: pda(&[b"config"]),
+            pda: pda(&[b"connected"]),
+            pending_transfer: pending_transfer(0),
+            receipt: receipt(&user, 0),
+            transfer_record: transfer_record(0),
+            nft_info: pda(&[b"nft_info", &TOKEN_ID.to_le_bytes()]),
+            mint,
+            token_account,
+            owner_index: pda(&[b"owner_index", user.as_ref(), &0u32.to_le_bytes()]),
+            supply_index: pda(&[b"supply_index", &0u32.to_le_bytes()]),
+            token_program: spl_token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::ReclaimExpired { transfer_id: transfer_id(0) },
+    );
+    let code = send_expecting_error(&mut harness.context, &[reclaim], &[&harness.user]).await;
+    assert_eq!(code, anchor_lang::error::ErrorCode::AccountNotInitialized as u32);
+
+    let record: TransferRecord = fetch(&mut harness.context, transfer_record(0)).await;
+    assert_eq!(record.status, TransferStatus::Confirmed);
+    let nft_info: NFTInfo = fetch(&mut harness.context, pda(&[b"nft_info", &TOKEN_ID.to_le_bytes()])).await;
+    assert!(nft_info.is_burned);
+}
+
+#[tokio::test]
+async fn bridged_out_nft_is_not_swept_and_can_return() {
+    let mut harness = setup().await;
+    let (mint, token_account) = mint_local(&mut harness, URI).await;
+    transfer_out(&mut harness, mint, token_account, URI, GAS_AMOUNT).await;
+
+    // Delivered abroad, with burned NFTs sweepable at once
+    let payer = harness.context.payer.pubkey();
+    let user = harness.user.pubkey();
+    let nft_info = pda(&[b"nft_info", &TOKEN_ID.to_le_bytes()]);
+    let confirm = connected_ix(
+        connected::accounts::ConfirmTransfer {
+            authority: payer,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            pending_transfer: pending_transfer(0),
+            receipt: receipt(&user, 0),
+            transfer_record: transfer_record(0),
+            owner: user,
+        },
+        connected::instruction::ConfirmTransfer { transfer_id: transfer_id(0) },
+    );
+    let sweep_at_once = connected_ix(
+        connected::accounts::UpdateConfig {
+            authority: payer,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            config: pda(&[b"config"]),
+        },
+        connected::instruction::UpdateConfig {
+            params: UpdateConfigParams {
+                sweep_min_age_slots: Some(0),
+                ..Default::default()
+            },
+        },
+    );
+    send(&mut harness.context, &[confirm, sweep_at_once], &[]).await;
+
+    // The NFT is only abroad, so neither the crank nor its owner may close its NFTInfo
+    let sweep = connected_ix(
+        connected::accounts::SweepBurned {
+            cranker: payer,
+            universal_nft_state: pda(&[b"universal_nft_state"]),
+            config: pda(&[b"config"]),
+            nft_info,
+            owner: user,
+        },
+        connected::instruction::SweepBurned { token_id: TOKEN_ID },
+    );
+    let code = send_expecting_error(&mut harness.context, &[sweep], &[]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::NftBridgedOut));
+    let close = connected_ix(
+        connected::accounts::CloseBurned {
+            owner: user,
+            nft_info,
+            config: pda(&[b"config"]),
+        },
+        connected::instruction::CloseBurned { token_id: TOKEN_ID },
+    );
+    let code = send_expecting_error(&mut harness.context, &[close], &[&harness.user]).await;
+    assert_eq!(code, u32::from(connected::UniversalNFTError::NftBridgedOut));
+
+    // Bridging back home reuses the NFTInfo and mint, keeping its history
+    let bridged: NFTInfo = fetch(&mut harness.context, nft_info).await;
+    let transfer = CrossChainNFTTransfer {
+        token_id: TOKEN_ID,
+        name: bridged.name.clone(),
+        symbol: bridged.symbol.clone(),
+        uri: URI.to_string(),
+        receiver: user,
+        source_chain: DESTINATION_CHAIN_ID.to_le_bytes().to_vec(),
+        origin_contract: Vec::new(),
+        origin_token_id: bridged.universal_id.unwrap(),
+        extra_data: Vec::new(),
+        amount: 1,
+    };
+    let mut data = vec![0u8];
+    data.extend(transfer.try_to_vec().unwrap());
+    let mut accounts = mock_gateway::accounts::Execute {
+        signer: payer,
+        pda: harness.gateway_meta,
+        destination: connected::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(on_call_accounts(&harness, mint, TOKEN_ID).to_account_metas(None));
+    let execute = Instruction {
+        program_id: mock_gateway::ID,
+        accounts,
+        data: mock_gateway::instruction::Execute {
+            amount: 0,
+            sender: [0x44; 20],
+            data,
+        }
+        .data(),
+    };
+    send(&mut harness.context, &[execute], &[]).await;
+
+    let returned: NFTInfo = fetch(&mut harness.context, nft_info).await;
+    assert!(!returned.is_burned);
+    assert!(returned.cross_chain_data.is_none());
+    assert_eq!(returned.owner, user);
+    assert_eq!(returned.transfer_nonce, bridged.transfer_nonce);
+    assert_eq!(returned.universal_id, bridged.universal_id);
+    assert_eq!(returned.provenance.last().unwrap().action, ProvenanceAction::BridgeIn);
+    let token_account: TokenAccount =
+        fetch(&mut harness.context, get_associated_token_address(&user, &mint)).await;
+    assert_eq!(token_account.amount, 1);
+}
+
+// Overwrite a program account with raw bytes, rent-exempt at their length
+async fn store_raw(context: &mut ProgramTestContext, address: Pubkey, data: Vec<u8>) {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let account = solana_sdk::account::Account {
+        lamports: rent.minimum_balance(data.len()),
+        data,
+        owner: connected::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(&address, &account.into());
+}
+
+#[tokio::test]
+async fn baseline_accounts_migrate_to_current_layout() {
+    let mut harness = setup().await;
+    let payer = harness.context.payer.pubkey();
+    let state = pda(&[b"universal_nft_state"]);
+    let nft_info = pda(&[b"nft_info", &7u64.to_le_bytes()]);
+
+    // Accounts as the first deployment wrote them, at its `8 + size_of` allocations
+    let mut state_data = UniversalNFTState::DISCRIMINATOR.to_vec();
+    LegacyUniversalNFTState { authority: payer, total_supply: 3, next_token_id: 8 }
+        .serialize(&mut state_data)
+        .unwrap();
+    assert_eq!(state_data.len(), 56);
+    store_raw(&mut harness.context, state, state_data).await;
+
+    let owner = harness.user.pubkey();
+    let mint = Pubkey::new_unique();
+    let mut nft_info_data = NFTInfo::DISCRIMINATOR.to_vec();
+    LegacyNFTInfo {
+        token_id: 7,
+        name: "Legacy".to_string(),
+        symbol: "OLD".to_string(),
+        uri: URI.to_string(),
+        owner,
+        mint,
+        is_burned: true,
+        cross_chain_data: Some(LegacyCrossChainData {
+            destination_chain_id: DESTINATION_CHAIN_ID,
+            recipient_address: EVM_RECIPIENT,
+            transfer_timestamp: 1_700_000_000,
+        }),
+    }
+    .serialize(&mut nft_info_data)
+    .unwrap();
+    nft_info_data.resize(208, 0);
+    store_raw(&mut harness.context, nft_info, nft_info_data).await;
+
+    let migrate_state = connected_ix(
+        connected::accounts::MigrateState {
+            authority: payer,
+            universal_nft_state: state,
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::MigrateState {},
+    );
+    let migrate_nft_info = connected_ix(
+        connected::accounts::MigrateNFTInfo {
+            payer,
+            nft_info,
+            system_program: solana_sdk::system_program::ID,
+        },
+        connected::instruction::MigrateNftInfo { _token_id: 7 },
+    );
+    send(&mut harness.context, &[migrate_state, migrate_nft_info], &[]).await;
+
+    let migrated: UniversalNFTState = fetch(&mut harness.context, state).await;
+    assert_eq!(migrated.authority, payer);
+    assert_eq!(migrated.total_supply, 3);
+    assert_eq!(migrated.next_token_id, 8);
+    assert_eq!(migrated.max_supply, None);
+    assert_eq!(migrated.version, connected::UNIVERSAL_NFT_STATE_VERSION);
+
+    let migrated: NFTInfo = fetch(&mut harness.context, nft_info).await;
+    assert_eq!(migrated.token_id, 7);
+    assert_eq!(migrated.name, "Legacy");
+    assert_eq!(migrated.uri, URI);
+    assert_eq!((migrated.owner, migrated.mint), (owner, mint));
+    assert!(migrated.is_burned);
+    let cross_chain_data = migrated.cross_chain_data.unwrap();
+    assert_eq!(cross_chain_data.destination_chain_id, DESTINATION_CHAIN_ID);
+    assert_eq!(cross_chain_data.recipient_address, EVM_RECIPIENT.to_vec());
+    assert_eq!(cross_chain_data.transfer_timestamp, 1_700_000_000);
+    assert_eq!(cross_chain_data.transfer_id, [0; 32]);
+    assert!(migrated.provenance.is_empty());
+    assert_eq!(migrated.version, connected::NFT_INFO_VERSION);
+}
@@ -0,0 +1,334 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::system_program;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use gateway::RevertOptions;
+
+declare_id!("3NM5owmxE4oQpcn6GaTqMi41q5VgXSu7K6HxeQYcgKU4");
+
+/// Largest deposit message recorded by the mock
+pub const MAX_MESSAGE_LEN: usize = 1280;
+/// Largest revert message recorded by the mock
+pub const MAX_REVERT_MESSAGE_LEN: usize = 512;
+
+/// Mock of the ZetaChain Gateway for localnet testing
+///
+/// Mirrors the deposit instructions the connected program calls, with the same account
+/// layouts, and records the latest deposit in the `meta` PDA instead of relaying it. The
+/// `execute` and `execute_revert` instructions stand in for ZetaChain delivering a call or
+/// a revert back to a connected program.
+#[program]
+pub mod mock_gateway {
+    use super::*;
+
+    /// Create the `meta` PDA that records deposits
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let meta = &mut ctx.accounts.pda;
+        meta.deposit_count = 0;
+        meta.executed_count = 0;
+        meta.last_deposit = None;
+        Ok(())
+    }
+
+    /// Whitelist an SPL mint for `deposit_spl_token_and_call`
+    pub fn whitelist_spl_mint(ctx: Context<WhitelistSplMint>) -> Result<()> {
+        ctx.accounts.whitelist_entry.mint = ctx.accounts.mint_account.key();
+        Ok(())
+    }
+
+    /// Deposit SOL into the `meta` PDA and record the call
+    pub fn deposit_and_call(
+        ctx: Context<Deposit>,
+        amount: u64,
+        receiver: [u8; 20],
+        message: Vec<u8>,
+        revert_options: Option<RevertOptions>,
+    ) -> Result<()> {
+        if amount > 0 {
+            let cpi_accounts = system_program::Transfer {
+                from: ctx.accounts.signer.to_account_info(),
+                to: ctx.accounts.pda.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?;
+        }
+
+        ctx.accounts.pda.record(RecordedDeposit {
+            signer: ctx.accounts.signer.key(),
+            amount,
+            mint: None,
+            receiver,
+            message,
+            revert_options,
+        })
+    }
+
+    /// Record a message-only call; no funds move
+    pub fn call(
+        ctx: Context<Call>,
+        receiver: [u8; 20],
+        message: Vec<u8>,
+        revert_options: Option<RevertOptions>,
+    ) -> Result<()> {
+        ctx.accounts.pda.record(RecordedDeposit {
+            signer: ctx.accounts.signer.key(),
+            amount: 0,
+            mint: None,
+            receiver,
+            message,
+            revert_options,
+        })
+    }
+
+    /// Record an SPL token deposit and call
+    ///
+    /// Token balances are not moved; tests assert on the recorded deposit instead.
+    pub fn deposit_spl_token_and_call(
+        ctx: Context<DepositSplToken>,
+        amount: u64,
+        receiver: [u8; 20],
+        message: Vec<u8>,
+        revert_options: Option<RevertOptions>,
+    ) -> Result<()> {
+        ctx.accounts.pda.record(RecordedDeposit {
+            signer: ctx.accounts.signer.key(),
+            amount,
+            mint: Some(ctx.accounts.mint_account.key()),
+            receiver,
+            message,
+            revert_options,
+        })
+    }
+
+    /// Deliver an inbound call to `destination`'s `on_call`
+    ///
+    /// The remaining accounts are passed through as the destination's `on_call` accounts.
+    /// As the Gateway does, `amount` lamports are first paid to the destination's PDA, its
+    /// first account.
+    pub fn execute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Execute<'info>>,
+        amount: u64,
+        sender: [u8; 20],
+        data: Vec<u8>,
+    ) -> Result<()> {
+        pay_destination_pda(&ctx.accounts.pda, ctx.remaining_accounts, amount)?;
+        invoke_destination(
+            &ctx.accounts.destination,
+            ctx.remaining_accounts,
+            "on_call",
+            (amount, sender, data),
+        )?;
+        ctx.accounts.pda.executed_count += 1;
+        Ok(())
+    }
+
+    /// Deliver a revert to `destination`'s `on_revert`
+    ///
+    /// The remaining accounts are passed through as the destination's `on_revert` accounts.
+    /// As the Gateway does, `amount` is first returned to the destination's PDA, its first
+    /// account; the mock only returns SOL.
+    pub fn execute_revert<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Execute<'info>>,
+        amount: u64,
+        sender: Pubkey,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        pay_destination_pda(&ctx.accounts.pda, ctx.remaining_accounts, amount)?;
+        invoke_destination(
+            &ctx.accounts.destination,
+            ctx.remaining_accounts,
+            "on_revert",
+            (amount, sender, data),
+        )?;
+        ctx.accounts.pda.executed_count += 1;
+        Ok(())
+    }
+}
+
+// Move `amount` lamports from `meta` to the destination program's PDA, the first passed-through account
+fn pay_destination_pda(meta: &Account<Meta>, accounts: &[AccountInfo], amount: u64) -> Result<()> {
+    if amount > 0 {
+        let destination_pda = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        meta.sub_lamports(amount)?;
+        destination_pda.add_lamports(amount)?;
+    }
+    Ok(())
+}
+
+// Invoke an Anchor instruction of `destination` with the given accounts and Borsh args
+fn invoke_destination<'info>(
+    destination: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    instruction_name: &str,
+    args: impl AnchorSerialize,
+) -> Result<()> {
+    let preimage = format!("global:{}", instruction_name);
+    let mut data = anchor_lang::solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8].to_vec();
+    args.serialize(&mut data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let instruction = Instruction {
+        program_id: destination.key(),
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    };
+    let mut account_infos = accounts.to_vec();
+    account_infos.push(destination.clone());
+    invoke(&instruction, &account_infos)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(init, payer = signer, space = 8 + Meta::SPACE, seeds = [b"meta"], bump)]
+    pub pda: Account<'info, Meta>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WhitelistSplMint<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub mint_account: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + WhitelistEntry::INIT_SPACE,
+        seeds = [b"whitelist", mint_account.key().as_ref()],
+        bump
+    )]
+    pub whitelist_entry: Account<'info, WhitelistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"meta"], bump)]
+    pub pda: Account<'info, Meta>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Same leading account as the Gateway's `call`; the `meta` PDA follows so the call is recorded
+#[derive(Accounts)]
+pub struct Call<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"meta"], bump)]
+    pub pda: Account<'info, Meta>,
+}
+
+#[derive(Accounts)]
+pub struct DepositSplToken<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"meta"], bump)]
+    pub pda: Account<'info, Meta>,
+
+    #[account(seeds = [b"whitelist", mint_account.key().as_ref()], bump)]
+    pub whitelist_entry: Account<'info, WhitelistEntry>,
+
+    pub mint_account: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(mut, constraint = from.mint == mint_account.key())]
+    pub from: Account<'info, TokenAccount>,
+
+    /// CHECK: Gateway token account; balances are not simulated
+    #[account(mut)]
+    pub to: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"meta"], bump)]
+    pub pda: Account<'info, Meta>,
+
+    /// CHECK: Program receiving the call
+    #[account(executable)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+/// Gateway PDA, recording the most recent deposit
+#[account]
+pub struct Meta {
+    pub deposit_count: u64,
+    pub executed_count: u64,
+    pub last_deposit: Option<RecordedDeposit>,
+}
+
+impl Meta {
+    pub const SPACE: usize = 8 + 8 + 1 + RecordedDeposit::SPACE;
+
+    fn record(&mut self, deposit: RecordedDeposit) -> Result<()> {
+        require!(deposit.message.len() <= MAX_MESSAGE_LEN, MockGatewayError::MessageTooLong);
+        let revert_message_len = deposit
+            .revert_options
+            .as_ref()
+            .map_or(0, |options| options.revert_message.len());
+        require!(
+            revert_message_len <= MAX_REVERT_MESSAGE_LEN,
+            MockGatewayError::MessageTooLong
+        );
+        self.deposit_count += 1;
+        self.last_deposit = Some(deposit);
+        Ok(())
+    }
+}
+
+/// A deposit as received by the mock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RecordedDeposit {
+    pub signer: Pubkey,
+    /// Lamports for `deposit_and_call`, token units for `deposit_spl_token_and_call`
+    pub amount: u64,
+    /// SPL mint of the deposit, `None` for SOL
+    pub mint: Option<Pubkey>,
+    pub receiver: [u8; 20],
+    pub message: Vec<u8>,
+    pub revert_options: Option<RevertOptions>,
+}
+
+impl RecordedDeposit {
+    // RevertOptions: revert_address, abort_address, call_on_revert, revert_message, on_revert_gas_limit
+    const REVERT_OPTIONS_SPACE: usize = 32 + 20 + 1 + (4 + MAX_REVERT_MESSAGE_LEN) + 8;
+    pub const SPACE: usize =
+        32 + 8 + (1 + 32) + 20 + (4 + MAX_MESSAGE_LEN) + (1 + Self::REVERT_OPTIONS_SPACE);
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct WhitelistEntry {
+    pub mint: Pubkey,
+}
+
+#[error_code]
+pub enum MockGatewayError {
+    #[msg("Message exceeds the mock's recording capacity")]
+    MessageTooLong,
+}
@@ -0,0 +1,264 @@
+mod zeta;
+
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::{AccountDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use connected::{address, Config, PendingTransfer, TransferStatus};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::zeta::{Cctx, CctxStatus, ZetaClient};
+
+// Transactions touching a pending transfer searched for its Gateway call
+const INBOUND_SIGNATURE_LIMIT: usize = 20;
+
+/// Reconcile outbound Universal NFT transfers with their ZetaChain CCTX
+///
+/// Every outbound `CrossChainTransferEvent` leaves a PendingTransfer account until the transfer
+/// is confirmed, reverted or reclaimed. The relayer looks each one up on ZetaChain by the Solana
+/// transaction that made its Gateway call and flags those that have neither confirmed nor
+/// reverted within the deadline. With `--crank` it also dispatches transfers whose Gateway call
+/// never went out, and confirms delivered transfers when run with the program authority.
+#[derive(Parser)]
+#[command(name = "universal-nft-relayer", version)]
+struct Cli {
+    /// Solana RPC URL
+    #[arg(short, long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// ZetaChain node REST (LCD) endpoint
+    #[arg(long)]
+    zeta_api: String,
+
+    /// Slots after which an unresolved transfer is flagged [default: the config's transfer timeout]
+    #[arg(long)]
+    deadline_slots: Option<u64>,
+
+    /// Send `retry_dispatch` and `confirm_transfer` instead of only reporting
+    #[arg(long)]
+    crank: bool,
+
+    /// Keypair paying for cranks; `confirm_transfer` also needs it to be the program authority
+    #[arg(short, long)]
+    keypair: Option<PathBuf>,
+
+    /// Seconds between scans
+    #[arg(long, default_value_t = 30)]
+    poll_interval: u64,
+
+    /// Scan once and exit
+    #[arg(long)]
+    once: bool,
+}
+
+struct Relayer {
+    rpc: RpcClient,
+    zeta: ZetaClient,
+    deadline_slots: Option<u64>,
+    cranker: Option<Keypair>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let cranker = match (cli.crank, cli.keypair) {
+        (true, Some(path)) => Some(
+            read_keypair_file(&path).map_err(|err| anyhow!("failed to read keypair {}: {}", path.display(), err))?,
+        ),
+        (true, None) => return Err(anyhow!("--crank needs --keypair")),
+        (false, _) => None,
+    };
+    let relayer = Relayer {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        zeta: ZetaClient::new(&cli.zeta_api),
+        deadline_slots: cli.deadline_slots,
+        cranker,
+    };
+
+    loop {
+        if let Err(err) = relayer.scan() {
+            eprintln!("scan failed: {:#}", err);
+        }
+        if cli.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(cli.poll_interval));
+    }
+}
+
+impl Relayer {
+    fn scan(&self) -> Result<()> {
+        let config: Config = fetch(&self.rpc, &address::config())?;
+        let deadline_slots = self.deadline_slots.unwrap_or(config.transfer_timeout_slots);
+        let current_slot = self.rpc.get_slot()?;
+
+        for (pending_address, pending) in self.pending_transfers()? {
+            // Reverted transfers are settled on Solana and wait for their owner to reclaim
+            if pending.status != TransferStatus::Initiated {
+                continue;
+            }
+            let overdue = current_slot >= pending.initiated_slot.saturating_add(deadline_slots);
+
+            // Only queued transfers await dispatch; their escrow pays the Gateway deposit
+            if !pending.dispatched {
+                if self.cranker.is_some() && pending.gas_escrow > 0 {
+                    if let Err(err) = self.retry_dispatch(&config, &pending) {
+                        report(&pending, &format!("dispatch failed: {:#}", err));
+                    }
+                } else if overdue {
+                    flag(&pending, "Gateway call never dispatched");
+                }
+                continue;
+            }
+
+            match self.find_cctx(&pending_address)? {
+                Some(cctx) if cctx.status == CctxStatus::Mined => {
+                    if self.can_confirm()? {
+                        if let Err(err) = self.confirm_transfer(&pending) {
+                            report(&pending, &format!("confirmation failed: {:#}", err));
+                        }
+                    } else if overdue {
+                        flag(&pending, &format!("delivered by CCTX {} but not confirmed", cctx.index));
+                    }
+                }
+                Some(cctx) if overdue => flag(
+                    &pending,
+                    &format!("CCTX {} is {:?}: {}", cctx.index, cctx.status, cctx.status_message),
+                ),
+                None if overdue => flag(&pending, "no CCTX observed on ZetaChain"),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn pending_transfers(&self) -> Result<Vec<(Pubkey, PendingTransfer)>> {
+        let accounts = self.rpc.get_program_accounts_with_config(
+            &connected::ID,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    0,
+                    PendingTransfer::DISCRIMINATOR,
+                ))]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, account)| {
+                let pending = PendingTransfer::try_deserialize(&mut account.data.as_slice()).ok()?;
+                Some((address, pending))
+            })
+            .collect())
+    }
+
+    // The CCTX of the newest transaction touching the pending transfer that ZetaChain has seen
+    fn find_cctx(&self, pending_address: &Pubkey) -> Result<Option<Cctx>> {
+        let signatures = self.rpc.get_signatures_for_address_with_config(
+            pending_address,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(INBOUND_SIGNATURE_LIMIT),
+                commitment: Some(self.rpc.commitment()),
+                ..Default::default()
+            },
+        )?;
+        for status in signatures.iter().filter(|status| status.err.is_none()) {
+            if let Some(cctx) = self.zeta.cctx_for_inbound(&status.signature)? {
+                return Ok(Some(cctx));
+            }
+        }
+        Ok(None)
+    }
+
+    fn can_confirm(&self) -> Result<bool> {
+        let Some(cranker) = &self.cranker else {
+            return Ok(false);
+        };
+        let state: connected::UniversalNFTState = fetch(&self.rpc, &address::universal_nft_state())?;
+        Ok(state.authority == cranker.pubkey())
+    }
+
+    fn retry_dispatch(&self, config: &Config, pending: &PendingTransfer) -> Result<()> {
+        let cranker = self.cranker.as_ref().expect("cranking requires a keypair");
+        let ix = connected_ix(
+            connected::accounts::RetryDispatch {
+                cranker: cranker.pubkey(),
+                universal_nft_state: address::universal_nft_state(),
+                config: address::config(),
+                pending_transfer: address::pending_transfer(&pending.transfer_id),
+                gateway_pda: Pubkey::find_program_address(&[b"meta"], &config.gateway_program).0,
+                gateway_program: config.gateway_program,
+                system_program: solana_sdk::system_program::ID,
+            },
+            connected::instruction::RetryDispatch { transfer_id: pending.transfer_id },
+        );
+        let signature = send(&self.rpc, cranker, ix)?;
+        report(pending, &format!("dispatched: {}", signature));
+        Ok(())
+    }
+
+    fn confirm_transfer(&self, pending: &PendingTransfer) -> Result<()> {
+        let authority = self.cranker.as_ref().expect("confirming requires a keypair");
+        let ix = connected_ix(
+            connected::accounts::ConfirmTransfer {
+                authority: authority.pubkey(),
+                universal_nft_state: address::universal_nft_state(),
+                pending_transfer: address::pending_transfer(&pending.transfer_id),
+                receipt: address::receipt(&pending.owner, &pending.transfer_id),
+                transfer_record: address::transfer_record(&pending.transfer_id),
+                owner: pending.owner,
+            },
+            connected::instruction::ConfirmTransfer { transfer_id: pending.transfer_id },
+        );
+        let signature = send(&self.rpc, authority, ix)?;
+        report(pending, &format!("confirmed: {}", signature));
+        Ok(())
+    }
+}
+
+fn flag(pending: &PendingTransfer, reason: &str) {
+    report(pending, &format!("STUCK {}", reason));
+}
+
+fn report(pending: &PendingTransfer, message: &str) {
+    println!(
+        "token {} transfer 0x{} to chain {}: {}",
+        pending.token_id,
+        hex::encode(pending.transfer_id),
+        pending.destination_chain_id,
+        message
+    );
+}
+
+fn connected_ix(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: connected::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+fn send(rpc: &RpcClient, payer: &Keypair, instruction: Instruction) -> Result<Signature> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&payer.pubkey()), &[payer], blockhash);
+    Ok(rpc.send_and_confirm_transaction(&transaction)?)
+}
+
+fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let data = rpc.get_account_data(address)?;
+    Ok(T::try_deserialize(&mut data.as_slice())?)
+}
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+/// Where a cross-chain transaction stands on ZetaChain
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CctxStatus {
+    Pending,
+    /// Delivered on the destination chain
+    Mined,
+    /// Reverted back to Solana; the Gateway calls `on_revert`
+    Reverted,
+    /// Failed to deliver or revert
+    Aborted,
+}
+
+pub struct Cctx {
+    pub index: String,
+    pub status: CctxStatus,
+    pub status_message: String,
+}
+
+/// Client of a ZetaChain node's REST (LCD) API
+pub struct ZetaClient {
+    http: reqwest::blocking::Client,
+    api_url: String,
+}
+
+#[derive(Deserialize)]
+struct InboundHashToCctxData {
+    #[serde(rename = "CrossChainTxs", default)]
+    cross_chain_txs: Vec<CrossChainTx>,
+}
+
+#[derive(Deserialize)]
+struct CrossChainTx {
+    index: String,
+    cctx_status: CrossChainTxStatus,
+}
+
+#[derive(Deserialize)]
+struct CrossChainTxStatus {
+    status: String,
+    #[serde(default)]
+    status_message: String,
+}
+
+impl ZetaClient {
+    pub fn new(api_url: &str) -> Self {
+        Self {
+            http: reqwest::blocking::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// The CCTX created for an inbound Solana transaction, if ZetaChain has observed it
+    pub fn cctx_for_inbound(&self, inbound_hash: &str) -> Result<Option<Cctx>> {
+        let url = format!("{}/zeta-chain/crosschain/inboundHashToCctxData/{}", self.api_url, inbound_hash);
+        let response = self.http.get(url).send()?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let data: InboundHashToCctxData = response.error_for_status()?.json()?;
+        Ok(data.cross_chain_txs.into_iter().next().map(|cctx| Cctx {
+            index: cctx.index,
+            status: match cctx.cctx_status.status.as_str() {
+                "OutboundMined" => CctxStatus::Mined,
+                "Reverted" => CctxStatus::Reverted,
+                "Aborted" => CctxStatus::Aborted,
+                _ => CctxStatus::Pending,
+            },
+            status_message: cctx.cctx_status.status_message,
+        }))
+    }
+}
@@ -121,7 +121,8 @@ export class SolanaNFTSetup {
           to
         )
         .accounts({
-          signer: this.config.authority.publicKey,
+          payer: this.config.authority.publicKey,
+          authority: this.config.authority.publicKey,
           universalNftState: universalNFTState,
           mint: mint,
           tokenAccount: tokenAccount.address,